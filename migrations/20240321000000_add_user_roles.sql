@@ -0,0 +1,4 @@
+-- 角色：admin / librarian / member，早期注册的 'user' 统一迁移为 'member'
+UPDATE users SET role = 'member' WHERE role = 'user';
+
+ALTER TABLE users MODIFY role VARCHAR(50) NOT NULL DEFAULT 'member';
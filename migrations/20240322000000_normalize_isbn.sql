@@ -0,0 +1,97 @@
+-- 规范化已有 ISBN：去掉连字符和空格后校验，ISBN-10 转换为 978 前缀的 ISBN-13，isbn 列只保存 13 位数字。
+-- 无法解析、校验位错误，或规范化后与另一本图书重复的值保持原样，记录到 isbn_migration_issues 供人工处理，不中断迁移
+CREATE TABLE isbn_migration_issues (
+    book_id VARCHAR(36) PRIMARY KEY,
+    isbn VARCHAR(13) NOT NULL,
+    issue VARCHAR(16) NOT NULL, -- invalid：无法解析或校验位错误；duplicate：与 duplicate_of 规范化后相同
+    normalized VARCHAR(13),
+    duplicate_of VARCHAR(36),
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE isbn_normalization (
+    book_id VARCHAR(36) PRIMARY KEY,
+    original VARCHAR(13) NOT NULL,
+    stripped VARCHAR(13) NOT NULL,
+    normalized VARCHAR(13)
+);
+
+INSERT INTO isbn_normalization (book_id, original, stripped)
+SELECT id, isbn, UPPER(REPLACE(REPLACE(TRIM(isbn), '-', ''), ' ', '')) FROM books;
+
+-- ISBN-10：加权和 10×d1 + 9×d2 + … + 1×d10 能被 11 整除，末位 X 表示 10
+UPDATE isbn_normalization
+SET normalized = CONCAT(
+    '978',
+    LEFT(stripped, 9),
+    MOD(10 - MOD(
+        38
+        + 3 * SUBSTRING(stripped, 1, 1) + SUBSTRING(stripped, 2, 1)
+        + 3 * SUBSTRING(stripped, 3, 1) + SUBSTRING(stripped, 4, 1)
+        + 3 * SUBSTRING(stripped, 5, 1) + SUBSTRING(stripped, 6, 1)
+        + 3 * SUBSTRING(stripped, 7, 1) + SUBSTRING(stripped, 8, 1)
+        + 3 * SUBSTRING(stripped, 9, 1),
+        10), 10)
+)
+WHERE stripped REGEXP '^[0-9]{9}[0-9X]$'
+  AND MOD(
+        10 * SUBSTRING(stripped, 1, 1) + 9 * SUBSTRING(stripped, 2, 1)
+        + 8 * SUBSTRING(stripped, 3, 1) + 7 * SUBSTRING(stripped, 4, 1)
+        + 6 * SUBSTRING(stripped, 5, 1) + 5 * SUBSTRING(stripped, 6, 1)
+        + 4 * SUBSTRING(stripped, 7, 1) + 3 * SUBSTRING(stripped, 8, 1)
+        + 2 * SUBSTRING(stripped, 9, 1)
+        + IF(RIGHT(stripped, 1) = 'X', 10, CAST(RIGHT(stripped, 1) AS UNSIGNED)),
+        11) = 0;
+
+-- ISBN-13：978/979 前缀，奇数位权重 1、偶数位权重 3，加权和能被 10 整除
+UPDATE isbn_normalization
+SET normalized = stripped
+WHERE stripped REGEXP '^97[89][0-9]{10}$'
+  AND MOD(
+        SUBSTRING(stripped, 1, 1) + 3 * SUBSTRING(stripped, 2, 1)
+        + SUBSTRING(stripped, 3, 1) + 3 * SUBSTRING(stripped, 4, 1)
+        + SUBSTRING(stripped, 5, 1) + 3 * SUBSTRING(stripped, 6, 1)
+        + SUBSTRING(stripped, 7, 1) + 3 * SUBSTRING(stripped, 8, 1)
+        + SUBSTRING(stripped, 9, 1) + 3 * SUBSTRING(stripped, 10, 1)
+        + SUBSTRING(stripped, 11, 1) + 3 * SUBSTRING(stripped, 12, 1)
+        + SUBSTRING(stripped, 13, 1),
+        10) = 0;
+
+-- 规范化后相同的图书只转换一本：已是规范形式的优先，其次是最早创建的
+CREATE TABLE isbn_normalization_keepers (
+    book_id VARCHAR(36) PRIMARY KEY,
+    normalized VARCHAR(13) NOT NULL,
+    keeper_id VARCHAR(36) NOT NULL
+);
+
+INSERT INTO isbn_normalization_keepers (book_id, normalized, keeper_id)
+SELECT
+    n.book_id,
+    n.normalized,
+    FIRST_VALUE(n.book_id) OVER (
+        PARTITION BY n.normalized
+        ORDER BY n.original = n.normalized DESC, b.created_at, n.book_id
+    ) AS keeper_id
+FROM isbn_normalization n
+JOIN books b ON b.id = n.book_id
+WHERE n.normalized IS NOT NULL;
+
+INSERT INTO isbn_migration_issues (book_id, isbn, issue, normalized, duplicate_of)
+SELECT book_id, original, 'invalid', NULL, NULL
+FROM isbn_normalization
+WHERE normalized IS NULL;
+
+INSERT INTO isbn_migration_issues (book_id, isbn, issue, normalized, duplicate_of)
+SELECT k.book_id, n.original, 'duplicate', k.normalized, k.keeper_id
+FROM isbn_normalization_keepers k
+JOIN isbn_normalization n ON n.book_id = k.book_id
+WHERE k.book_id <> k.keeper_id;
+
+-- 每个规范值只写入保留的那本；已有图书持有该值时保留的就是它本身，无需更新，因此不会触发唯一索引冲突
+UPDATE books b
+JOIN isbn_normalization_keepers k ON k.book_id = b.id
+SET b.isbn = k.normalized
+WHERE k.book_id = k.keeper_id AND b.isbn <> k.normalized;
+
+DROP TABLE isbn_normalization_keepers;
+DROP TABLE isbn_normalization;
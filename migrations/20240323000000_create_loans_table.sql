@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS loans (
+    id CHAR(36) PRIMARY KEY,
+    book_id VARCHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    checkout_at DATETIME NOT NULL,
+    due_at DATETIME NOT NULL,
+    returned_at DATETIME NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_loans_user (user_id, returned_at),
+    INDEX idx_loans_book (book_id, returned_at),
+    FOREIGN KEY (book_id) REFERENCES books(id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
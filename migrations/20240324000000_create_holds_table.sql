@@ -0,0 +1,15 @@
+-- 预约队列：waiting（排队中）/ ready（已留书待取）/ fulfilled（已借出）/ cancelled / expired
+CREATE TABLE IF NOT EXISTS holds (
+    id CHAR(36) PRIMARY KEY,
+    book_id VARCHAR(36) NOT NULL,
+    user_id CHAR(36) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'waiting',
+    ready_at DATETIME NULL,
+    expires_at DATETIME NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_holds_book_status (book_id, status, created_at),
+    INDEX idx_holds_user_status (user_id, status),
+    FOREIGN KEY (book_id) REFERENCES books(id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
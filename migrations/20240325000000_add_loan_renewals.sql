@@ -0,0 +1,2 @@
+-- 续借次数，每次续借后加一
+ALTER TABLE loans ADD COLUMN renewal_count INT NOT NULL DEFAULT 0 AFTER returned_at;
@@ -0,0 +1,16 @@
+-- 罚款流水：charge（罚款，正数）/ payment（缴费，负数）/ waiver（减免，负数），金额单位为分
+CREATE TABLE IF NOT EXISTS fine_transactions (
+    id CHAR(36) PRIMARY KEY,
+    user_id CHAR(36) NOT NULL,
+    loan_id CHAR(36) NULL,
+    kind VARCHAR(20) NOT NULL,
+    amount_cents BIGINT NOT NULL,
+    note VARCHAR(500) NULL,
+    recorded_by CHAR(36) NULL,
+    created_at DATETIME NOT NULL,
+    INDEX idx_fines_user (user_id, created_at),
+    INDEX idx_fines_loan (loan_id, kind),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (loan_id) REFERENCES loans(id),
+    FOREIGN KEY (recorded_by) REFERENCES users(id)
+);
@@ -0,0 +1,6 @@
+-- 手动停借：blocked 为停借状态，block_note / block_updated_by / block_updated_at 记录最近一次停借或解除的原因、操作人和时间
+ALTER TABLE users
+    ADD COLUMN blocked BOOLEAN NOT NULL DEFAULT FALSE AFTER role,
+    ADD COLUMN block_note VARCHAR(500) NULL AFTER blocked,
+    ADD COLUMN block_updated_by CHAR(36) NULL AFTER block_note,
+    ADD COLUMN block_updated_at DATETIME NULL AFTER block_updated_by;
@@ -0,0 +1,22 @@
+-- 读者类别，用于匹配流通规则
+ALTER TABLE users ADD COLUMN patron_category VARCHAR(50) NOT NULL DEFAULT 'general' AFTER role;
+
+-- 流通规则：按 (读者类别, 图书类型) 匹配，'*' 表示任意；都不匹配时使用配置文件中的默认值
+CREATE TABLE IF NOT EXISTS circulation_policies (
+    id CHAR(36) PRIMARY KEY,
+    patron_category VARCHAR(50) NOT NULL DEFAULT '*',
+    book_type VARCHAR(50) NOT NULL DEFAULT '*',
+    loan_period_hours INT NOT NULL,
+    max_renewals INT NOT NULL,
+    fine_rate_cents INT NOT NULL,
+    max_loans INT NOT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    UNIQUE KEY uk_policies_key (patron_category, book_type)
+);
+
+-- 借书时确定的借阅条件，续借和计罚都以此为准；早期借阅为 NULL，使用配置默认值
+ALTER TABLE loans
+    ADD COLUMN loan_period_hours INT NULL AFTER renewal_count,
+    ADD COLUMN max_renewals INT NULL AFTER loan_period_hours,
+    ADD COLUMN fine_rate_cents INT NULL AFTER max_renewals;
@@ -0,0 +1,46 @@
+-- 单册：每本图书下的实体副本，可借数量由单册状态计算
+CREATE TABLE IF NOT EXISTS items (
+    id CHAR(36) PRIMARY KEY,
+    book_id VARCHAR(36) NOT NULL,
+    barcode VARCHAR(64) NOT NULL UNIQUE,
+    status VARCHAR(20) NOT NULL DEFAULT 'available',
+    acquired_at DATE NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_items_book_status (book_id, status),
+    FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+);
+
+ALTER TABLE loans
+    ADD COLUMN item_id CHAR(36) NULL AFTER book_id,
+    ADD INDEX idx_loans_item (item_id, returned_at),
+    ADD FOREIGN KEY (item_id) REFERENCES items(id);
+
+-- 按原有 quantity 为每本书生成占位单册，条码为 P + 图书 ID（去掉连字符）+ 至少四位的序号。
+-- 递归深度按实际最大馆藏数设置，不设固定上限
+SET @max_quantity = (SELECT COALESCE(MAX(quantity), 0) FROM books);
+SET SESSION cte_max_recursion_depth = GREATEST(@max_quantity + 1, 1000);
+
+INSERT INTO items (id, book_id, barcode, status, acquired_at, created_at, updated_at)
+WITH RECURSIVE seq (n) AS (
+    SELECT 1
+    UNION ALL
+    SELECT n + 1 FROM seq WHERE n < @max_quantity
+)
+SELECT UUID(), b.id, CONCAT('P', REPLACE(b.id, '-', ''), '-', LPAD(seq.n, GREATEST(CHAR_LENGTH(seq.n), 4), '0')),
+       'available', DATE(b.created_at), NOW(), NOW()
+FROM books b JOIN seq ON seq.n <= b.quantity;
+
+-- 未归还的借阅按借出顺序依次对应到占位单册
+UPDATE loans l
+JOIN (
+    SELECT id, book_id, ROW_NUMBER() OVER (PARTITION BY book_id ORDER BY checkout_at, id) AS n
+    FROM loans WHERE returned_at IS NULL
+) active ON active.id = l.id
+JOIN items i ON i.barcode = CONCAT('P', REPLACE(active.book_id, '-', ''), '-', LPAD(active.n, GREATEST(CHAR_LENGTH(active.n), 4), '0'))
+SET l.item_id = i.id;
+
+UPDATE items i JOIN loans l ON l.item_id = i.id AND l.returned_at IS NULL
+SET i.status = 'on_loan';
+
+ALTER TABLE books DROP COLUMN quantity;
@@ -0,0 +1,56 @@
+-- 分馆；已有单册全部归入迁移时创建的总馆
+CREATE TABLE IF NOT EXISTS branches (
+    id CHAR(36) PRIMARY KEY,
+    code VARCHAR(20) NOT NULL UNIQUE,
+    name VARCHAR(255) NOT NULL,
+    address VARCHAR(500) NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL
+);
+
+INSERT INTO branches (id, code, name, address, created_at, updated_at)
+VALUES (UUID(), 'MAIN', '总馆', NULL, NOW(), NOW());
+
+-- home_branch_id 为所属分馆，current_branch_id 为当前所在分馆（调拨后改变）
+ALTER TABLE items
+    ADD COLUMN home_branch_id CHAR(36) NULL AFTER book_id,
+    ADD COLUMN current_branch_id CHAR(36) NULL AFTER home_branch_id;
+
+UPDATE items
+SET home_branch_id = (SELECT id FROM branches WHERE code = 'MAIN'),
+    current_branch_id = (SELECT id FROM branches WHERE code = 'MAIN');
+
+ALTER TABLE items
+    MODIFY home_branch_id CHAR(36) NOT NULL,
+    MODIFY current_branch_id CHAR(36) NOT NULL,
+    ADD INDEX idx_items_branch_status (current_branch_id, status),
+    ADD FOREIGN KEY (home_branch_id) REFERENCES branches(id),
+    ADD FOREIGN KEY (current_branch_id) REFERENCES branches(id);
+
+-- 馆际调拨：requested（已申请）/ in_transit（运送中）/ received（已到馆）/ cancelled
+CREATE TABLE IF NOT EXISTS transfers (
+    id CHAR(36) PRIMARY KEY,
+    book_id VARCHAR(36) NOT NULL,
+    item_id CHAR(36) NULL,
+    from_branch_id CHAR(36) NULL,
+    to_branch_id CHAR(36) NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'requested',
+    user_id CHAR(36) NULL,
+    requested_by CHAR(36) NOT NULL,
+    shipped_at DATETIME NULL,
+    received_at DATETIME NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_transfers_status (status, to_branch_id),
+    FOREIGN KEY (book_id) REFERENCES books(id),
+    FOREIGN KEY (item_id) REFERENCES items(id),
+    FOREIGN KEY (from_branch_id) REFERENCES branches(id),
+    FOREIGN KEY (to_branch_id) REFERENCES branches(id),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (requested_by) REFERENCES users(id)
+);
+
+-- 调拨到馆后为申请读者留书时记录留出的单册，其他读者借书或调拨时跳过该单册
+ALTER TABLE holds
+    ADD COLUMN item_id CHAR(36) NULL AFTER user_id,
+    ADD FOREIGN KEY (item_id) REFERENCES items(id) ON DELETE SET NULL;
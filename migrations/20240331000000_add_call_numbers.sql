@@ -0,0 +1,9 @@
+-- 索书号（中图法或杜威分类号 + 书次号）；call_number_sort 为应用计算的排架排序键，按字节比较
+ALTER TABLE books
+    ADD COLUMN call_number VARCHAR(64) NULL AFTER type,
+    ADD COLUMN call_number_sort VARCHAR(255) CHARACTER SET ascii COLLATE ascii_bin NULL AFTER call_number,
+    ADD INDEX idx_books_call_number_sort (call_number_sort);
+
+-- 单册的排架位置，如 3F-A12
+ALTER TABLE items
+    ADD COLUMN shelf_location VARCHAR(100) NULL AFTER barcode;
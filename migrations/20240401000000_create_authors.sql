@@ -0,0 +1,61 @@
+-- 作者；同名作者可以是不同的人，通过 id 区分
+CREATE TABLE IF NOT EXISTS authors (
+    id CHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    bio TEXT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_authors_name (name)
+);
+
+-- 图书与作者的多对多关联：role 为 author / translator / editor / illustrator，position 为排列顺序
+CREATE TABLE IF NOT EXISTS book_authors (
+    book_id VARCHAR(36) NOT NULL,
+    author_id CHAR(36) NOT NULL,
+    role VARCHAR(20) NOT NULL DEFAULT 'author',
+    position INT NOT NULL,
+    PRIMARY KEY (book_id, author_id, role),
+    INDEX idx_book_authors_author (author_id),
+    FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE,
+    FOREIGN KEY (author_id) REFERENCES authors(id)
+);
+
+-- 按 "、"、","、"，"、" and " 拆分已有的作者字符串，同名作者只创建一条
+CREATE TABLE author_names (
+    book_id VARCHAR(36) NOT NULL,
+    position INT NOT NULL,
+    name VARCHAR(255) NOT NULL
+);
+
+INSERT INTO author_names (book_id, position, name)
+WITH RECURSIVE parts (book_id, n, name, rest) AS (
+    SELECT id,
+           1,
+           SUBSTRING_INDEX(normalized, '、', 1),
+           IF(LOCATE('、', normalized) > 0, SUBSTRING(normalized, LOCATE('、', normalized) + 1), NULL)
+    FROM (
+        SELECT id, REPLACE(REPLACE(REPLACE(author, ' and ', '、'), ',', '、'), '，', '、') AS normalized
+        FROM books
+    ) b
+    UNION ALL
+    SELECT book_id,
+           n + 1,
+           SUBSTRING_INDEX(rest, '、', 1),
+           IF(LOCATE('、', rest) > 0, SUBSTRING(rest, LOCATE('、', rest) + 1), NULL)
+    FROM parts
+    WHERE rest IS NOT NULL
+)
+SELECT book_id, ROW_NUMBER() OVER (PARTITION BY book_id ORDER BY MIN(n)), TRIM(name)
+FROM parts
+WHERE TRIM(name) <> ''
+GROUP BY book_id, TRIM(name);
+
+INSERT INTO authors (id, name, bio, created_at, updated_at)
+SELECT UUID(), name, NULL, NOW(), NOW()
+FROM (SELECT DISTINCT name FROM author_names) names;
+
+INSERT INTO book_authors (book_id, author_id, role, position)
+SELECT an.book_id, a.id, 'author', an.position
+FROM author_names an JOIN authors a ON a.name = an.name;
+
+DROP TABLE author_names;
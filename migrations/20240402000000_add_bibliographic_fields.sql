@@ -0,0 +1,11 @@
+-- 编目信息：副题名、原题名、出版者、出版年、版本说明、语种（ISO 639 代码）和页数
+ALTER TABLE books
+    ADD COLUMN subtitle VARCHAR(255) NULL AFTER title,
+    ADD COLUMN original_title VARCHAR(255) NULL AFTER subtitle,
+    ADD COLUMN publisher VARCHAR(255) NULL AFTER type,
+    ADD COLUMN publication_year INT NULL AFTER publisher,
+    ADD COLUMN edition VARCHAR(100) NULL AFTER publication_year,
+    ADD COLUMN language VARCHAR(3) NULL AFTER edition,
+    ADD COLUMN pages INT NULL AFTER language,
+    ADD INDEX idx_books_publication_year (publication_year),
+    ADD INDEX idx_books_language (language);
@@ -0,0 +1,30 @@
+-- 主题分类树；parent_id 为空表示顶级分类，仍有子分类的分类不能删除
+CREATE TABLE IF NOT EXISTS categories (
+    id CHAR(36) PRIMARY KEY,
+    parent_id CHAR(36) NULL,
+    name VARCHAR(100) NOT NULL,
+    description TEXT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_categories_parent (parent_id),
+    FOREIGN KEY (parent_id) REFERENCES categories(id)
+);
+
+-- 图书与分类的多对多关联；仍有图书的分类不能删除
+CREATE TABLE IF NOT EXISTS book_categories (
+    book_id VARCHAR(36) NOT NULL,
+    category_id CHAR(36) NOT NULL,
+    PRIMARY KEY (book_id, category_id),
+    INDEX idx_book_categories_category (category_id),
+    FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE,
+    FOREIGN KEY (category_id) REFERENCES categories(id)
+);
+
+-- 已有的 type 取值迁移为顶级分类，图书归入对应分类
+INSERT INTO categories (id, parent_id, name, description, created_at, updated_at)
+SELECT UUID(), NULL, type, NULL, NOW(), NOW()
+FROM (SELECT DISTINCT type FROM books) t;
+
+INSERT INTO book_categories (book_id, category_id)
+SELECT b.id, c.id
+FROM books b JOIN categories c ON c.name = b.type AND c.parent_id IS NULL;
@@ -0,0 +1,30 @@
+-- 作品：把同一作品的不同版本（再版、重印、译本）归为一组
+CREATE TABLE IF NOT EXISTS works (
+    id CHAR(36) PRIMARY KEY,
+    title VARCHAR(255) NOT NULL,
+    original_title VARCHAR(255) NULL,
+    description TEXT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_works_title (title)
+);
+
+-- 丛书
+CREATE TABLE IF NOT EXISTS series (
+    id CHAR(36) PRIMARY KEY,
+    name VARCHAR(255) NOT NULL,
+    description TEXT NULL,
+    created_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL,
+    INDEX idx_series_name (name)
+);
+
+-- 未归入作品的图书视为独立作品；同一卷次可以有多个版本
+ALTER TABLE books
+    ADD COLUMN work_id CHAR(36) NULL AFTER call_number_sort,
+    ADD COLUMN series_id CHAR(36) NULL AFTER work_id,
+    ADD COLUMN series_volume INT NULL AFTER series_id,
+    ADD INDEX idx_books_work (work_id),
+    ADD INDEX idx_books_series (series_id, series_volume),
+    ADD CONSTRAINT fk_books_work FOREIGN KEY (work_id) REFERENCES works(id),
+    ADD CONSTRAINT fk_books_series FOREIGN KEY (series_id) REFERENCES series(id);
@@ -0,0 +1,13 @@
+-- 图书封面：每本书最多一张，storage_key 为该版本原图和缩略图所在的存储目录
+CREATE TABLE IF NOT EXISTS book_covers (
+    book_id VARCHAR(36) PRIMARY KEY,
+    storage_key VARCHAR(255) NOT NULL,
+    content_type VARCHAR(50) NOT NULL,
+    width INT NOT NULL,
+    height INT NOT NULL,
+    url VARCHAR(1024) NOT NULL,
+    small_url VARCHAR(1024) NOT NULL,
+    medium_url VARCHAR(1024) NOT NULL,
+    updated_at DATETIME NOT NULL,
+    FOREIGN KEY (book_id) REFERENCES books(id) ON DELETE CASCADE
+);
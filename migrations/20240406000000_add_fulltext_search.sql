@@ -0,0 +1,4 @@
+-- 全文检索索引，使用 ngram 分词以支持中文；题名、责任者单独建索引用于加权计算相关度
+ALTER TABLE books ADD FULLTEXT INDEX ft_books_title (title, subtitle) WITH PARSER ngram;
+ALTER TABLE books ADD FULLTEXT INDEX ft_books_author (author) WITH PARSER ngram;
+ALTER TABLE books ADD FULLTEXT INDEX ft_books_text (title, subtitle, author, description) WITH PARSER ngram;
@@ -1 +1,307 @@
-pub mod database; 
\ No newline at end of file
+pub mod database;
+
+use serde::Deserialize;
+use std::{env, fmt, fs, path::Path};
+
+const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+#[derive(Debug, Clone)]
+pub struct AppConfig {
+    pub database_url: String,
+    pub test_database_url: Option<String>,
+    pub pool_size: u32,
+    pub jwt_secret: String,
+    pub jwt_expiry_hours: i64,
+    pub host: String,
+    pub port: u16,
+    pub log_level: String,
+    pub admin: Option<AdminBootstrap>,
+    pub loan_period_days: i64,
+    pub hold_pickup_days: i64,
+    pub max_renewals: i32,
+    pub renewal_grace_days: i64,
+    pub fine_daily_rate_cents: i64,
+    pub fine_max_per_item_cents: i64,
+    pub max_owed_cents: i64,
+    pub max_overdue_items: i64,
+    pub max_concurrent_loans: i64,
+    /// 本地存储目录，上传的封面保存在其中
+    pub storage_dir: String,
+    /// 本地存储对外访问的 URL 前缀
+    pub storage_base_url: String,
+    pub cover_max_bytes: usize,
+    /// 搜索索引目录，仅在启用 search-index 特性时使用
+    pub search_index_dir: String,
+    /// 输入补全索引的刷新间隔（秒）
+    pub suggest_refresh_seconds: u64,
+}
+
+/// 首个管理员账号，仅在系统中尚无管理员时使用
+#[derive(Debug, Clone, Deserialize)]
+pub struct AdminBootstrap {
+    pub username: String,
+    pub email: String,
+    pub password: String,
+}
+
+/// TOML 配置文件，所有字段可选，环境变量优先级更高
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    database_url: Option<String>,
+    test_database_url: Option<String>,
+    pool_size: Option<u32>,
+    jwt_secret: Option<String>,
+    jwt_expiry_hours: Option<i64>,
+    host: Option<String>,
+    port: Option<u16>,
+    log_level: Option<String>,
+    admin: Option<AdminBootstrap>,
+    loan_period_days: Option<i64>,
+    hold_pickup_days: Option<i64>,
+    max_renewals: Option<i32>,
+    renewal_grace_days: Option<i64>,
+    fine_daily_rate_cents: Option<i64>,
+    fine_max_per_item_cents: Option<i64>,
+    max_owed_cents: Option<i64>,
+    max_overdue_items: Option<i64>,
+    max_concurrent_loans: Option<i64>,
+    storage_dir: Option<String>,
+    storage_base_url: Option<String>,
+    cover_max_bytes: Option<usize>,
+    search_index_dir: Option<String>,
+    suggest_refresh_seconds: Option<u64>,
+}
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Missing(&'static str),
+    Invalid { key: &'static str, value: String },
+    File(String),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Missing(key) => write!(f, "missing required setting {}", key),
+            ConfigError::Invalid { key, value } => write!(f, "invalid value for {}: {}", key, value),
+            ConfigError::File(message) => write!(f, "failed to read config file: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl AppConfig {
+    /// 读取配置：先加载 APP_CONFIG 指定的（或当前目录下的）config.toml，再用环境变量覆盖
+    pub fn load() -> Result<Self, ConfigError> {
+        let file = match env::var("APP_CONFIG") {
+            Ok(path) => read_file(Path::new(&path))?,
+            Err(_) if Path::new(DEFAULT_CONFIG_FILE).exists() => {
+                read_file(Path::new(DEFAULT_CONFIG_FILE))?
+            }
+            Err(_) => FileConfig::default(),
+        };
+
+        let admin = match (
+            env::var("ADMIN_USERNAME"),
+            env::var("ADMIN_EMAIL"),
+            env::var("ADMIN_PASSWORD"),
+        ) {
+            (Ok(username), Ok(email), Ok(password)) => Some(AdminBootstrap {
+                username,
+                email,
+                password,
+            }),
+            _ => file.admin,
+        };
+
+        let config = AppConfig {
+            database_url: env::var("DATABASE_URL")
+                .ok()
+                .or(file.database_url)
+                .ok_or(ConfigError::Missing("DATABASE_URL"))?,
+            test_database_url: env::var("TEST_DATABASE_URL").ok().or(file.test_database_url),
+            pool_size: parse_env("DB_POOL_SIZE")?.or(file.pool_size).unwrap_or(5),
+            jwt_secret: env::var("JWT_SECRET")
+                .ok()
+                .or(file.jwt_secret)
+                .ok_or(ConfigError::Missing("JWT_SECRET"))?,
+            jwt_expiry_hours: parse_env("JWT_EXPIRY_HOURS")?
+                .or(file.jwt_expiry_hours)
+                .unwrap_or(24),
+            host: env::var("APP_HOST")
+                .ok()
+                .or(file.host)
+                .unwrap_or_else(|| "127.0.0.1".to_string()),
+            port: parse_env("APP_PORT")?.or(file.port).unwrap_or(8080),
+            log_level: env::var("LOG_LEVEL")
+                .ok()
+                .or(file.log_level)
+                .unwrap_or_else(|| "info".to_string()),
+            admin,
+            loan_period_days: parse_env("LOAN_PERIOD_DAYS")?
+                .or(file.loan_period_days)
+                .unwrap_or(30),
+            hold_pickup_days: parse_env("HOLD_PICKUP_DAYS")?
+                .or(file.hold_pickup_days)
+                .unwrap_or(7),
+            max_renewals: parse_env("MAX_RENEWALS")?
+                .or(file.max_renewals)
+                .unwrap_or(2),
+            renewal_grace_days: parse_env("RENEWAL_GRACE_DAYS")?
+                .or(file.renewal_grace_days)
+                .unwrap_or(0),
+            fine_daily_rate_cents: parse_env("FINE_DAILY_RATE_CENTS")?
+                .or(file.fine_daily_rate_cents)
+                .unwrap_or(10),
+            fine_max_per_item_cents: parse_env("FINE_MAX_PER_ITEM_CENTS")?
+                .or(file.fine_max_per_item_cents)
+                .unwrap_or(2000),
+            max_owed_cents: parse_env("MAX_OWED_CENTS")?
+                .or(file.max_owed_cents)
+                .unwrap_or(1000),
+            max_overdue_items: parse_env("MAX_OVERDUE_ITEMS")?
+                .or(file.max_overdue_items)
+                .unwrap_or(0),
+            max_concurrent_loans: parse_env("MAX_CONCURRENT_LOANS")?
+                .or(file.max_concurrent_loans)
+                .unwrap_or(10),
+            storage_dir: env::var("STORAGE_DIR")
+                .ok()
+                .or(file.storage_dir)
+                .unwrap_or_else(|| "uploads".to_string()),
+            storage_base_url: env::var("STORAGE_BASE_URL")
+                .ok()
+                .or(file.storage_base_url)
+                .unwrap_or_else(|| "/uploads".to_string()),
+            cover_max_bytes: parse_env("COVER_MAX_BYTES")?
+                .or(file.cover_max_bytes)
+                .unwrap_or(5 * 1024 * 1024),
+            search_index_dir: env::var("SEARCH_INDEX_DIR")
+                .ok()
+                .or(file.search_index_dir)
+                .unwrap_or_else(|| "search-index".to_string()),
+            suggest_refresh_seconds: parse_env("SUGGEST_REFRESH_SECONDS")?
+                .or(file.suggest_refresh_seconds)
+                .unwrap_or(300),
+        };
+
+        if config.jwt_secret.trim().is_empty() {
+            return Err(ConfigError::Missing("JWT_SECRET"));
+        }
+        if config.pool_size == 0 {
+            return Err(ConfigError::Invalid {
+                key: "DB_POOL_SIZE",
+                value: "0".to_string(),
+            });
+        }
+        if config.jwt_expiry_hours <= 0 {
+            return Err(ConfigError::Invalid {
+                key: "JWT_EXPIRY_HOURS",
+                value: config.jwt_expiry_hours.to_string(),
+            });
+        }
+        // 借期、罚款和在借上限会转换为按小时、分计的 i32 写入借阅和流通规则，超出范围的值拒绝而不是回绕
+        if config.loan_period_days <= 0 || config.loan_period_hours().is_none() {
+            return Err(ConfigError::Invalid {
+                key: "LOAN_PERIOD_DAYS",
+                value: config.loan_period_days.to_string(),
+            });
+        }
+        if config.hold_pickup_days <= 0 {
+            return Err(ConfigError::Invalid {
+                key: "HOLD_PICKUP_DAYS",
+                value: config.hold_pickup_days.to_string(),
+            });
+        }
+        if config.max_renewals < 0 {
+            return Err(ConfigError::Invalid {
+                key: "MAX_RENEWALS",
+                value: config.max_renewals.to_string(),
+            });
+        }
+        if config.renewal_grace_days < 0 {
+            return Err(ConfigError::Invalid {
+                key: "RENEWAL_GRACE_DAYS",
+                value: config.renewal_grace_days.to_string(),
+            });
+        }
+        if config.fine_daily_rate_cents < 0 || i32::try_from(config.fine_daily_rate_cents).is_err() {
+            return Err(ConfigError::Invalid {
+                key: "FINE_DAILY_RATE_CENTS",
+                value: config.fine_daily_rate_cents.to_string(),
+            });
+        }
+        if config.fine_max_per_item_cents < 0 {
+            return Err(ConfigError::Invalid {
+                key: "FINE_MAX_PER_ITEM_CENTS",
+                value: config.fine_max_per_item_cents.to_string(),
+            });
+        }
+        if config.max_owed_cents < 0 {
+            return Err(ConfigError::Invalid {
+                key: "MAX_OWED_CENTS",
+                value: config.max_owed_cents.to_string(),
+            });
+        }
+        if config.max_overdue_items < 0 {
+            return Err(ConfigError::Invalid {
+                key: "MAX_OVERDUE_ITEMS",
+                value: config.max_overdue_items.to_string(),
+            });
+        }
+        if config.max_concurrent_loans <= 0 || i32::try_from(config.max_concurrent_loans).is_err() {
+            return Err(ConfigError::Invalid {
+                key: "MAX_CONCURRENT_LOANS",
+                value: config.max_concurrent_loans.to_string(),
+            });
+        }
+        if config.cover_max_bytes == 0 {
+            return Err(ConfigError::Invalid {
+                key: "COVER_MAX_BYTES",
+                value: "0".to_string(),
+            });
+        }
+        if config.suggest_refresh_seconds == 0 {
+            return Err(ConfigError::Invalid {
+                key: "SUGGEST_REFRESH_SECONDS",
+                value: "0".to_string(),
+            });
+        }
+        if !config.storage_base_url.starts_with('/') && !config.storage_base_url.contains("://") {
+            return Err(ConfigError::Invalid {
+                key: "STORAGE_BASE_URL",
+                value: config.storage_base_url.clone(),
+            });
+        }
+
+        Ok(config)
+    }
+
+    pub fn bind_address(&self) -> (String, u16) {
+        (self.host.clone(), self.port)
+    }
+
+    /// 默认借期（小时），超出 i32 范围时为 None
+    pub fn loan_period_hours(&self) -> Option<i32> {
+        self.loan_period_days
+            .checked_mul(24)
+            .and_then(|hours| i32::try_from(hours).ok())
+    }
+}
+
+fn read_file(path: &Path) -> Result<FileConfig, ConfigError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ConfigError::File(format!("{}: {}", path.display(), e)))?;
+    toml::from_str(&content).map_err(|e| ConfigError::File(format!("{}: {}", path.display(), e)))
+}
+
+fn parse_env<T: std::str::FromStr>(key: &'static str) -> Result<Option<T>, ConfigError> {
+    match env::var(key) {
+        Ok(value) => value
+            .parse()
+            .map(Some)
+            .map_err(|_| ConfigError::Invalid { key, value }),
+        Err(_) => Ok(None),
+    }
+}
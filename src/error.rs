@@ -0,0 +1,219 @@
+use actix_web::{http::StatusCode, HttpResponse, ResponseError};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+
+/// 统一的错误类型，所有接口错误都以 {code, message, details} 的 JSON 返回
+#[derive(Debug)]
+pub enum AppError {
+    NotFound(String),
+    Conflict(String, Option<Value>),
+    Validation(String, Option<Value>),
+    Unauthorized(String),
+    Forbidden(String),
+    Internal(String),
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody<'a> {
+    code: &'a str,
+    message: &'a str,
+    details: Option<&'a Value>,
+}
+
+pub type AppResult<T> = Result<T, AppError>;
+
+impl AppError {
+    pub fn not_found(message: impl Into<String>) -> Self {
+        AppError::NotFound(message.into())
+    }
+
+    pub fn conflict(message: impl Into<String>) -> Self {
+        AppError::Conflict(message.into(), None)
+    }
+
+    pub fn validation(message: impl Into<String>, details: Option<Value>) -> Self {
+        AppError::Validation(message.into(), details)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        AppError::Unauthorized(message.into())
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        AppError::Forbidden(message.into())
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Conflict(..) => "CONFLICT",
+            AppError::Validation(..) => "VALIDATION_FAILED",
+            AppError::Unauthorized(_) => "UNAUTHORIZED",
+            AppError::Forbidden(_) => "FORBIDDEN",
+            AppError::Internal(_) => "INTERNAL_ERROR",
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            AppError::NotFound(message)
+            | AppError::Conflict(message, _)
+            | AppError::Validation(message, _)
+            | AppError::Unauthorized(message)
+            | AppError::Forbidden(message) => message,
+            // 内部错误细节只写日志，不返回给客户端
+            AppError::Internal(_) => "Internal server error",
+        }
+    }
+
+    fn details(&self) -> Option<&Value> {
+        match self {
+            AppError::Conflict(_, details) | AppError::Validation(_, details) => details.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Internal(message) => write!(f, "{}: {}", self.code(), message),
+            _ => write!(f, "{}: {}", self.code(), self.message()),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+impl ResponseError for AppError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(..) => StatusCode::CONFLICT,
+            AppError::Validation(..) => StatusCode::BAD_REQUEST,
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            AppError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AppError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        if let AppError::Internal(message) = self {
+            log::error!("{}", message);
+        }
+
+        HttpResponse::build(self.status_code()).json(ErrorBody {
+            code: self.code(),
+            message: self.message(),
+            details: self.details(),
+        })
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::RowNotFound => AppError::not_found("Resource not found"),
+            sqlx::Error::Database(db_error) => match db_error.kind() {
+                sqlx::error::ErrorKind::UniqueViolation => AppError::Conflict(
+                    "Resource already exists".to_string(),
+                    duplicate_key(db_error.message()).map(|key| serde_json::json!({ "key": key })),
+                ),
+                sqlx::error::ErrorKind::ForeignKeyViolation => {
+                    AppError::conflict("Resource is referenced by or references missing data")
+                }
+                _ => AppError::Internal(format!("Database error: {}", error)),
+            },
+            _ => AppError::Internal(format!("Database error: {}", error)),
+        }
+    }
+}
+
+impl From<crate::models::loan::RenewalRefusal> for AppError {
+    fn from(refusal: crate::models::loan::RenewalRefusal) -> Self {
+        AppError::Conflict(
+            refusal.message().to_string(),
+            Some(serde_json::json!({ "reason": refusal.code() })),
+        )
+    }
+}
+
+impl From<crate::models::user::BlockReason> for AppError {
+    fn from(reason: crate::models::user::BlockReason) -> Self {
+        AppError::Conflict(
+            reason.message().to_string(),
+            Some(serde_json::json!({ "reason": reason.code() })),
+        )
+    }
+}
+
+impl From<bcrypt::BcryptError> for AppError {
+    fn from(error: bcrypt::BcryptError) -> Self {
+        AppError::Internal(format!("Password hashing error: {}", error))
+    }
+}
+
+impl From<jsonwebtoken::errors::Error> for AppError {
+    fn from(error: jsonwebtoken::errors::Error) -> Self {
+        AppError::Internal(format!("Token error: {}", error))
+    }
+}
+
+#[cfg(feature = "search-index")]
+impl From<tantivy::TantivyError> for AppError {
+    fn from(error: tantivy::TantivyError) -> Self {
+        AppError::Internal(format!("Search index error: {}", error))
+    }
+}
+
+impl From<validator::ValidationErrors> for AppError {
+    fn from(errors: validator::ValidationErrors) -> Self {
+        // details 形如 {"title": [{"code": "length", "message": "..."}]}
+        let fields: serde_json::Map<String, Value> = errors
+            .field_errors()
+            .into_iter()
+            .map(|(field, errors)| {
+                let errors = errors
+                    .iter()
+                    .map(|error| {
+                        serde_json::json!({
+                            "code": error.code,
+                            "message": error
+                                .message
+                                .clone()
+                                .unwrap_or_else(|| format!("{} is invalid ({})", field, error.code).into()),
+                        })
+                    })
+                    .collect();
+                // r#type 这类原始标识符按 JSON 字段名返回
+                (field.trim_start_matches("r#").to_string(), Value::Array(errors))
+            })
+            .collect();
+
+        AppError::validation("Request validation failed", Some(Value::Object(fields)))
+    }
+}
+
+/// 从 MySQL 的 "Duplicate entry 'x' for key 'users.username'" 中取出键名
+fn duplicate_key(message: &str) -> Option<String> {
+    let start = message.find("for key '")? + "for key '".len();
+    let end = message[start..].find('\'')?;
+    Some(message[start..start + end].to_string())
+}
+
+/// 请求体、路径、查询参数解析失败时同样返回统一的错误格式
+pub fn json_config() -> actix_web::web::JsonConfig {
+    actix_web::web::JsonConfig::default()
+        .error_handler(|err, _req| AppError::validation(err.to_string(), None).into())
+}
+
+pub fn path_config() -> actix_web::web::PathConfig {
+    actix_web::web::PathConfig::default()
+        .error_handler(|err, _req| AppError::validation(err.to_string(), None).into())
+}
+
+pub fn query_config() -> actix_web::web::QueryConfig {
+    actix_web::web::QueryConfig::default()
+        .error_handler(|err, _req| AppError::validation(err.to_string(), None).into())
+}
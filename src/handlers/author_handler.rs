@@ -0,0 +1,333 @@
+use std::collections::{HashMap, HashSet};
+
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
+use crate::handlers::book_handler::book_responses;
+use crate::models::author::{
+    split_author_names, Author, AuthorQuery, AuthorRole, BookAuthor, BookAuthorInput, CreateAuthor, UpdateAuthor,
+};
+use crate::models::book::Book;
+
+pub async fn list_authors(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<AuthorQuery>,
+) -> AppResult<HttpResponse> {
+    let page_no = query.page_no.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10);
+    let offset = (page_no - 1) * page_size;
+    let name = query.name.as_ref().map(|name| format!("%{}%", name));
+
+    let authors: Vec<Author> = sqlx::query_as!(
+        Author,
+        r#"
+        SELECT * FROM authors
+        WHERE (? IS NULL OR name LIKE ?)
+        ORDER BY name, created_at
+        LIMIT ? OFFSET ?
+        "#,
+        name,
+        name,
+        page_size,
+        offset
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let total: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM authors WHERE (? IS NULL OR name LIKE ?)
+        "#,
+        name,
+        name
+    )
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": total,
+        "page_no": page_no,
+        "page_size": page_size,
+        "data": authors
+    })))
+}
+
+pub async fn create_author(
+    pool: web::Data<MySqlPool>,
+    author: web::Json<CreateAuthor>,
+) -> AppResult<HttpResponse> {
+    author.validate()?;
+
+    let now = Utc::now().naive_local();
+    let new_author = Author {
+        id: Uuid::new_v4().to_string(),
+        name: author.name.trim().to_string(),
+        bio: author.bio.clone(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    let mut conn = pool.acquire().await?;
+    insert_author(&mut conn, &new_author).await?;
+
+    Ok(HttpResponse::Created().json(new_author))
+}
+
+pub async fn get_author(
+    pool: web::Data<MySqlPool>,
+    author_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let author = fetch_author(pool.get_ref(), &author_id.to_string()).await?;
+    Ok(HttpResponse::Ok().json(author))
+}
+
+pub async fn update_author(
+    pool: web::Data<MySqlPool>,
+    author_id: web::Path<Uuid>,
+    update: web::Json<UpdateAuthor>,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+    let author_id = author_id.to_string();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE authors
+        SET name = COALESCE(?, name),
+            bio = COALESCE(?, bio),
+            updated_at = ?
+        WHERE id = ?
+        "#,
+        update.name.as_deref().map(str::trim),
+        update.bio,
+        Utc::now().naive_local(),
+        author_id
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Author not found"));
+    }
+
+    let author = fetch_author(pool.get_ref(), &author_id).await?;
+    Ok(HttpResponse::Ok().json(author))
+}
+
+pub async fn delete_author(
+    pool: web::Data<MySqlPool>,
+    author_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    // 仍有图书关联的作者由外键拦截，返回 409
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM authors WHERE id = ?
+        "#,
+        author_id.to_string()
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Author not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn list_author_books(
+    pool: web::Data<MySqlPool>,
+    author_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let author_id = author_id.to_string();
+    fetch_author(pool.get_ref(), &author_id).await?;
+
+    let books = sqlx::query_as::<_, Book>(
+        "SELECT * FROM books
+         WHERE id IN (SELECT book_id FROM book_authors WHERE author_id = ?)
+         ORDER BY created_at DESC",
+    )
+    .bind(&author_id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let data = book_responses(pool.get_ref(), books).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": data.len(),
+        "data": data
+    })))
+}
+
+async fn fetch_author(pool: &MySqlPool, author_id: &str) -> AppResult<Author> {
+    sqlx::query_as!(
+        Author,
+        r#"
+        SELECT * FROM authors WHERE id = ?
+        "#,
+        author_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Author not found"))
+}
+
+async fn insert_author(conn: &mut MySqlConnection, author: &Author) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO authors (id, name, bio, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        author.id,
+        author.name,
+        author.bio,
+        author.created_at,
+        author.updated_at
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// 用给定的责任者替换图书现有的关联，顺序即数组顺序
+pub(crate) async fn link_authors(
+    conn: &mut MySqlConnection,
+    book_id: &str,
+    authors: &[BookAuthorInput],
+) -> AppResult<()> {
+    for author in authors {
+        let exists: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) FROM authors WHERE id = ?
+            "#,
+            author.author_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if exists == 0 {
+            return Err(AppError::validation(
+                "Request validation failed",
+                Some(serde_json::json!({
+                    "authors": [{ "code": "author", "message": format!("Author {} not found", author.author_id) }]
+                })),
+            ));
+        }
+    }
+
+    sqlx::query!(
+        r#"
+        DELETE FROM book_authors WHERE book_id = ?
+        "#,
+        book_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    for (position, author) in (1..).zip(authors) {
+        insert_book_author(conn, book_id, &author.author_id, author.role, position).await?;
+    }
+    Ok(())
+}
+
+/// 未指定责任者时按著录的作者字符串拆分，替换图书现有的关联；同名作者已存在时复用最早创建的一位
+pub(crate) async fn link_author_names(
+    conn: &mut MySqlConnection,
+    book_id: &str,
+    author: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        DELETE FROM book_authors WHERE book_id = ?
+        "#,
+        book_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    let mut seen = HashSet::new();
+    let mut names = split_author_names(author);
+    names.retain(|name| seen.insert(name.clone()));
+
+    for (position, name) in (1..).zip(names) {
+        let existing: Option<String> = sqlx::query_scalar!(
+            r#"
+            SELECT id FROM authors WHERE name = ? ORDER BY created_at, id LIMIT 1
+            "#,
+            name
+        )
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let author_id = match existing {
+            Some(author_id) => author_id,
+            None => {
+                let now = Utc::now().naive_local();
+                let new_author = Author {
+                    id: Uuid::new_v4().to_string(),
+                    name,
+                    bio: None,
+                    created_at: now,
+                    updated_at: now,
+                };
+                insert_author(conn, &new_author).await?;
+                new_author.id
+            }
+        };
+        insert_book_author(conn, book_id, &author_id, AuthorRole::Author, position).await?;
+    }
+    Ok(())
+}
+
+async fn insert_book_author(
+    conn: &mut MySqlConnection,
+    book_id: &str,
+    author_id: &str,
+    role: AuthorRole,
+    position: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO book_authors (book_id, author_id, role, position)
+        VALUES (?, ?, ?, ?)
+        "#,
+        book_id,
+        author_id,
+        role.as_str(),
+        position
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// 批量查询多本图书的责任者，按顺序排列
+pub(crate) async fn book_authors(
+    pool: &MySqlPool,
+    book_ids: &[&str],
+) -> Result<HashMap<String, Vec<BookAuthor>>, sqlx::Error> {
+    if book_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders = vec!["?"; book_ids.len()].join(", ");
+    let query_str = format!(
+        "SELECT ba.book_id, a.id, a.name, ba.role, ba.position
+         FROM book_authors ba JOIN authors a ON a.id = ba.author_id
+         WHERE ba.book_id IN ({})
+         ORDER BY ba.position",
+        placeholders
+    );
+
+    let mut query_builder = sqlx::query_as::<_, BookAuthor>(&query_str);
+    for book_id in book_ids {
+        query_builder = query_builder.bind(book_id);
+    }
+
+    let mut authors: HashMap<String, Vec<BookAuthor>> = HashMap::new();
+    for author in query_builder.fetch_all(pool).await? {
+        authors.entry(author.book_id.clone()).or_default().push(author);
+    }
+    Ok(authors)
+}
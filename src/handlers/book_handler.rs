@@ -1,186 +1,720 @@
-use actix_web::{web, HttpResponse, Responder};
-use sqlx::MySqlPool;
+use std::collections::HashMap;
+
+use actix_web::{web, HttpResponse};
+use sqlx::mysql::MySqlArguments;
+use sqlx::query::QueryAs;
+use sqlx::{MySql, MySqlConnection, MySqlPool};
 use uuid::Uuid;
 use chrono::Utc;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
 
-use crate::models::book::{Book, CreateBook, UpdateBook};
+use crate::error::{AppError, AppResult};
+use crate::handlers::author_handler::{book_authors, link_author_names, link_authors};
+use crate::handlers::cover_handler::{book_covers, cover_prefix, remove_stored};
+use crate::handlers::category_handler::{book_categories, link_categories, CATEGORY_SUBTREE};
+use crate::handlers::branch_handler::resolve_branch;
+use crate::handlers::item_handler::{insert_placeholder_items, item_counts};
+use crate::handlers::series_handler::ensure_series;
+use crate::handlers::work_handler::ensure_work;
+use crate::models::book::{
+    Book, BookDetail, BookFacets, BookResponse, BookSummary, CreateBook, FacetCount, Isbn, SearchHit, UpdateBook,
+};
+use crate::models::call_number::CallNumber;
+use crate::models::item::ItemStatus;
+use crate::models::pinyin;
+use crate::models::search::{SearchQuery, SearchTerm};
+#[cfg(feature = "search-index")]
+use crate::search_index::SearchIndex;
+use crate::storage::Storage;
 
 pub async fn create_book(
     pool: web::Data<MySqlPool>,
+    #[cfg(feature = "search-index")] index: Option<web::Data<SearchIndex>>,
     book: web::Json<CreateBook>,
-) -> impl Responder {
+) -> AppResult<HttpResponse> {
+    book.validate()?;
+
+    let isbn = parse_isbn(&book.isbn)?;
+    let call_number = book.call_number.as_deref().map(parse_call_number).transpose()?;
+    if book.series_volume.is_some() && book.series_id.is_none() {
+        return Err(volume_without_series());
+    }
     let book_id = Uuid::new_v4().to_string();
     let now = chrono::Utc::now().naive_local();
+    let title_pinyin = pinyin::full(&book.title);
+    let title_initials = pinyin::initials(&book.title);
+    let author_pinyin = pinyin::full(&book.author);
+    let author_initials = pinyin::initials(&book.author);
+    let mut tx = pool.begin().await?;
+    let branch_id = resolve_branch(&mut tx, book.branch_id.as_deref()).await?;
+    check_work_and_series(&mut tx, book.work_id.as_deref(), book.series_id.as_deref()).await?;
 
-    match sqlx::query!(
+    sqlx::query!(
         r#"
-        INSERT INTO books (id, title, author, isbn, description, type, quantity, created_at, updated_at)
-        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        INSERT INTO books
+            (id, title, title_pinyin, title_initials, subtitle, original_title,
+             author, author_pinyin, author_initials, isbn, description, type,
+             publisher, publication_year, edition, language, pages,
+             call_number, call_number_sort, work_id, series_id, series_volume, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
         "#,
         book_id,
         book.title,
+        title_pinyin,
+        title_initials,
+        book.subtitle,
+        book.original_title,
         book.author,
-        book.isbn,
+        author_pinyin,
+        author_initials,
+        isbn.as_str(),
         book.description,
         book.r#type,
-        book.quantity,
+        book.publisher,
+        book.publication_year,
+        book.edition,
+        book.language,
+        book.pages,
+        call_number.as_ref().map(CallNumber::as_str),
+        call_number.as_ref().map(CallNumber::sort_key),
+        book.work_id,
+        book.series_id,
+        book.series_volume,
         now,
         now
     )
-    .execute(pool.get_ref())
-    .await
-    {
-        Ok(_) => {
-            let new_book = Book {
-                id: book_id.clone(),
-                title: book.title.clone(),
-                author: book.author.clone(),
-                isbn: book.isbn.clone(),
-                description: book.description.clone(),
-                r#type: book.r#type.clone(),
-                quantity: book.quantity,
-                created_at: now,
-                updated_at: now,
-            };
-            HttpResponse::Created().json(new_book)
-        }
-        Err(e) => {
-            eprintln!("Error creating book: {}", e);
-            HttpResponse::InternalServerError().finish()
-        }
+    .execute(&mut *tx)
+    .await?;
+
+    match &book.authors {
+        Some(authors) => link_authors(&mut tx, &book_id, authors).await?,
+        None => link_author_names(&mut tx, &book_id, &book.author).await?,
+    }
+    if let Some(category_ids) = &book.category_ids {
+        link_categories(&mut tx, &book_id, category_ids).await?;
     }
+    insert_placeholder_items(&mut tx, &book_id, &branch_id, book.quantity).await?;
+    tx.commit().await?;
+
+    let new_book = Book {
+        id: book_id.clone(),
+        title: book.title.clone(),
+        title_pinyin,
+        title_initials,
+        subtitle: book.subtitle.clone(),
+        original_title: book.original_title.clone(),
+        author: book.author.clone(),
+        author_pinyin,
+        author_initials,
+        isbn: isbn.to_string(),
+        description: book.description.clone(),
+        r#type: book.r#type.clone(),
+        publisher: book.publisher.clone(),
+        publication_year: book.publication_year,
+        edition: book.edition.clone(),
+        language: book.language.clone(),
+        pages: book.pages,
+        call_number: call_number.as_ref().map(CallNumber::to_string),
+        call_number_sort: call_number.as_ref().map(|call_number| call_number.sort_key().to_string()),
+        work_id: book.work_id.clone(),
+        series_id: book.series_id.clone(),
+        series_volume: book.series_volume,
+        created_at: now,
+        updated_at: now,
+    };
+    #[cfg(feature = "search-index")]
+    index_book(index, &new_book).await;
+    Ok(HttpResponse::Created().json(book_response(pool.get_ref(), new_book).await?))
 }
 
 pub async fn get_book(
     pool: web::Data<MySqlPool>,
     book_id: web::Path<Uuid>,
-) -> impl Responder {
-    match sqlx::query_as!(
-        Book,
-        r#"
-        SELECT * FROM books WHERE id = ?
-        "#,
-        book_id.to_string()
-    )
-    .fetch_optional(pool.get_ref())
-    .await
-    {
-        Ok(Some(book)) => HttpResponse::Ok().json(book),
-        Ok(None) => HttpResponse::NotFound().finish(),
-        Err(e) => {
-            eprintln!("Error fetching book: {}", e);
-            HttpResponse::InternalServerError().finish()
+) -> AppResult<HttpResponse> {
+    let book = fetch_book(pool.get_ref(), &book_id.to_string()).await?;
+
+    // 同一作品的其他版本，按出版年倒序
+    let other_editions: Vec<BookSummary> = match &book.work_id {
+        Some(work_id) => {
+            sqlx::query_as::<_, BookSummary>(
+                "SELECT id, title, author, edition, publication_year, language, series_volume
+                 FROM books
+                 WHERE work_id = ? AND id <> ?
+                 ORDER BY publication_year IS NULL, publication_year DESC, created_at DESC",
+            )
+            .bind(work_id)
+            .bind(&book.id)
+            .fetch_all(pool.get_ref())
+            .await?
         }
-    }
+        None => Vec::new(),
+    };
+
+    // 丛书中卷次更大的第一卷；同一卷次有多个版本时取最早入藏的一个
+    let next_in_series: Option<BookSummary> = match (&book.series_id, book.series_volume) {
+        (Some(series_id), Some(series_volume)) => {
+            sqlx::query_as::<_, BookSummary>(
+                "SELECT id, title, author, edition, publication_year, language, series_volume
+                 FROM books
+                 WHERE series_id = ? AND series_volume > ?
+                 ORDER BY series_volume, created_at
+                 LIMIT 1",
+            )
+            .bind(series_id)
+            .bind(series_volume)
+            .fetch_optional(pool.get_ref())
+            .await?
+        }
+        _ => None,
+    };
+
+    let book = book_response(pool.get_ref(), book).await?;
+    Ok(HttpResponse::Ok().json(BookDetail { book, other_editions, next_in_series }))
 }
 
 pub async fn update_book(
     pool: web::Data<MySqlPool>,
+    #[cfg(feature = "search-index")] index: Option<web::Data<SearchIndex>>,
     book_id: web::Path<Uuid>,
     book_update: web::Json<UpdateBook>,
-) -> impl Responder {
+) -> AppResult<HttpResponse> {
+    book_update.validate()?;
+
+    let isbn = book_update.isbn.as_deref().map(parse_isbn).transpose()?;
+    let call_number = book_update
+        .call_number
+        .as_ref()
+        .map(|call_number| call_number.as_deref().map(parse_call_number).transpose())
+        .transpose()?;
     let now = Utc::now().naive_local();
+    let book_id = book_id.to_string();
+    let mut tx = pool.begin().await?;
+    check_work_and_series(
+        &mut tx,
+        book_update.work_id.as_ref().and_then(Option::as_deref),
+        book_update.series_id.as_ref().and_then(Option::as_deref),
+    )
+    .await?;
 
-    let update_query = sqlx::query!(
+    // MySQL 按书写顺序赋值，series_volume 写在 series_id 之前，比较的是修改前的丛书
+    let result = sqlx::query!(
         r#"
         UPDATE books
         SET title = COALESCE(?, title),
+            title_pinyin = COALESCE(?, title_pinyin),
+            title_initials = COALESCE(?, title_initials),
+            subtitle = IF(?, ?, subtitle),
+            original_title = IF(?, ?, original_title),
             author = COALESCE(?, author),
+            author_pinyin = COALESCE(?, author_pinyin),
+            author_initials = COALESCE(?, author_initials),
             isbn = COALESCE(?, isbn),
             description = COALESCE(?, description),
             type = COALESCE(?, type),
-            quantity = COALESCE(?, quantity),
+            publisher = IF(?, ?, publisher),
+            publication_year = IF(?, ?, publication_year),
+            edition = IF(?, ?, edition),
+            language = IF(?, ?, language),
+            pages = IF(?, ?, pages),
+            call_number = IF(?, ?, call_number),
+            call_number_sort = IF(?, ?, call_number_sort),
+            work_id = IF(?, ?, work_id),
+            series_volume = CASE
+                WHEN ? THEN ?
+                WHEN ? AND NOT (series_id <=> ?) THEN NULL
+                ELSE series_volume
+            END,
+            series_id = IF(?, ?, series_id),
             updated_at = ?
         WHERE id = ?
         "#,
         book_update.title,
+        book_update.title.as_deref().map(pinyin::full),
+        book_update.title.as_deref().map(pinyin::initials),
+        book_update.subtitle.is_some(),
+        book_update.subtitle.clone().flatten(),
+        book_update.original_title.is_some(),
+        book_update.original_title.clone().flatten(),
         book_update.author,
-        book_update.isbn,
+        book_update.author.as_deref().map(pinyin::full),
+        book_update.author.as_deref().map(pinyin::initials),
+        isbn.as_ref().map(Isbn::as_str),
         book_update.description,
         book_update.r#type,
-        book_update.quantity,
+        book_update.publisher.is_some(),
+        book_update.publisher.clone().flatten(),
+        book_update.publication_year.is_some(),
+        book_update.publication_year.flatten(),
+        book_update.edition.is_some(),
+        book_update.edition.clone().flatten(),
+        book_update.language.is_some(),
+        book_update.language.clone().flatten(),
+        book_update.pages.is_some(),
+        book_update.pages.flatten(),
+        call_number.is_some(),
+        call_number.as_ref().and_then(|call_number| call_number.as_ref().map(CallNumber::as_str)),
+        call_number.is_some(),
+        call_number.as_ref().and_then(|call_number| call_number.as_ref().map(CallNumber::sort_key)),
+        book_update.work_id.is_some(),
+        book_update.work_id.clone().flatten(),
+        book_update.series_volume.is_some(),
+        book_update.series_volume.flatten(),
+        book_update.series_id.is_some(),
+        book_update.series_id.clone().flatten(),
+        book_update.series_id.is_some(),
+        book_update.series_id.clone().flatten(),
         now,
-        book_id.to_string()
+        book_id
     )
-    .execute(pool.get_ref())
-    .await;
-
-    match update_query {
-        Ok(_) => {
-            match sqlx::query_as!(
-                Book,
-                r#"
-                SELECT * FROM books WHERE id = ?
-                "#,
-                book_id.to_string()
-            )
-            .fetch_optional(pool.get_ref())
-            .await
-            {
-                Ok(Some(book)) => HttpResponse::Ok().json(book),
-                _ => HttpResponse::NotFound().finish(),
-            }
-        }
-        Err(e) => {
-            eprintln!("Error updating book: {}", e);
-            HttpResponse::InternalServerError().finish()
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Book not found"));
+    }
+    if let Some(Some(_)) = book_update.series_volume {
+        let without_series: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) FROM books WHERE id = ? AND series_id IS NULL
+            "#,
+            book_id
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+        if without_series > 0 {
+            return Err(volume_without_series());
         }
     }
+    // 只修改 author 字符串时与新增图书一样按姓名重新关联作者
+    match (&book_update.authors, &book_update.author) {
+        (Some(authors), _) => link_authors(&mut tx, &book_id, authors).await?,
+        (None, Some(author)) => link_author_names(&mut tx, &book_id, author).await?,
+        (None, None) => {}
+    }
+    if let Some(category_ids) = &book_update.category_ids {
+        link_categories(&mut tx, &book_id, category_ids).await?;
+    }
+    tx.commit().await?;
+
+    let book = fetch_book(pool.get_ref(), &book_id).await?;
+    #[cfg(feature = "search-index")]
+    index_book(index, &book).await;
+    Ok(HttpResponse::Ok().json(book_response(pool.get_ref(), book).await?))
 }
 
 pub async fn delete_book(
     pool: web::Data<MySqlPool>,
+    storage: web::Data<dyn Storage>,
+    #[cfg(feature = "search-index")] index: Option<web::Data<SearchIndex>>,
     book_id: web::Path<Uuid>,
-) -> impl Responder {
-    match sqlx::query!(
+) -> AppResult<HttpResponse> {
+    let book_id = book_id.to_string();
+    let result = sqlx::query!(
         r#"
         DELETE FROM books WHERE id = ?
         "#,
-        book_id.to_string()
+        book_id
     )
     .execute(pool.get_ref())
-    .await
-    {
-        Ok(_) => HttpResponse::NoContent().finish(),
-        Err(e) => {
-            eprintln!("Error deleting book: {}", e);
-            HttpResponse::InternalServerError().finish()
-        }
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Book not found"));
     }
+
+    // 封面记录随图书级联删除，存储中的文件在此一并清理
+    remove_stored(storage.get_ref(), &cover_prefix(&book_id)).await;
+    #[cfg(feature = "search-index")]
+    unindex_book(index, book_id).await;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// 数据库提交后同步搜索索引。失败只记录日志，不影响本次请求，可用 rebuild-search-index 命令重建恢复
+#[cfg(feature = "search-index")]
+async fn index_book(index: Option<web::Data<SearchIndex>>, book: &Book) {
+    let Some(index) = index else { return };
+    let book = book.clone();
+    let book_id = book.id.clone();
+    match web::block(move || index.upsert(&book)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => log::warn!("Failed to index book {}: {}", book_id, e),
+        Err(e) => log::warn!("Failed to index book {}: {}", book_id, e),
+    }
+}
+
+#[cfg(feature = "search-index")]
+async fn unindex_book(index: Option<web::Data<SearchIndex>>, book_id: String) {
+    let Some(index) = index else { return };
+    let id = book_id.clone();
+    match web::block(move || index.delete(&id)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => log::warn!("Failed to remove book {} from search index: {}", book_id, e),
+        Err(e) => log::warn!("Failed to remove book {} from search index: {}", book_id, e),
+    }
+}
+
+async fn fetch_book(pool: &MySqlPool, book_id: &str) -> AppResult<Book> {
+    sqlx::query_as!(
+        Book,
+        r#"
+        SELECT * FROM books WHERE id = ?
+        "#,
+        book_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Book not found"))
+}
+
+async fn book_response(pool: &MySqlPool, book: Book) -> AppResult<BookResponse> {
+    let mut responses = book_responses(pool, vec![book]).await?;
+    Ok(responses.remove(0))
+}
+
+/// 批量查询责任者、分类、封面和馆藏统计，组装图书响应
+pub(crate) async fn book_responses(pool: &MySqlPool, books: Vec<Book>) -> AppResult<Vec<BookResponse>> {
+    let book_ids: Vec<&str> = books.iter().map(|book| book.id.as_str()).collect();
+    let mut authors = book_authors(pool, &book_ids).await?;
+    let mut categories = book_categories(pool, &book_ids).await?;
+    let mut covers = book_covers(pool, &book_ids).await?;
+    let counts = item_counts(pool, &book_ids).await?;
+
+    Ok(books
+        .into_iter()
+        .map(|book| {
+            let book_authors = authors.remove(&book.id).unwrap_or_default();
+            let book_categories = categories.remove(&book.id).unwrap_or_default();
+            let book_cover = covers.remove(&book.id);
+            let book_counts = counts.get(&book.id).cloned().unwrap_or_default();
+            BookResponse::new(book, book_authors, book_categories, book_cover, book_counts)
+        })
+        .collect())
+}
+
+fn parse_isbn(value: &str) -> AppResult<Isbn> {
+    Isbn::parse(value).map_err(|e| {
+        AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({ "isbn": [{ "code": "isbn", "message": e.to_string() }] })),
+        )
+    })
+}
+
+fn parse_call_number(value: &str) -> AppResult<CallNumber> {
+    CallNumber::parse(value).map_err(|e| {
+        AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({ "call_number": [{ "code": "call_number", "message": e.to_string() }] })),
+        )
+    })
+}
+
+async fn check_work_and_series(
+    conn: &mut MySqlConnection,
+    work_id: Option<&str>,
+    series_id: Option<&str>,
+) -> AppResult<()> {
+    if let Some(work_id) = work_id {
+        ensure_work(conn, work_id).await?;
+    }
+    if let Some(series_id) = series_id {
+        ensure_series(conn, series_id).await?;
+    }
+    Ok(())
+}
+
+fn volume_without_series() -> AppError {
+    AppError::validation(
+        "Request validation failed",
+        Some(serde_json::json!({
+            "series_volume": [{ "code": "series", "message": "series_volume requires series_id" }]
+        })),
+    )
+}
+
+/// 拼音的两种匹配方式：全拼包含输入，首字母以输入开头
+fn pinyin_patterns(key: &str) -> [String; 2] {
+    [format!("%{}%", key), format!("{}%", key)]
+}
+
+fn parse_search(value: &str) -> AppResult<SearchQuery> {
+    SearchQuery::parse(value).map_err(|e| {
+        AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({ "q": [{ "code": "search", "message": e.to_string() }] })),
+        )
+    })
+}
+
+/// 搜索索引最多返回的候选图书数，超出部分不参与过滤和分页
+#[cfg(feature = "search-index")]
+const INDEX_HIT_LIMIT: usize = 1000;
+
+/// 全文检索条件，使用 ft_books_text 索引
+const SEARCH_CONDITION: &str = "MATCH(title, subtitle, author, description) AGAINST (? IN BOOLEAN MODE)";
+
+/// 相关度：题名命中权重最高，其次是责任者，再加上全部字段的得分
+const SEARCH_SCORE: &str = "MATCH(title, subtitle) AGAINST (? IN BOOLEAN MODE) * 3 \
+     + MATCH(author) AGAINST (? IN BOOLEAN MODE) * 2 \
+     + MATCH(title, subtitle, author, description) AGAINST (? IN BOOLEAN MODE)";
+
+/// 列表排序方式：默认按创建时间倒序，带 q 时默认按相关度；call_number 按索书号排架顺序（无索书号的排在最后）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BookSort {
+    CreatedAt,
+    CallNumber,
+    Relevance,
+}
+
+#[derive(sqlx::FromRow)]
+struct ScoredBook {
+    #[sqlx(flatten)]
+    book: Book,
+    /// 未检索时为 NULL
+    score: Option<f64>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct BookQuery {
     pub page_no: Option<i64>,
     pub page_size: Option<i64>,
+    /// 全文检索题名、责任者和简介，支持 "短语" 和 前缀*
+    pub q: Option<String>,
     pub id: Option<String>,
     pub title: Option<String>,
     pub author: Option<String>,
+    pub isbn: Option<String>,
+    /// 只返回该作者参与的图书
+    pub author_id: Option<String>,
+    pub publisher: Option<String>,
+    pub language: Option<String>,
+    /// 出版年范围，两端均包含
+    pub year_from: Option<i32>,
+    pub year_to: Option<i32>,
+    /// 只返回该分馆有馆藏的图书
+    pub branch_id: Option<String>,
+    /// 只返回属于该分类或其子孙分类的图书
+    pub category_id: Option<String>,
+    pub work_id: Option<String>,
+    pub series_id: Option<String>,
+    /// 馆藏类型，精确匹配
+    pub r#type: Option<String>,
+    /// 出版年代，如 1990 表示 1990~1999 年
+    pub decade: Option<i32>,
+    /// true 只返回有在架可借单册的图书，false 只返回没有的
+    pub available: Option<bool>,
+    /// 同一作品的多个版本只返回一条
+    #[serde(default)]
+    pub collapse: bool,
+    pub sort: Option<BookSort>,
+    /// 是否同时返回分面统计
+    #[serde(default)]
+    pub facets: bool,
 }
 
 pub async fn list_books(
     pool: web::Data<MySqlPool>,
+    #[cfg(feature = "search-index")] index: Option<web::Data<SearchIndex>>,
     query: web::Query<BookQuery>,
-) -> impl Responder {
+) -> AppResult<HttpResponse> {
     let page_no = query.page_no.unwrap_or(1);
     let page_size = query.page_size.unwrap_or(10);
     let offset = (page_no - 1) * page_size;
 
+    if let (Some(year_from), Some(year_to)) = (query.year_from, query.year_to) {
+        if year_from > year_to {
+            return Err(AppError::validation(
+                "Request validation failed",
+                Some(serde_json::json!({
+                    "year_from": [{ "code": "range", "message": "year_from must not be after year_to" }]
+                })),
+            ));
+        }
+    }
+
+    if query.decade.is_some_and(|decade| decade % 10 != 0) {
+        return Err(AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({
+                "decade": [{ "code": "decade", "message": "decade must be a multiple of 10" }]
+            })),
+        ));
+    }
+
+    let search = query.q.as_deref().map(parse_search).transpose()?;
+    let sort = match (query.sort, &search) {
+        (Some(BookSort::Relevance), None) => {
+            return Err(AppError::validation(
+                "Request validation failed",
+                Some(serde_json::json!({
+                    "sort": [{ "code": "sort", "message": "sort=relevance requires q" }]
+                })),
+            ));
+        }
+        (Some(sort), _) => sort,
+        (None, Some(_)) => BookSort::Relevance,
+        (None, None) => BookSort::CreatedAt,
+    };
+
+    // 启用搜索索引时由索引给出候选图书及相关度，其余过滤、排序和分页仍由 SQL 完成
+    #[cfg(feature = "search-index")]
+    let index_hits = match (&search, index) {
+        (Some(search), Some(index)) => {
+            let search = search.clone();
+            let hits = web::block(move || index.search(&search, INDEX_HIT_LIMIT))
+                .await
+                .map_err(|e| AppError::Internal(format!("Search index query failed: {}", e)))??;
+            Some(hits)
+        }
+        _ => None,
+    };
+    #[cfg(not(feature = "search-index"))]
+    let index_hits: Option<Vec<(String, f32)>> = None;
+    let index_condition = match &index_hits {
+        Some(hits) if !hits.is_empty() => format!("id IN ({})", vec!["?"; hits.len()].join(", ")),
+        _ => "FALSE".to_string(),
+    };
+
+    // 检索词可能是拼音时，同时匹配题名、责任者的全拼和首字母
+    let pinyin_key = search.as_ref().and_then(|search| {
+        let text: Vec<&str> = search.terms().iter().map(SearchTerm::text).collect();
+        pinyin::search_key(&text.join(" "))
+    });
+    let search_condition: String;
+    let category_condition = format!(
+        "id IN (SELECT book_id FROM book_categories WHERE category_id IN ({}))",
+        CATEGORY_SUBTREE
+    );
+    let collapse_condition: String;
     let mut conditions = Vec::new();
     let mut params: Vec<String> = Vec::new();
 
+    if let Some(search) = &search {
+        let text_condition = match &index_hits {
+            Some(hits) => {
+                params.extend(hits.iter().map(|(id, _)| id.clone()));
+                index_condition.as_str()
+            }
+            None => {
+                params.push(search.boolean_mode());
+                SEARCH_CONDITION
+            }
+        };
+        search_condition = match &pinyin_key {
+            Some(key) => {
+                let [contains, prefix] = pinyin_patterns(key);
+                params.extend([contains.clone(), prefix.clone(), contains, prefix]);
+                format!(
+                    "({} OR title_pinyin LIKE ? OR title_initials LIKE ? OR author_pinyin LIKE ? OR author_initials LIKE ?)",
+                    text_condition
+                )
+            }
+            None => text_condition.to_string(),
+        };
+        conditions.push(search_condition.as_str());
+    }
     if let Some(id) = &query.id {
         conditions.push("id = ?");
         params.push(id.clone());
     }
     if let Some(title) = &query.title {
-        conditions.push("title LIKE ?");
-        params.push(format!("%{}%", title));
+        match pinyin::search_key(title) {
+            Some(key) => {
+                conditions.push("(title LIKE ? OR title_pinyin LIKE ? OR title_initials LIKE ?)");
+                params.push(format!("%{}%", title));
+                params.extend(pinyin_patterns(&key));
+            }
+            None => {
+                conditions.push("title LIKE ?");
+                params.push(format!("%{}%", title));
+            }
+        }
     }
     if let Some(author) = &query.author {
-        conditions.push("author LIKE ?");
-        params.push(format!("%{}%", author));
+        match pinyin::search_key(author) {
+            Some(key) => {
+                conditions.push("(author LIKE ? OR author_pinyin LIKE ? OR author_initials LIKE ?)");
+                params.push(format!("%{}%", author));
+                params.extend(pinyin_patterns(&key));
+            }
+            None => {
+                conditions.push("author LIKE ?");
+                params.push(format!("%{}%", author));
+            }
+        }
+    }
+    if let Some(isbn) = &query.isbn {
+        // 任意格式的 ISBN 都按规范化后的 13 位形式匹配
+        conditions.push("isbn = ?");
+        params.push(parse_isbn(isbn)?.to_string());
+    }
+    if let Some(author_id) = &query.author_id {
+        conditions.push("id IN (SELECT book_id FROM book_authors WHERE author_id = ?)");
+        params.push(author_id.clone());
+    }
+    if let Some(publisher) = &query.publisher {
+        conditions.push("publisher LIKE ?");
+        params.push(format!("%{}%", publisher));
+    }
+    if let Some(language) = &query.language {
+        conditions.push("language = ?");
+        params.push(language.to_lowercase());
+    }
+    if let Some(year_from) = query.year_from {
+        conditions.push("publication_year >= ?");
+        params.push(year_from.to_string());
+    }
+    if let Some(year_to) = query.year_to {
+        conditions.push("publication_year <= ?");
+        params.push(year_to.to_string());
+    }
+    if let Some(branch_id) = &query.branch_id {
+        conditions.push("id IN (SELECT book_id FROM items WHERE current_branch_id = ? AND status <> ?)");
+        params.push(branch_id.clone());
+        params.push(ItemStatus::Withdrawn.as_str().to_string());
+    }
+    if let Some(category_id) = &query.category_id {
+        conditions.push(category_condition.as_str());
+        params.push(category_id.clone());
+    }
+    if let Some(work_id) = &query.work_id {
+        conditions.push("work_id = ?");
+        params.push(work_id.clone());
+    }
+    if let Some(series_id) = &query.series_id {
+        conditions.push("series_id = ?");
+        params.push(series_id.clone());
+    }
+    if let Some(r#type) = &query.r#type {
+        conditions.push("type = ?");
+        params.push(r#type.clone());
+    }
+    if let Some(decade) = query.decade {
+        conditions.push("publication_year BETWEEN ? AND ?");
+        params.push(decade.to_string());
+        params.push((decade + 9).to_string());
+    }
+    if let Some(available) = query.available {
+        conditions.push(if available {
+            "id IN (SELECT book_id FROM items WHERE status = ?)"
+        } else {
+            "id NOT IN (SELECT book_id FROM items WHERE status = ?)"
+        });
+        params.push(ItemStatus::Available.as_str().to_string());
+    }
+    if query.collapse {
+        // 每个作品只保留满足其余条件的版本中出版年最新的一条，子查询重复一遍过滤条件及参数
+        let inner_conditions: String = conditions.iter().map(|condition| format!(" AND {}", condition)).collect();
+        collapse_condition = format!(
+            "(work_id IS NULL OR id = (SELECT id FROM books WHERE work_id = b.work_id{} \
+             ORDER BY publication_year IS NULL, publication_year DESC, created_at DESC, id LIMIT 1))",
+            inner_conditions
+        );
+        conditions.push(collapse_condition.as_str());
+        params.extend_from_within(..);
     }
 
     let where_clause = if conditions.is_empty() {
@@ -189,50 +723,185 @@ pub async fn list_books(
         format!("WHERE {}", conditions.join(" AND "))
     };
 
-    // 构建查询语句
+    // 使用搜索索引时相关度来自索引，按命中顺序排序；FIELD 按倒序传入，只由拼音命中的图书（FIELD 为 0）排在最后
+    let mut order_params: Vec<String> = Vec::new();
+    let order_clause = match (sort, &index_hits) {
+        (BookSort::CreatedAt, _) => "created_at DESC".to_string(),
+        (BookSort::CallNumber, _) => "call_number_sort IS NULL, call_number_sort, created_at DESC".to_string(),
+        (BookSort::Relevance, Some(hits)) if !hits.is_empty() => {
+            order_params.extend(hits.iter().rev().map(|(id, _)| id.clone()));
+            format!("FIELD(id, {}) DESC, created_at DESC", vec!["?"; hits.len()].join(", "))
+        }
+        (BookSort::Relevance, Some(_)) => "created_at DESC".to_string(),
+        (BookSort::Relevance, None) => "score DESC, created_at DESC".to_string(),
+    };
+    let sql_search = search.is_some() && index_hits.is_none();
+
+    // 构建查询语句；检索时相关度的三个参数排在过滤条件之前
     let query_str = format!(
-        "SELECT * FROM books {} ORDER BY created_at DESC LIMIT ? OFFSET ?",
-        where_clause
+        "SELECT b.*, {} AS score FROM books b {} ORDER BY {} LIMIT ? OFFSET ?",
+        if sql_search { SEARCH_SCORE } else { "NULL" },
+        where_clause,
+        order_clause
     );
 
-    // 添加分页参数
-    params.push(page_size.to_string());
-    params.push(offset.to_string());
-
     // 执行查询
-    let mut query_builder = sqlx::query_as::<_, Book>(&query_str);
-    for param in &params {
+    let mut query_builder = sqlx::query_as::<_, ScoredBook>(&query_str);
+    if let (Some(search), true) = (&search, sql_search) {
+        let boolean_mode = search.boolean_mode();
+        for _ in 0..3 {
+            query_builder = query_builder.bind(boolean_mode.clone());
+        }
+    }
+    for param in params.iter().chain(&order_params) {
         query_builder = query_builder.bind(param);
     }
-    let books = query_builder
+    let scored_books = query_builder
+        .bind(page_size)
+        .bind(offset)
         .fetch_all(pool.get_ref())
-        .await;
-
-    match books {
-        Ok(books) => {
-            let count_query = format!(
-                "SELECT COUNT(*) as total FROM books {}",
-                where_clause
-            );
-            let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
-            for param in &params[..params.len()-2] { // 移除分页参数
-                count_query_builder = count_query_builder.bind(param);
-            }
-            let total: i64 = count_query_builder
-                .fetch_one(pool.get_ref())
-                .await
-                .unwrap_or(0);
-
-            HttpResponse::Ok().json(serde_json::json!({
-                "total": total,
-                "page_no": page_no,
-                "page_size": page_size,
-                "data": books
-            }))
-        }
-        Err(e) => {
-            eprintln!("Error fetching books: {}", e);
-            HttpResponse::InternalServerError().finish()
+        .await?;
+
+    let count_query = format!(
+        "SELECT COUNT(*) as total FROM books b {}",
+        where_clause
+    );
+    let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
+    for param in &params {
+        count_query_builder = count_query_builder.bind(param);
+    }
+    let total: i64 = count_query_builder
+        .fetch_one(pool.get_ref())
+        .await?;
+
+    let facets = if query.facets {
+        Some(book_facets(pool.get_ref(), &where_clause, &params, query.category_id.as_deref()).await?)
+    } else {
+        None
+    };
+
+    let index_scores: HashMap<&str, f64> = index_hits
+        .iter()
+        .flatten()
+        .map(|(id, score)| (id.as_str(), f64::from(*score)))
+        .collect();
+    let (books, scores): (Vec<Book>, Vec<Option<f64>>) = scored_books
+        .into_iter()
+        .map(|scored| {
+            let score = scored.score.or_else(|| index_scores.get(scored.book.id.as_str()).copied());
+            (scored.book, score)
+        })
+        .unzip();
+    let data = book_responses(pool.get_ref(), books).await?;
+
+    // 检索结果附带相关度和高亮片段
+    Ok(match &search {
+        Some(search) => {
+            let hits: Vec<SearchHit> = data
+                .into_iter()
+                .zip(scores)
+                .map(|(book, score)| SearchHit::new(book, score.unwrap_or_default(), search))
+                .collect();
+            page_response(total, page_no, page_size, hits, facets)
         }
+        None => page_response(total, page_no, page_size, data, facets),
+    })
+}
+
+fn page_response<T: Serialize>(
+    total: i64,
+    page_no: i64,
+    page_size: i64,
+    data: Vec<T>,
+    facets: Option<BookFacets>,
+) -> HttpResponse {
+    let mut body = serde_json::json!({
+        "total": total,
+        "page_no": page_no,
+        "page_size": page_size,
+        "data": data
+    });
+    if let Some(facets) = facets {
+        body["facets"] = serde_json::json!(facets);
+    }
+    HttpResponse::Ok().json(body)
+}
+
+/// 作者分面最多返回的取值数
+const AUTHOR_FACET_LIMIT: i64 = 20;
+
+/// 按列表的过滤条件统计各分面；where_clause 以 books b 为外层别名，params 为其参数
+async fn book_facets(
+    pool: &MySqlPool,
+    where_clause: &str,
+    params: &[String],
+    category_id: Option<&str>,
+) -> AppResult<BookFacets> {
+    let value_facet = |expression: &str| {
+        format!(
+            "SELECT {} AS value, CAST(NULL AS CHAR) AS name, COUNT(*) AS count
+             FROM books b {}
+             GROUP BY value
+             HAVING value IS NOT NULL
+             ORDER BY count DESC, value",
+            expression, where_clause
+        )
+    };
+
+    let type_query = value_facet("type");
+    let r#type = fetch_facet(pool, sqlx::query_as(&type_query), params).await?;
+    let language_query = value_facet("language");
+    let language = fetch_facet(pool, sqlx::query_as(&language_query), params).await?;
+    let decade_query = value_facet("CAST(publication_year DIV 10 * 10 AS CHAR)");
+    let decade = fetch_facet(pool, sqlx::query_as(&decade_query), params).await?;
+    let availability_query =
+        value_facet("IF(id IN (SELECT book_id FROM items WHERE status = ?), 'true', 'false')");
+    let availability = fetch_facet(
+        pool,
+        sqlx::query_as(&availability_query).bind(ItemStatus::Available.as_str()),
+        params,
+    )
+    .await?;
+
+    // 已选分类时统计其子分类，否则统计顶级分类；每个分类的数量包含其子孙分类中的图书
+    let category_query = format!(
+        "WITH RECURSIVE tree (root_id, id) AS (
+             SELECT id, id FROM categories WHERE parent_id <=> ?
+             UNION ALL
+             SELECT t.root_id, c.id FROM categories c JOIN tree t ON c.parent_id = t.id
+         )
+         SELECT r.id AS value, r.name AS name, COUNT(DISTINCT bc.book_id) AS count
+         FROM tree t
+         JOIN categories r ON r.id = t.root_id
+         JOIN book_categories bc ON bc.category_id = t.id
+         WHERE bc.book_id IN (SELECT id FROM books b {})
+         GROUP BY r.id, r.name
+         ORDER BY count DESC, r.name",
+        where_clause
+    );
+    let category = fetch_facet(pool, sqlx::query_as(&category_query).bind(category_id), params).await?;
+
+    let author_query = format!(
+        "SELECT a.id AS value, a.name AS name, COUNT(DISTINCT ba.book_id) AS count
+         FROM book_authors ba JOIN authors a ON a.id = ba.author_id
+         WHERE ba.book_id IN (SELECT id FROM books b {})
+         GROUP BY a.id, a.name
+         ORDER BY count DESC, a.name
+         LIMIT {}",
+        where_clause, AUTHOR_FACET_LIMIT
+    );
+    let author = fetch_facet(pool, sqlx::query_as(&author_query), params).await?;
+
+    Ok(BookFacets { r#type, category, author, language, decade, availability })
+}
+
+async fn fetch_facet<'q>(
+    pool: &MySqlPool,
+    mut query_builder: QueryAs<'q, MySql, FacetCount, MySqlArguments>,
+    params: &'q [String],
+) -> Result<Vec<FacetCount>, sqlx::Error> {
+    for param in params {
+        query_builder = query_builder.bind(param);
     }
-} 
\ No newline at end of file
+    query_builder.fetch_all(pool).await
+}
\ No newline at end of file
@@ -0,0 +1,164 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
+use crate::models::branch::{Branch, CreateBranch, UpdateBranch};
+
+pub async fn list_branches(pool: web::Data<MySqlPool>) -> AppResult<HttpResponse> {
+    let branches = sqlx::query_as!(
+        Branch,
+        r#"
+        SELECT * FROM branches ORDER BY code
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": branches.len(),
+        "data": branches
+    })))
+}
+
+pub async fn get_branch(
+    pool: web::Data<MySqlPool>,
+    branch_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let branch = fetch_branch(pool.get_ref(), &branch_id.to_string()).await?;
+    Ok(HttpResponse::Ok().json(branch))
+}
+
+pub async fn create_branch(
+    pool: web::Data<MySqlPool>,
+    branch: web::Json<CreateBranch>,
+) -> AppResult<HttpResponse> {
+    branch.validate()?;
+
+    let now = Utc::now().naive_local();
+    let new_branch = Branch {
+        id: Uuid::new_v4().to_string(),
+        code: branch.code.clone(),
+        name: branch.name.trim().to_string(),
+        address: branch.address.clone(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO branches (id, code, name, address, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        new_branch.id,
+        new_branch.code,
+        new_branch.name,
+        new_branch.address,
+        new_branch.created_at,
+        new_branch.updated_at
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Created().json(new_branch))
+}
+
+pub async fn update_branch(
+    pool: web::Data<MySqlPool>,
+    branch_id: web::Path<Uuid>,
+    update: web::Json<UpdateBranch>,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+    let branch_id = branch_id.to_string();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE branches
+        SET name = COALESCE(?, name),
+            address = COALESCE(?, address),
+            updated_at = ?
+        WHERE id = ?
+        "#,
+        update.name.as_deref().map(str::trim),
+        update.address,
+        Utc::now().naive_local(),
+        branch_id
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Branch not found"));
+    }
+
+    let branch = fetch_branch(pool.get_ref(), &branch_id).await?;
+    Ok(HttpResponse::Ok().json(branch))
+}
+
+pub async fn delete_branch(
+    pool: web::Data<MySqlPool>,
+    branch_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    // 仍有单册或调拨记录引用的分馆由外键拦截，返回 409
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM branches WHERE id = ?
+        "#,
+        branch_id.to_string()
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Branch not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+async fn fetch_branch(pool: &MySqlPool, branch_id: &str) -> AppResult<Branch> {
+    sqlx::query_as!(
+        Branch,
+        r#"
+        SELECT * FROM branches WHERE id = ?
+        "#,
+        branch_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Branch not found"))
+}
+
+/// 校验指定的分馆存在；未指定时使用最早创建的分馆（迁移时创建的总馆）
+pub(crate) async fn resolve_branch(conn: &mut MySqlConnection, branch_id: Option<&str>) -> AppResult<String> {
+    let branch: Option<String> = match branch_id {
+        Some(branch_id) => {
+            sqlx::query_scalar!(
+                r#"
+                SELECT id FROM branches WHERE id = ?
+                "#,
+                branch_id
+            )
+            .fetch_optional(&mut *conn)
+            .await?
+        }
+        None => {
+            sqlx::query_scalar!(
+                r#"
+                SELECT id FROM branches ORDER BY created_at, code LIMIT 1
+                "#
+            )
+            .fetch_optional(&mut *conn)
+            .await?
+        }
+    };
+
+    branch.ok_or_else(|| {
+        AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({ "branch_id": [{ "code": "branch", "message": "Branch not found" }] })),
+        )
+    })
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
+use crate::models::category::{BookCategory, Category, CategoryNode, CreateCategory, UpdateCategory};
+
+/// 分类及其全部子孙分类的 id，供图书列表按分类过滤时使用
+pub(crate) const CATEGORY_SUBTREE: &str = "WITH RECURSIVE subtree (id) AS (
+        SELECT id FROM categories WHERE id = ?
+        UNION ALL
+        SELECT c.id FROM categories c JOIN subtree s ON c.parent_id = s.id
+    )
+    SELECT id FROM subtree";
+
+pub async fn list_categories(pool: web::Data<MySqlPool>) -> AppResult<HttpResponse> {
+    let categories: Vec<Category> = sqlx::query_as!(
+        Category,
+        r#"
+        SELECT * FROM categories ORDER BY name
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": categories.len(),
+        "data": CategoryNode::children(None, &categories)
+    })))
+}
+
+pub async fn get_category(
+    pool: web::Data<MySqlPool>,
+    category_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let category_id = category_id.to_string();
+    let mut conn = pool.acquire().await?;
+    let category = fetch_category(&mut conn, &category_id).await?;
+
+    let query_str = format!(
+        "SELECT * FROM categories WHERE id IN ({}) AND id <> ? ORDER BY name",
+        CATEGORY_SUBTREE
+    );
+    let descendants = sqlx::query_as::<_, Category>(&query_str)
+        .bind(&category_id)
+        .bind(&category_id)
+        .fetch_all(&mut *conn)
+        .await?;
+
+    let children = CategoryNode::children(Some(&category_id), &descendants);
+    Ok(HttpResponse::Ok().json(CategoryNode { category, children }))
+}
+
+pub async fn create_category(
+    pool: web::Data<MySqlPool>,
+    category: web::Json<CreateCategory>,
+) -> AppResult<HttpResponse> {
+    category.validate()?;
+
+    let mut conn = pool.acquire().await?;
+    if let Some(parent_id) = &category.parent_id {
+        fetch_category(&mut conn, parent_id)
+            .await
+            .map_err(|_| invalid_parent("Parent category not found"))?;
+    }
+
+    let now = Utc::now().naive_local();
+    let new_category = Category {
+        id: Uuid::new_v4().to_string(),
+        parent_id: category.parent_id.clone(),
+        name: category.name.trim().to_string(),
+        description: category.description.clone(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO categories (id, parent_id, name, description, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        new_category.id,
+        new_category.parent_id,
+        new_category.name,
+        new_category.description,
+        new_category.created_at,
+        new_category.updated_at
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(HttpResponse::Created().json(new_category))
+}
+
+pub async fn update_category(
+    pool: web::Data<MySqlPool>,
+    category_id: web::Path<Uuid>,
+    update: web::Json<UpdateCategory>,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+    let category_id = category_id.to_string();
+    let mut tx = pool.begin().await?;
+
+    // 移到其他分类下时按主键顺序锁定全部分类，使并发的移动依次执行，环检测总能看到最新的树；
+    // 分类数量有限且很少移动，整表加锁的代价可以接受
+    if let Some(Some(_)) = &update.parent_id {
+        sqlx::query!(
+            r#"
+            SELECT id FROM categories ORDER BY id FOR UPDATE
+            "#
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+    }
+    fetch_category(&mut tx, &category_id).await?;
+
+    // 不能移动到自身或自己的子孙分类下，否则会形成环
+    if let Some(Some(parent_id)) = &update.parent_id {
+        fetch_category(&mut tx, parent_id)
+            .await
+            .map_err(|_| invalid_parent("Parent category not found"))?;
+
+        let query_str = format!("SELECT COUNT(*) FROM ({}) subtree WHERE id = ?", CATEGORY_SUBTREE);
+        let in_subtree: i64 = sqlx::query_scalar(&query_str)
+            .bind(&category_id)
+            .bind(parent_id)
+            .fetch_one(&mut *tx)
+            .await?;
+        if in_subtree > 0 {
+            return Err(invalid_parent("Cannot move a category under itself or its descendants"));
+        }
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE categories
+        SET name = COALESCE(?, name),
+            parent_id = IF(?, ?, parent_id),
+            description = COALESCE(?, description),
+            updated_at = ?
+        WHERE id = ?
+        "#,
+        update.name.as_deref().map(str::trim),
+        update.parent_id.is_some(),
+        update.parent_id.clone().flatten(),
+        update.description,
+        Utc::now().naive_local(),
+        category_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let category = fetch_category(&mut tx, &category_id).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(category))
+}
+
+pub async fn delete_category(
+    pool: web::Data<MySqlPool>,
+    category_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    // 仍有子分类或图书的分类由外键拦截，返回 409
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM categories WHERE id = ?
+        "#,
+        category_id.to_string()
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Category not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+async fn fetch_category(conn: &mut MySqlConnection, category_id: &str) -> AppResult<Category> {
+    sqlx::query_as!(
+        Category,
+        r#"
+        SELECT * FROM categories WHERE id = ?
+        "#,
+        category_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("Category not found"))
+}
+
+fn invalid_parent(message: &str) -> AppError {
+    AppError::validation(
+        "Request validation failed",
+        Some(serde_json::json!({ "parent_id": [{ "code": "parent", "message": message }] })),
+    )
+}
+
+/// 用给定的分类替换图书现有的分类
+pub(crate) async fn link_categories(
+    conn: &mut MySqlConnection,
+    book_id: &str,
+    category_ids: &[String],
+) -> AppResult<()> {
+    for category_id in category_ids {
+        let exists: i64 = sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) FROM categories WHERE id = ?
+            "#,
+            category_id
+        )
+        .fetch_one(&mut *conn)
+        .await?;
+        if exists == 0 {
+            return Err(AppError::validation(
+                "Request validation failed",
+                Some(serde_json::json!({
+                    "category_ids": [{ "code": "category", "message": format!("Category {} not found", category_id) }]
+                })),
+            ));
+        }
+    }
+
+    sqlx::query!(
+        r#"
+        DELETE FROM book_categories WHERE book_id = ?
+        "#,
+        book_id
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    for category_id in category_ids {
+        sqlx::query!(
+            r#"
+            INSERT INTO book_categories (book_id, category_id) VALUES (?, ?)
+            "#,
+            book_id,
+            category_id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+    Ok(())
+}
+
+/// 批量查询多本图书所属的分类
+pub(crate) async fn book_categories(
+    pool: &MySqlPool,
+    book_ids: &[&str],
+) -> Result<HashMap<String, Vec<BookCategory>>, sqlx::Error> {
+    if book_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders = vec!["?"; book_ids.len()].join(", ");
+    let query_str = format!(
+        "SELECT bc.book_id, c.id, c.name
+         FROM book_categories bc JOIN categories c ON c.id = bc.category_id
+         WHERE bc.book_id IN ({})
+         ORDER BY c.name",
+        placeholders
+    );
+
+    let mut query_builder = sqlx::query_as::<_, BookCategory>(&query_str);
+    for book_id in book_ids {
+        query_builder = query_builder.bind(book_id);
+    }
+
+    let mut categories: HashMap<String, Vec<BookCategory>> = HashMap::new();
+    for category in query_builder.fetch_all(pool).await? {
+        categories.entry(category.book_id.clone()).or_default().push(category);
+    }
+    Ok(categories)
+}
@@ -0,0 +1,243 @@
+use std::collections::HashMap;
+
+use actix_multipart::Multipart;
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use futures_util::StreamExt;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::models::cover::{is_cover_type, process_cover, BookCover, MEDIUM, SMALL};
+use crate::storage::Storage;
+
+/// 上传或替换图书封面：multipart/form-data 中的 file 字段
+pub async fn upload_cover(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    storage: web::Data<dyn Storage>,
+    book_id: web::Path<Uuid>,
+    mut payload: Multipart,
+) -> AppResult<HttpResponse> {
+    let book_id = book_id.to_string();
+    let exists: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM books WHERE id = ?
+        "#,
+        book_id
+    )
+    .fetch_one(pool.get_ref())
+    .await?;
+    if exists == 0 {
+        return Err(AppError::not_found("Book not found"));
+    }
+
+    let (content_type, data) = read_cover_field(&mut payload, config.cover_max_bytes).await?;
+
+    // 解码和缩放比较耗时，放到阻塞线程池中执行
+    let declared_type = content_type.clone();
+    let (processed, data) = web::block(move || process_cover(&declared_type, &data).map(|processed| (processed, data)))
+        .await
+        .map_err(|e| AppError::Internal(format!("Cover processing failed: {}", e)))?
+        .map_err(|e| file_error("image", &e.to_string()))?;
+
+    // 每次上传使用新的目录，替换封面后旧 URL 不会命中缓存中的旧图
+    let storage_key = format!("{}/{}", cover_prefix(&book_id), Uuid::new_v4().simple());
+    let original_key = format!("{}/original.{}", storage_key, processed.extension);
+    let small_key = format!("{}/{}.jpg", storage_key, SMALL.name);
+    let medium_key = format!("{}/{}.jpg", storage_key, MEDIUM.name);
+
+    let stored = async {
+        storage.put(&original_key, &content_type, data).await?;
+        storage.put(&small_key, "image/jpeg", processed.small).await?;
+        storage.put(&medium_key, "image/jpeg", processed.medium).await
+    }
+    .await;
+    if let Err(e) = stored {
+        remove_stored(storage.get_ref(), &storage_key).await;
+        return Err(AppError::Internal(format!("Failed to store cover: {}", e)));
+    }
+
+    let cover = BookCover {
+        book_id: book_id.clone(),
+        storage_key: storage_key.clone(),
+        content_type,
+        width: processed.width as i32,
+        height: processed.height as i32,
+        url: storage.url(&original_key),
+        small_url: storage.url(&small_key),
+        medium_url: storage.url(&medium_key),
+        updated_at: Utc::now().naive_local(),
+    };
+
+    let previous = match save_cover(pool.get_ref(), &cover).await {
+        Ok(previous) => previous,
+        Err(e) => {
+            remove_stored(storage.get_ref(), &storage_key).await;
+            return Err(e);
+        }
+    };
+    if let Some(previous) = previous {
+        remove_stored(storage.get_ref(), &previous).await;
+    }
+
+    Ok(HttpResponse::Ok().json(cover))
+}
+
+pub async fn delete_cover(
+    pool: web::Data<MySqlPool>,
+    storage: web::Data<dyn Storage>,
+    book_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let book_id = book_id.to_string();
+    let mut tx = pool.begin().await?;
+
+    let storage_key: Option<String> = sqlx::query_scalar!(
+        r#"
+        SELECT storage_key FROM book_covers WHERE book_id = ? FOR UPDATE
+        "#,
+        book_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    let storage_key = storage_key.ok_or_else(|| AppError::not_found("Cover not found"))?;
+
+    sqlx::query!(
+        r#"
+        DELETE FROM book_covers WHERE book_id = ?
+        "#,
+        book_id
+    )
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await?;
+
+    remove_stored(storage.get_ref(), &storage_key).await;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// 写入封面记录，返回被替换的旧封面目录
+async fn save_cover(pool: &MySqlPool, cover: &BookCover) -> AppResult<Option<String>> {
+    let mut tx = pool.begin().await?;
+
+    let previous: Option<String> = sqlx::query_scalar!(
+        r#"
+        SELECT storage_key FROM book_covers WHERE book_id = ? FOR UPDATE
+        "#,
+        cover.book_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    sqlx::query!(
+        r#"
+        INSERT INTO book_covers
+            (book_id, storage_key, content_type, width, height, url, small_url, medium_url, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        ON DUPLICATE KEY UPDATE
+            storage_key = VALUES(storage_key),
+            content_type = VALUES(content_type),
+            width = VALUES(width),
+            height = VALUES(height),
+            url = VALUES(url),
+            small_url = VALUES(small_url),
+            medium_url = VALUES(medium_url),
+            updated_at = VALUES(updated_at)
+        "#,
+        cover.book_id,
+        cover.storage_key,
+        cover.content_type,
+        cover.width,
+        cover.height,
+        cover.url,
+        cover.small_url,
+        cover.medium_url,
+        cover.updated_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(previous)
+}
+
+/// 读取 file 字段，边读边检查大小，超限时立即返回
+async fn read_cover_field(payload: &mut Multipart, max_bytes: usize) -> AppResult<(String, Vec<u8>)> {
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|e| file_error("multipart", &e.to_string()))?;
+        if field.name() != Some("file") {
+            continue;
+        }
+
+        let content_type = field
+            .content_type()
+            .map(|mime| mime.essence_str().to_string())
+            .unwrap_or_default();
+        if !is_cover_type(&content_type) {
+            return Err(file_error(
+                "content_type",
+                &format!("Unsupported content type '{}', expected image/jpeg, image/png or image/webp", content_type),
+            ));
+        }
+
+        let mut data = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| file_error("multipart", &e.to_string()))?;
+            if data.len() + chunk.len() > max_bytes {
+                return Err(file_error("size", &format!("Cover image must not exceed {} bytes", max_bytes)));
+            }
+            data.extend_from_slice(&chunk);
+        }
+        if data.is_empty() {
+            return Err(file_error("required", "Cover image is empty"));
+        }
+        return Ok((content_type, data));
+    }
+
+    Err(file_error("required", "Missing multipart field 'file'"))
+}
+
+fn file_error(code: &str, message: &str) -> AppError {
+    AppError::validation(
+        "Request validation failed",
+        Some(serde_json::json!({ "file": [{ "code": code, "message": message }] })),
+    )
+}
+
+/// 图书全部封面文件所在的存储目录
+pub(crate) fn cover_prefix(book_id: &str) -> String {
+    format!("covers/{}", book_id)
+}
+
+/// 删除存储中的文件；失败只记录日志，残留文件不影响数据一致性
+pub(crate) async fn remove_stored(storage: &dyn Storage, prefix: &str) {
+    if let Err(e) = storage.delete_prefix(prefix).await {
+        log::warn!("Failed to remove stored files under {}: {}", prefix, e);
+    }
+}
+
+/// 批量查询多本图书的封面
+pub(crate) async fn book_covers(
+    pool: &MySqlPool,
+    book_ids: &[&str],
+) -> Result<HashMap<String, BookCover>, sqlx::Error> {
+    if book_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders = vec!["?"; book_ids.len()].join(", ");
+    let query_str = format!("SELECT * FROM book_covers WHERE book_id IN ({})", placeholders);
+
+    let mut query_builder = sqlx::query_as::<_, BookCover>(&query_str);
+    for book_id in book_ids {
+        query_builder = query_builder.bind(book_id);
+    }
+
+    Ok(query_builder
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|cover| (cover.book_id.clone(), cover))
+        .collect())
+}
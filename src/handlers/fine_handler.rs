@@ -0,0 +1,291 @@
+use actix_web::{web, HttpResponse};
+use chrono::{NaiveDateTime, Utc};
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::fine::{overdue_fine, FineAdjustment, FineBalance, FineKind, FineTransaction};
+use crate::models::loan::Loan;
+
+pub async fn get_balance(
+    pool: web::Data<MySqlPool>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let user_id = user_id.to_string();
+    if !caller.can_act_for(&user_id) {
+        return Err(AppError::forbidden("Cannot view another user's fines"));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let balance = fine_balance(&mut conn, &user_id).await?;
+
+    Ok(HttpResponse::Ok().json(balance))
+}
+
+pub async fn list_fines(
+    pool: web::Data<MySqlPool>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let user_id = user_id.to_string();
+    if !caller.can_act_for(&user_id) {
+        return Err(AppError::forbidden("Cannot view another user's fines"));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let transactions = sqlx::query_as!(
+        FineTransaction,
+        r#"
+        SELECT * FROM fine_transactions WHERE user_id = ? ORDER BY created_at DESC, id DESC
+        "#,
+        user_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+    let balance = fine_balance(&mut conn, &user_id).await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "balance_cents": balance.balance_cents,
+        "total": transactions.len(),
+        "data": transactions
+    })))
+}
+
+pub async fn record_payment(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+    payment: web::Json<FineAdjustment>,
+) -> AppResult<HttpResponse> {
+    record_adjustment(&pool, &config, &caller, &user_id.to_string(), &payment, FineKind::Payment).await
+}
+
+pub async fn waive_fine(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+    waiver: web::Json<FineAdjustment>,
+) -> AppResult<HttpResponse> {
+    record_adjustment(&pool, &config, &caller, &user_id.to_string(), &waiver, FineKind::Waiver).await
+}
+
+async fn record_adjustment(
+    pool: &MySqlPool,
+    config: &AppConfig,
+    caller: &AuthenticatedUser,
+    user_id: &str,
+    adjustment: &FineAdjustment,
+    kind: FineKind,
+) -> AppResult<HttpResponse> {
+    adjustment.validate()?;
+
+    let mut tx = pool.begin().await?;
+    assess_user_fines(&mut tx, user_id, config).await?;
+
+    if let Some(loan_id) = &adjustment.loan_id {
+        let loan_user: Option<String> = sqlx::query_scalar!(
+            r#"
+            SELECT user_id FROM loans WHERE id = ?
+            "#,
+            loan_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        if loan_user.as_deref() != Some(user_id) {
+            return Err(AppError::not_found("Loan not found"));
+        }
+    }
+
+    // 缴费和减免都不能超过当前欠款
+    let balance = fine_balance(&mut tx, user_id).await?;
+    if adjustment.amount_cents > balance.balance_cents {
+        return Err(AppError::Conflict(
+            "Amount exceeds the outstanding balance".to_string(),
+            Some(serde_json::json!({ "balance_cents": balance.balance_cents })),
+        ));
+    }
+
+    let transaction = FineTransaction {
+        id: Uuid::new_v4().to_string(),
+        user_id: user_id.to_string(),
+        loan_id: adjustment.loan_id.clone(),
+        kind: kind.as_str().to_string(),
+        amount_cents: -adjustment.amount_cents,
+        note: Some(adjustment.note.trim().to_string()),
+        recorded_by: Some(caller.user_id.clone()),
+        created_at: Utc::now().naive_local(),
+    };
+    insert_transaction(&mut tx, &transaction).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Created().json(transaction))
+}
+
+/// 为所有未归还的逾期借阅补记罚款，供定时任务使用；查询余额和流水的接口只读不写
+pub async fn assess_overdue_fines(pool: &MySqlPool, config: &AppConfig) -> AppResult<()> {
+    let user_ids: Vec<String> = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT user_id FROM loans WHERE returned_at IS NULL AND due_at < ?
+        "#,
+        Utc::now().naive_local()
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for user_id in user_ids {
+        let mut tx = pool.begin().await?;
+        assess_user_fines(&mut tx, &user_id, config).await?;
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// 锁定用户行；所有罚款流水的写入都先获取该锁，避免重复计罚
+async fn lock_user(conn: &mut MySqlConnection, user_id: &str) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+        SELECT id FROM users WHERE id = ? FOR UPDATE
+        "#,
+        user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("User not found"))?;
+    Ok(())
+}
+
+/// 按当前时间为用户所有逾期借阅（含已归还和未归还）补记罚款
+pub(crate) async fn assess_user_fines(
+    conn: &mut MySqlConnection,
+    user_id: &str,
+    config: &AppConfig,
+) -> AppResult<()> {
+    lock_user(conn, user_id).await?;
+
+    let now = Utc::now().naive_local();
+    let loans = sqlx::query_as!(
+        Loan,
+        r#"
+        SELECT * FROM loans WHERE user_id = ? AND due_at < COALESCE(returned_at, ?)
+        "#,
+        user_id,
+        now
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    for loan in &loans {
+        charge_loan(conn, loan, now, config).await?;
+    }
+    Ok(())
+}
+
+/// 为单笔借阅补记罚款，借还书时调用
+pub(crate) async fn assess_loan_fine(
+    conn: &mut MySqlConnection,
+    loan: &Loan,
+    config: &AppConfig,
+) -> AppResult<()> {
+    lock_user(conn, &loan.user_id).await?;
+    charge_loan(conn, loan, Utc::now().naive_local(), config).await
+}
+
+/// 应收罚款与已记罚款的差额记为一笔新的 charge，重复调用不会多收
+async fn charge_loan(
+    conn: &mut MySqlConnection,
+    loan: &Loan,
+    now: NaiveDateTime,
+    config: &AppConfig,
+) -> AppResult<()> {
+    let end = loan.returned_at.unwrap_or(now);
+    let daily_rate = loan
+        .fine_rate_cents
+        .map(i64::from)
+        .unwrap_or(config.fine_daily_rate_cents);
+    let due = overdue_fine(loan.due_at, end, daily_rate, config.fine_max_per_item_cents);
+    if due == 0 {
+        return Ok(());
+    }
+
+    let charged: Option<i64> = sqlx::query_scalar!(
+        r#"
+        SELECT CAST(COALESCE(SUM(amount_cents), 0) AS SIGNED)
+        FROM fine_transactions WHERE loan_id = ? AND kind = ?
+        "#,
+        loan.id,
+        FineKind::Charge.as_str()
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let outstanding = due - charged.unwrap_or(0);
+    if outstanding <= 0 {
+        return Ok(());
+    }
+
+    let transaction = FineTransaction {
+        id: Uuid::new_v4().to_string(),
+        user_id: loan.user_id.clone(),
+        loan_id: Some(loan.id.clone()),
+        kind: FineKind::Charge.as_str().to_string(),
+        amount_cents: outstanding,
+        note: Some("Overdue fine".to_string()),
+        recorded_by: None,
+        created_at: now,
+    };
+    insert_transaction(conn, &transaction).await
+}
+
+async fn insert_transaction(conn: &mut MySqlConnection, transaction: &FineTransaction) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+        INSERT INTO fine_transactions (id, user_id, loan_id, kind, amount_cents, note, recorded_by, created_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        transaction.id,
+        transaction.user_id,
+        transaction.loan_id,
+        transaction.kind,
+        transaction.amount_cents,
+        transaction.note,
+        transaction.recorded_by,
+        transaction.created_at
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+pub(crate) async fn fine_balance(conn: &mut MySqlConnection, user_id: &str) -> Result<FineBalance, sqlx::Error> {
+    let totals = sqlx::query!(
+        r#"
+        SELECT
+            CAST(COALESCE(SUM(amount_cents), 0) AS SIGNED) AS balance,
+            CAST(COALESCE(SUM(CASE WHEN kind = ? THEN amount_cents END), 0) AS SIGNED) AS charged,
+            CAST(COALESCE(SUM(CASE WHEN kind = ? THEN -amount_cents END), 0) AS SIGNED) AS paid,
+            CAST(COALESCE(SUM(CASE WHEN kind = ? THEN -amount_cents END), 0) AS SIGNED) AS waived
+        FROM fine_transactions WHERE user_id = ?
+        "#,
+        FineKind::Charge.as_str(),
+        FineKind::Payment.as_str(),
+        FineKind::Waiver.as_str(),
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(FineBalance {
+        user_id: user_id.to_string(),
+        balance_cents: totals.balance.unwrap_or(0),
+        charged_cents: totals.charged.unwrap_or(0),
+        paid_cents: totals.paid.unwrap_or(0),
+        waived_cents: totals.waived.unwrap_or(0),
+    })
+}
@@ -0,0 +1,435 @@
+use actix_web::{web, HttpResponse};
+use chrono::{Duration, Utc};
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::hold::{CreateHold, Hold, HoldResponse, HoldStatus};
+use crate::models::item::ItemStatus;
+
+pub async fn place_hold(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    hold: web::Json<CreateHold>,
+) -> AppResult<HttpResponse> {
+    let user_id = hold.user_id.clone().unwrap_or_else(|| caller.user_id.clone());
+    if !caller.can_act_for(&user_id) {
+        return Err(AppError::forbidden("Cannot place holds for another user"));
+    }
+
+    let mut tx = pool.begin().await?;
+    lock_book(&mut tx, &hold.book_id).await?;
+    refresh_holds(&mut tx, &hold.book_id, config.hold_pickup_days).await?;
+
+    if available_copies(&mut tx, &hold.book_id).await? > 0 {
+        return Err(AppError::conflict("Copies are available, check the book out instead"));
+    }
+
+    let on_loan: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM loans WHERE book_id = ? AND user_id = ? AND returned_at IS NULL
+        "#,
+        hold.book_id,
+        user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if on_loan > 0 {
+        return Err(AppError::conflict("User already has this book on loan"));
+    }
+
+    let existing: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM holds WHERE book_id = ? AND user_id = ? AND status IN (?, ?)
+        "#,
+        hold.book_id,
+        user_id,
+        HoldStatus::Waiting.as_str(),
+        HoldStatus::Ready.as_str()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if existing > 0 {
+        return Err(AppError::conflict("User already has an active hold on this book"));
+    }
+
+    let now = Utc::now().naive_local();
+    let new_hold = Hold {
+        id: Uuid::new_v4().to_string(),
+        book_id: hold.book_id.clone(),
+        user_id,
+        item_id: None,
+        status: HoldStatus::Waiting.as_str().to_string(),
+        ready_at: None,
+        expires_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO holds (id, book_id, user_id, status, ready_at, expires_at, created_at, updated_at)
+        VALUES (?, ?, ?, ?, NULL, NULL, ?, ?)
+        "#,
+        new_hold.id,
+        new_hold.book_id,
+        new_hold.user_id,
+        new_hold.status,
+        new_hold.created_at,
+        new_hold.updated_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let response = with_position(&mut tx, new_hold).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+pub async fn get_hold(
+    pool: web::Data<MySqlPool>,
+    caller: AuthenticatedUser,
+    hold_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let mut conn = pool.acquire().await?;
+    let hold = fetch_hold(&mut conn, &hold_id.to_string()).await?;
+    if !caller.can_act_for(&hold.user_id) {
+        return Err(AppError::forbidden("Cannot view another user's holds"));
+    }
+
+    Ok(HttpResponse::Ok().json(with_position(&mut conn, hold).await?))
+}
+
+pub async fn cancel_hold(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    hold_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let hold_id = hold_id.to_string();
+    let mut tx = pool.begin().await?;
+
+    let hold = fetch_hold(&mut tx, &hold_id).await?;
+    if !caller.can_act_for(&hold.user_id) {
+        return Err(AppError::forbidden("Cannot cancel another user's holds"));
+    }
+
+    lock_book(&mut tx, &hold.book_id).await?;
+    let now = Utc::now().naive_local();
+    let result = sqlx::query!(
+        r#"
+        UPDATE holds SET status = ?, updated_at = ? WHERE id = ? AND status IN (?, ?)
+        "#,
+        HoldStatus::Cancelled.as_str(),
+        now,
+        hold_id,
+        HoldStatus::Waiting.as_str(),
+        HoldStatus::Ready.as_str()
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict("Hold is no longer active"));
+    }
+
+    // 取消已到书的预约会空出副本，顺延给下一位
+    allocate_holds(&mut tx, &hold.book_id, config.hold_pickup_days).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn list_user_holds(
+    pool: web::Data<MySqlPool>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let user_id = user_id.to_string();
+    if !caller.can_act_for(&user_id) {
+        return Err(AppError::forbidden("Cannot view another user's holds"));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let holds = sqlx::query_as!(
+        Hold,
+        r#"
+        SELECT * FROM holds WHERE user_id = ? ORDER BY created_at DESC
+        "#,
+        user_id
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let mut data = Vec::with_capacity(holds.len());
+    for hold in holds {
+        data.push(with_position(&mut conn, hold).await?);
+    }
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": data.len(),
+        "data": data
+    })))
+}
+
+pub async fn list_ready_holds(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+) -> AppResult<HttpResponse> {
+    process_expired_holds(pool.get_ref(), config.hold_pickup_days).await?;
+
+    let holds = sqlx::query_as!(
+        Hold,
+        r#"
+        SELECT * FROM holds WHERE status = ? ORDER BY expires_at ASC
+        "#,
+        HoldStatus::Ready.as_str()
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": holds.len(),
+        "data": holds
+    })))
+}
+
+/// 处理所有过期未取的预约，供定时任务和留书列表使用
+pub async fn process_expired_holds(pool: &MySqlPool, pickup_days: i64) -> AppResult<()> {
+    let now = Utc::now().naive_local();
+    let book_ids: Vec<String> = sqlx::query_scalar!(
+        r#"
+        SELECT DISTINCT book_id FROM holds WHERE status = ? AND expires_at <= ?
+        "#,
+        HoldStatus::Ready.as_str(),
+        now
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for book_id in book_ids {
+        let mut tx = pool.begin().await?;
+        lock_book(&mut tx, &book_id).await?;
+        refresh_holds(&mut tx, &book_id, pickup_days).await?;
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// 锁定图书行；借还书和预约变更都先获取该锁，保证可借数量的计算不会并发冲突
+pub(crate) async fn lock_book(conn: &mut MySqlConnection, book_id: &str) -> AppResult<()> {
+    sqlx::query!(
+        r#"
+        SELECT id FROM books WHERE id = ? FOR UPDATE
+        "#,
+        book_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("Book not found"))?;
+    Ok(())
+}
+
+/// 可借副本数 = 在架可借的单册 - 已留书待取的预约
+pub(crate) async fn available_copies(conn: &mut MySqlConnection, book_id: &str) -> Result<i64, sqlx::Error> {
+    let available: Option<i64> = sqlx::query_scalar!(
+        r#"
+        SELECT CAST(
+            (SELECT COUNT(*) FROM items i WHERE i.book_id = ? AND i.status = ?)
+            - (SELECT COUNT(*) FROM holds h WHERE h.book_id = ? AND h.status = ?)
+            AS SIGNED)
+        "#,
+        book_id,
+        ItemStatus::Available.as_str(),
+        book_id,
+        HoldStatus::Ready.as_str()
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(available.unwrap_or(0))
+}
+
+/// 将到期未取的预约标记为过期，并把空出的副本分配给队列中的下一位
+pub(crate) async fn refresh_holds(
+    conn: &mut MySqlConnection,
+    book_id: &str,
+    pickup_days: i64,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now().naive_local();
+    sqlx::query!(
+        r#"
+        UPDATE holds SET status = ?, updated_at = ?
+        WHERE book_id = ? AND status = ? AND expires_at <= ?
+        "#,
+        HoldStatus::Expired.as_str(),
+        now,
+        book_id,
+        HoldStatus::Ready.as_str(),
+        now
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    allocate_holds(conn, book_id, pickup_days).await
+}
+
+/// 有空闲副本时按排队顺序为预约留书，并设置取书期限
+pub(crate) async fn allocate_holds(
+    conn: &mut MySqlConnection,
+    book_id: &str,
+    pickup_days: i64,
+) -> Result<(), sqlx::Error> {
+    let free = available_copies(conn, book_id).await?;
+    if free <= 0 {
+        return Ok(());
+    }
+
+    let next: Vec<String> = sqlx::query_scalar!(
+        r#"
+        SELECT id FROM holds WHERE book_id = ? AND status = ?
+        ORDER BY created_at ASC, id ASC
+        LIMIT ?
+        "#,
+        book_id,
+        HoldStatus::Waiting.as_str(),
+        free
+    )
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let now = Utc::now().naive_local();
+    let expires_at = now + Duration::days(pickup_days);
+    for hold_id in next {
+        sqlx::query!(
+            r#"
+            UPDATE holds SET status = ?, ready_at = ?, expires_at = ?, updated_at = ? WHERE id = ?
+            "#,
+            HoldStatus::Ready.as_str(),
+            now,
+            expires_at,
+            now,
+            hold_id
+        )
+        .execute(&mut *conn)
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// 调拨到馆后把该单册留给申请的读者：已有排队或待取预约的转为待取并指定单册，没有则新建一条待取预约
+pub(crate) async fn reserve_for_patron(
+    conn: &mut MySqlConnection,
+    book_id: &str,
+    item_id: &str,
+    user_id: &str,
+    pickup_days: i64,
+) -> Result<(), sqlx::Error> {
+    if available_copies(conn, book_id).await? <= 0 {
+        return Ok(());
+    }
+
+    let active: Option<(String, String)> = sqlx::query_as(
+        "SELECT id, status FROM holds WHERE book_id = ? AND user_id = ? AND status IN (?, ?) LIMIT 1",
+    )
+    .bind(book_id)
+    .bind(user_id)
+    .bind(HoldStatus::Waiting.as_str())
+    .bind(HoldStatus::Ready.as_str())
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let now = Utc::now().naive_local();
+    let expires_at = now + Duration::days(pickup_days);
+    match active {
+        Some((hold_id, status)) if status == HoldStatus::Ready.as_str() => {
+            sqlx::query!(
+                r#"
+                UPDATE holds SET item_id = ?, updated_at = ? WHERE id = ?
+                "#,
+                item_id,
+                now,
+                hold_id
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        Some((hold_id, _)) => {
+            sqlx::query!(
+                r#"
+                UPDATE holds SET status = ?, item_id = ?, ready_at = ?, expires_at = ?, updated_at = ? WHERE id = ?
+                "#,
+                HoldStatus::Ready.as_str(),
+                item_id,
+                now,
+                expires_at,
+                now,
+                hold_id
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+        None => {
+            sqlx::query!(
+                r#"
+                INSERT INTO holds (id, book_id, user_id, item_id, status, ready_at, expires_at, created_at, updated_at)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                "#,
+                Uuid::new_v4().to_string(),
+                book_id,
+                user_id,
+                item_id,
+                HoldStatus::Ready.as_str(),
+                now,
+                expires_at,
+                now,
+                now
+            )
+            .execute(&mut *conn)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn fetch_hold(conn: &mut MySqlConnection, hold_id: &str) -> AppResult<Hold> {
+    sqlx::query_as!(
+        Hold,
+        r#"
+        SELECT * FROM holds WHERE id = ?
+        "#,
+        hold_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("Hold not found"))
+}
+
+async fn with_position(conn: &mut MySqlConnection, hold: Hold) -> Result<HoldResponse, sqlx::Error> {
+    if hold.status != HoldStatus::Waiting.as_str() {
+        return Ok(HoldResponse { hold, queue_position: None });
+    }
+
+    let ahead: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM holds
+        WHERE book_id = ? AND status = ?
+          AND (created_at < ? OR (created_at = ? AND id < ?))
+        "#,
+        hold.book_id,
+        HoldStatus::Waiting.as_str(),
+        hold.created_at,
+        hold.created_at,
+        hold.id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    Ok(HoldResponse { hold, queue_position: Some(ahead + 1) })
+}
@@ -0,0 +1,266 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use std::collections::HashMap;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::handlers::branch_handler::resolve_branch;
+use crate::handlers::hold_handler::{allocate_holds, lock_book};
+use crate::models::item::{placeholder_barcode, BranchCounts, CreateItem, Item, ItemCounts, ItemStatus, UpdateItem};
+
+pub async fn list_items(
+    pool: web::Data<MySqlPool>,
+    book_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let items = sqlx::query_as!(
+        Item,
+        r#"
+        SELECT * FROM items WHERE book_id = ? ORDER BY barcode
+        "#,
+        book_id.to_string()
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": items.len(),
+        "data": items
+    })))
+}
+
+pub async fn create_item(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    book_id: web::Path<Uuid>,
+    item: web::Json<CreateItem>,
+) -> AppResult<HttpResponse> {
+    item.validate()?;
+    let book_id = book_id.to_string();
+
+    let mut tx = pool.begin().await?;
+    lock_book(&mut tx, &book_id).await?;
+    let branch_id = resolve_branch(&mut tx, item.branch_id.as_deref()).await?;
+    let now = Utc::now().naive_local();
+    let new_item = Item {
+        id: Uuid::new_v4().to_string(),
+        book_id: book_id.clone(),
+        home_branch_id: branch_id.clone(),
+        current_branch_id: branch_id,
+        barcode: item.barcode.clone(),
+        shelf_location: item.shelf_location.clone(),
+        status: item.status.unwrap_or(ItemStatus::Available).as_str().to_string(),
+        acquired_at: item.acquired_at,
+        created_at: now,
+        updated_at: now,
+    };
+
+    insert_item(&mut tx, &new_item).await?;
+    // 新到的在架副本先留给排队中的预约
+    allocate_holds(&mut tx, &book_id, config.hold_pickup_days).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Created().json(new_item))
+}
+
+pub async fn get_item(
+    pool: web::Data<MySqlPool>,
+    item_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let mut conn = pool.acquire().await?;
+    let item = fetch_item(&mut conn, &item_id.to_string()).await?;
+    Ok(HttpResponse::Ok().json(item))
+}
+
+pub async fn update_item(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    item_id: web::Path<Uuid>,
+    update: web::Json<UpdateItem>,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+    let item_id = item_id.to_string();
+
+    let mut tx = pool.begin().await?;
+    let item = fetch_item(&mut tx, &item_id).await?;
+    lock_book(&mut tx, &item.book_id).await?;
+
+    // 借出或调拨中的单册只能通过还书、到馆改变状态
+    if update.status.is_some()
+        && (item.status == ItemStatus::OnLoan.as_str() || item.status == ItemStatus::InTransit.as_str())
+    {
+        return Err(AppError::conflict("Item is on loan or in transit"));
+    }
+    if let Some(home_branch_id) = &update.home_branch_id {
+        resolve_branch(&mut tx, Some(home_branch_id)).await?;
+    }
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE items
+        SET barcode = COALESCE(?, barcode),
+            shelf_location = COALESCE(?, shelf_location),
+            home_branch_id = COALESCE(?, home_branch_id),
+            status = COALESCE(?, status),
+            acquired_at = COALESCE(?, acquired_at),
+            updated_at = ?
+        WHERE id = ? AND status = ?
+        "#,
+        update.barcode,
+        update.shelf_location,
+        update.home_branch_id,
+        update.status.map(|status| status.as_str()),
+        update.acquired_at,
+        Utc::now().naive_local(),
+        item_id,
+        item.status
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict("Item was modified concurrently, please retry"));
+    }
+
+    // 修补完成、找回等恢复在架的单册留给排队中的预约
+    allocate_holds(&mut tx, &item.book_id, config.hold_pickup_days).await?;
+    let item = fetch_item(&mut tx, &item_id).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(item))
+}
+
+pub async fn delete_item(
+    pool: web::Data<MySqlPool>,
+    item_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let item_id = item_id.to_string();
+    let mut conn = pool.acquire().await?;
+    let item = fetch_item(&mut conn, &item_id).await?;
+    if item.status == ItemStatus::OnLoan.as_str() {
+        return Err(AppError::conflict("Item is on loan"));
+    }
+
+    // 有借阅记录的单册受外键保护，应改为 withdrawn
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM items WHERE id = ? AND status <> ?
+        "#,
+        item_id,
+        ItemStatus::OnLoan.as_str()
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict("Item was checked out concurrently, please retry"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+async fn fetch_item(conn: &mut MySqlConnection, item_id: &str) -> AppResult<Item> {
+    sqlx::query_as!(
+        Item,
+        r#"
+        SELECT * FROM items WHERE id = ?
+        "#,
+        item_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("Item not found"))
+}
+
+pub(crate) async fn insert_item(conn: &mut MySqlConnection, item: &Item) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO items
+            (id, book_id, home_branch_id, current_branch_id, barcode, shelf_location, status, acquired_at, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        item.id,
+        item.book_id,
+        item.home_branch_id,
+        item.current_branch_id,
+        item.barcode,
+        item.shelf_location,
+        item.status,
+        item.acquired_at,
+        item.created_at,
+        item.updated_at
+    )
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// 新建图书时按数量在指定分馆生成占位单册
+pub(crate) async fn insert_placeholder_items(
+    conn: &mut MySqlConnection,
+    book_id: &str,
+    branch_id: &str,
+    quantity: i32,
+) -> Result<(), sqlx::Error> {
+    let now = Utc::now().naive_local();
+    for n in 1..=quantity.max(0) as usize {
+        let item = Item {
+            id: Uuid::new_v4().to_string(),
+            book_id: book_id.to_string(),
+            home_branch_id: branch_id.to_string(),
+            current_branch_id: branch_id.to_string(),
+            barcode: placeholder_barcode(book_id, n),
+            shelf_location: None,
+            status: ItemStatus::Available.as_str().to_string(),
+            acquired_at: Some(now.date()),
+            created_at: now,
+            updated_at: now,
+        };
+        insert_item(conn, &item).await?;
+    }
+    Ok(())
+}
+
+/// 批量统计多本图书的馆藏册数和可借册数，并按当前所在分馆细分
+pub(crate) async fn item_counts(
+    pool: &MySqlPool,
+    book_ids: &[&str],
+) -> Result<HashMap<String, ItemCounts>, sqlx::Error> {
+    if book_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let placeholders = vec!["?"; book_ids.len()].join(", ");
+    let query_str = format!(
+        "SELECT i.book_id, b.id, b.code, b.name,
+                CAST(SUM(i.status <> ?) AS SIGNED) AS quantity,
+                CAST(SUM(i.status = ?) AS SIGNED) AS available
+         FROM items i JOIN branches b ON b.id = i.current_branch_id
+         WHERE i.book_id IN ({})
+         GROUP BY i.book_id, b.id, b.code, b.name
+         ORDER BY b.code",
+        placeholders
+    );
+
+    let mut query_builder = sqlx::query_as::<_, (String, String, String, String, i64, i64)>(&query_str)
+        .bind(ItemStatus::Withdrawn.as_str())
+        .bind(ItemStatus::Available.as_str());
+    for book_id in book_ids {
+        query_builder = query_builder.bind(book_id);
+    }
+
+    let mut counts: HashMap<String, ItemCounts> = HashMap::new();
+    for (book_id, branch_id, code, name, quantity, available) in query_builder.fetch_all(pool).await? {
+        // 只剩注销单册的分馆不再列出
+        if quantity == 0 {
+            continue;
+        }
+        let book_counts = counts.entry(book_id).or_default();
+        book_counts.quantity += quantity;
+        book_counts.available += available;
+        book_counts.branches.push(BranchCounts { branch_id, code, name, quantity, available });
+    }
+    Ok(counts)
+}
@@ -0,0 +1,380 @@
+use actix_web::{web, HttpResponse};
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::handlers::fine_handler::assess_loan_fine;
+use crate::handlers::hold_handler::{allocate_holds, available_copies, lock_book, refresh_holds};
+use crate::handlers::patron_handler::check_standing;
+use crate::handlers::policy_handler::resolve_terms;
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::hold::HoldStatus;
+use crate::models::item::ItemStatus;
+use crate::models::loan::{CreateLoan, Loan, LoanQuery, LoanStatus, RenewalRefusal};
+use crate::models::user::BlockReason;
+
+pub async fn checkout(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    loan: web::Json<CreateLoan>,
+) -> AppResult<HttpResponse> {
+    let user_id = loan.user_id.clone().unwrap_or_else(|| caller.user_id.clone());
+    if !caller.can_act_for(&user_id) {
+        return Err(AppError::forbidden("Cannot check out books for another user"));
+    }
+
+    let mut tx = pool.begin().await?;
+
+    // 锁定图书行，保证并发借阅时不会借出超过馆藏数量的副本
+    lock_book(&mut tx, &loan.book_id).await?;
+    check_standing(&mut tx, &user_id, &config, true).await?;
+    refresh_holds(&mut tx, &loan.book_id, config.hold_pickup_days).await?;
+
+    let already_borrowed: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM loans WHERE book_id = ? AND user_id = ? AND returned_at IS NULL
+        "#,
+        loan.book_id,
+        user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+
+    if already_borrowed > 0 {
+        return Err(AppError::conflict("User already has this book on loan"));
+    }
+
+    // 按读者类别和图书类型确定借期、续借次数、罚款标准和该类图书的在借上限
+    let patron_category: String = sqlx::query_scalar!(
+        r#"
+        SELECT patron_category FROM users WHERE id = ?
+        "#,
+        user_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    let book_type: String = sqlx::query_scalar!(
+        r#"
+        SELECT type FROM books WHERE id = ?
+        "#,
+        loan.book_id
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    let terms = resolve_terms(&mut tx, &patron_category, &book_type, &config).await?;
+
+    let same_type_loans: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM loans l JOIN books b ON b.id = l.book_id
+        WHERE l.user_id = ? AND l.returned_at IS NULL AND b.type = ?
+        "#,
+        user_id,
+        book_type
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if same_type_loans >= i64::from(terms.max_loans) {
+        return Err(BlockReason::LoanLimitReached.into());
+    }
+
+    // 为该用户留好的书直接借出，否则只能借走未被预约占用的副本
+    let ready_hold = sqlx::query!(
+        r#"
+        SELECT id, item_id FROM holds WHERE book_id = ? AND user_id = ? AND status = ? LIMIT 1
+        "#,
+        loan.book_id,
+        user_id,
+        HoldStatus::Ready.as_str()
+    )
+    .fetch_optional(&mut *tx)
+    .await?;
+    let reserved_item = ready_hold.as_ref().and_then(|hold| hold.item_id.clone());
+
+    let now = Utc::now().naive_local();
+    match ready_hold {
+        Some(hold) => {
+            sqlx::query!(
+                r#"
+                UPDATE holds SET status = ?, updated_at = ? WHERE id = ?
+                "#,
+                HoldStatus::Fulfilled.as_str(),
+                now,
+                hold.id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        None if available_copies(&mut tx, &loan.book_id).await? <= 0 => {
+            return Err(AppError::conflict("No copies of this book are available"));
+        }
+        None => {}
+    }
+
+    // 选定借出的单册，优先借出调拨留给本人的单册，跳过留给他人的单册；图书行已加锁，无需再锁单册
+    let item_id: String = sqlx::query_scalar!(
+        r#"
+        SELECT i.id FROM items i
+        WHERE i.book_id = ? AND i.status = ? AND (? IS NULL OR i.barcode = ?)
+          AND (? IS NULL OR i.current_branch_id = ?)
+          AND NOT EXISTS (SELECT 1 FROM holds h WHERE h.item_id = i.id AND h.status = ? AND h.user_id <> ?)
+        ORDER BY i.id = ? DESC, i.barcode
+        LIMIT 1
+        "#,
+        loan.book_id,
+        ItemStatus::Available.as_str(),
+        loan.barcode,
+        loan.barcode,
+        loan.branch_id,
+        loan.branch_id,
+        HoldStatus::Ready.as_str(),
+        user_id,
+        reserved_item
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::conflict("Item is not available for checkout"))?;
+
+    sqlx::query!(
+        r#"
+        UPDATE items SET status = ?, updated_at = ? WHERE id = ?
+        "#,
+        ItemStatus::OnLoan.as_str(),
+        now,
+        item_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    let new_loan = Loan {
+        id: Uuid::new_v4().to_string(),
+        book_id: loan.book_id.clone(),
+        item_id: Some(item_id),
+        user_id,
+        checkout_at: now,
+        due_at: now + Duration::hours(i64::from(terms.loan_period_hours)),
+        returned_at: None,
+        renewal_count: 0,
+        loan_period_hours: Some(terms.loan_period_hours),
+        max_renewals: Some(terms.max_renewals),
+        fine_rate_cents: Some(terms.fine_rate_cents),
+        created_at: now,
+        updated_at: now,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO loans
+            (id, book_id, item_id, user_id, checkout_at, due_at, returned_at, renewal_count,
+             loan_period_hours, max_renewals, fine_rate_cents, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, NULL, 0, ?, ?, ?, ?, ?)
+        "#,
+        new_loan.id,
+        new_loan.book_id,
+        new_loan.item_id,
+        new_loan.user_id,
+        new_loan.checkout_at,
+        new_loan.due_at,
+        new_loan.loan_period_hours,
+        new_loan.max_renewals,
+        new_loan.fine_rate_cents,
+        new_loan.created_at,
+        new_loan.updated_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Created().json(new_loan))
+}
+
+pub async fn return_loan(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    loan_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let loan_id = loan_id.to_string();
+    let book_id: String = sqlx::query_scalar!(
+        r#"
+        SELECT book_id FROM loans WHERE id = ?
+        "#,
+        loan_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| AppError::not_found("Loan not found"))?;
+
+    // 与借书相同，先锁图书行再锁借阅记录，避免死锁
+    let mut tx = pool.begin().await?;
+    lock_book(&mut tx, &book_id).await?;
+
+    let mut loan = sqlx::query_as!(
+        Loan,
+        r#"
+        SELECT * FROM loans WHERE id = ? FOR UPDATE
+        "#,
+        loan_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::not_found("Loan not found"))?;
+
+    if loan.returned_at.is_some() {
+        return Err(AppError::conflict("Loan has already been returned"));
+    }
+
+    let now = Utc::now().naive_local();
+    sqlx::query!(
+        r#"
+        UPDATE loans SET returned_at = ?, updated_at = ? WHERE id = ?
+        "#,
+        now,
+        now,
+        loan_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if let Some(item_id) = &loan.item_id {
+        sqlx::query!(
+            r#"
+            UPDATE items SET status = ?, updated_at = ? WHERE id = ? AND status = ?
+            "#,
+            ItemStatus::Available.as_str(),
+            now,
+            item_id,
+            ItemStatus::OnLoan.as_str()
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    loan.returned_at = Some(now);
+    loan.updated_at = now;
+
+    // 逾期归还时记入罚款
+    assess_loan_fine(&mut tx, &loan, &config).await?;
+    // 归还的副本优先留给排队预约的读者
+    allocate_holds(&mut tx, &book_id, config.hold_pickup_days).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(loan))
+}
+
+pub async fn renew_loan(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    loan_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let loan_id = loan_id.to_string();
+    let book_id: String = sqlx::query_scalar!(
+        r#"
+        SELECT book_id FROM loans WHERE id = ?
+        "#,
+        loan_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| AppError::not_found("Loan not found"))?;
+
+    let mut tx = pool.begin().await?;
+    lock_book(&mut tx, &book_id).await?;
+
+    let mut loan = sqlx::query_as!(
+        Loan,
+        r#"
+        SELECT * FROM loans WHERE id = ? FOR UPDATE
+        "#,
+        loan_id
+    )
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::not_found("Loan not found"))?;
+
+    if !caller.can_act_for(&loan.user_id) {
+        return Err(AppError::forbidden("Cannot renew another user's loans"));
+    }
+    if loan.returned_at.is_some() {
+        return Err(AppError::conflict("Loan has already been returned"));
+    }
+
+    let now = Utc::now().naive_local();
+    if now > loan.due_at + Duration::days(config.renewal_grace_days) {
+        return Err(RenewalRefusal::Overdue.into());
+    }
+    if loan.renewal_count >= loan.max_renewals.unwrap_or(config.max_renewals) {
+        return Err(RenewalRefusal::LimitReached.into());
+    }
+
+    let pending_holds: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM holds WHERE book_id = ? AND status = ?
+        "#,
+        book_id,
+        HoldStatus::Waiting.as_str()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if pending_holds > 0 {
+        return Err(RenewalRefusal::HoldsPending.into());
+    }
+    check_standing(&mut tx, &loan.user_id, &config, false).await?;
+
+    loan.due_at += match loan.loan_period_hours {
+        Some(hours) => Duration::hours(i64::from(hours)),
+        None => Duration::days(config.loan_period_days),
+    };
+    loan.renewal_count += 1;
+    loan.updated_at = now;
+    sqlx::query!(
+        r#"
+        UPDATE loans SET due_at = ?, renewal_count = ?, updated_at = ? WHERE id = ?
+        "#,
+        loan.due_at,
+        loan.renewal_count,
+        loan.updated_at,
+        loan_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(loan))
+}
+
+pub async fn list_user_loans(
+    pool: web::Data<MySqlPool>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+    query: web::Query<LoanQuery>,
+) -> AppResult<HttpResponse> {
+    let user_id = user_id.to_string();
+    if !caller.can_act_for(&user_id) {
+        return Err(AppError::forbidden("Cannot view another user's loans"));
+    }
+
+    let status_clause = match query.status {
+        Some(LoanStatus::Active) => "AND returned_at IS NULL",
+        Some(LoanStatus::Returned) => "AND returned_at IS NOT NULL",
+        None => "",
+    };
+    let query_str = format!(
+        "SELECT * FROM loans WHERE user_id = ? {} ORDER BY checkout_at DESC",
+        status_clause
+    );
+
+    let loans = sqlx::query_as::<_, Loan>(&query_str)
+        .bind(&user_id)
+        .fetch_all(pool.get_ref())
+        .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": loans.len(),
+        "data": loans
+    })))
+}
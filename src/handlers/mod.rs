@@ -1,2 +1,16 @@
 pub mod user_handler;
-pub mod book_handler; 
\ No newline at end of file
+pub mod book_handler;
+pub mod loan_handler;
+pub mod hold_handler;
+pub mod fine_handler;
+pub mod patron_handler;
+pub mod policy_handler;
+pub mod item_handler;
+pub mod branch_handler;
+pub mod transfer_handler;
+pub mod author_handler;
+pub mod category_handler;
+pub mod work_handler;
+pub mod series_handler;
+pub mod cover_handler;
+pub mod suggest_handler;
@@ -0,0 +1,176 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::handlers::fine_handler::{assess_user_fines, fine_balance};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::user::{BlockReason, BlockUpdate, PatronStanding, ProfileResponse, User, UserResponse};
+
+pub async fn get_profile(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let user_id = user_id.to_string();
+    if !caller.can_act_for(&user_id) {
+        return Err(AppError::forbidden("Cannot view another user's profile"));
+    }
+
+    let mut conn = pool.acquire().await?;
+    let profile = fetch_profile(&mut conn, &user_id, &config).await?;
+
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+pub async fn block_user(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+    update: web::Json<BlockUpdate>,
+) -> AppResult<HttpResponse> {
+    set_blocked(&pool, &config, &caller, &user_id.to_string(), &update, true).await
+}
+
+pub async fn unblock_user(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    caller: AuthenticatedUser,
+    user_id: web::Path<Uuid>,
+    update: web::Json<BlockUpdate>,
+) -> AppResult<HttpResponse> {
+    set_blocked(&pool, &config, &caller, &user_id.to_string(), &update, false).await
+}
+
+async fn set_blocked(
+    pool: &MySqlPool,
+    config: &AppConfig,
+    caller: &AuthenticatedUser,
+    user_id: &str,
+    update: &BlockUpdate,
+    blocked: bool,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+
+    let mut tx = pool.begin().await?;
+    let result = sqlx::query!(
+        r#"
+        UPDATE users SET blocked = ?, block_note = ?, block_updated_by = ?, block_updated_at = ?
+        WHERE id = ?
+        "#,
+        blocked,
+        update.note.trim(),
+        caller.user_id,
+        Utc::now(),
+        user_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("User not found"));
+    }
+
+    let profile = fetch_profile(&mut tx, user_id, config).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(profile))
+}
+
+async fn fetch_profile(
+    conn: &mut MySqlConnection,
+    user_id: &str,
+    config: &AppConfig,
+) -> AppResult<ProfileResponse> {
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        SELECT * FROM users WHERE id = ?
+        "#,
+        user_id
+    )
+    .fetch_optional(&mut *conn)
+    .await?
+    .ok_or_else(|| AppError::not_found("User not found"))?;
+    let standing = patron_standing(conn, user_id, config).await?;
+
+    Ok(ProfileResponse {
+        user: UserResponse::from(user),
+        standing,
+    })
+}
+
+/// 汇总读者当前的借阅资格：手动停借、欠款、逾期和在借数量。欠款按已记入流水的罚款计算，不在此补记
+pub(crate) async fn patron_standing(
+    conn: &mut MySqlConnection,
+    user_id: &str,
+    config: &AppConfig,
+) -> AppResult<PatronStanding> {
+    let balance_cents = fine_balance(conn, user_id).await?.balance_cents;
+
+    let blocked: bool = sqlx::query_scalar!(
+        r#"
+        SELECT blocked AS `blocked: bool` FROM users WHERE id = ?
+        "#,
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+
+    let counts = sqlx::query!(
+        r#"
+        SELECT
+            CAST(COUNT(*) AS SIGNED) AS active,
+            CAST(COALESCE(SUM(due_at < ?), 0) AS SIGNED) AS overdue
+        FROM loans WHERE user_id = ? AND returned_at IS NULL
+        "#,
+        Utc::now().naive_local(),
+        user_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    let active_loans = counts.active.unwrap_or(0);
+    let overdue_loans = counts.overdue.unwrap_or(0);
+
+    let mut reasons = Vec::new();
+    if blocked {
+        reasons.push(BlockReason::ManualBlock);
+    }
+    if balance_cents > config.max_owed_cents {
+        reasons.push(BlockReason::FinesOwed);
+    }
+    if overdue_loans > config.max_overdue_items {
+        reasons.push(BlockReason::TooManyOverdue);
+    }
+    if active_loans >= config.max_concurrent_loans {
+        reasons.push(BlockReason::LoanLimitReached);
+    }
+
+    Ok(PatronStanding {
+        can_borrow: reasons.is_empty(),
+        reasons,
+        balance_cents,
+        overdue_loans,
+        active_loans,
+    })
+}
+
+/// 借书和续借前检查读者资格，不满足时返回带 reason 的 409
+pub(crate) async fn check_standing(
+    conn: &mut MySqlConnection,
+    user_id: &str,
+    config: &AppConfig,
+    checkout: bool,
+) -> AppResult<()> {
+    // 先补记逾期罚款（同时锁定用户行），再判断资格
+    assess_user_fines(conn, user_id, config).await?;
+    match patron_standing(conn, user_id, config).await?.refusal(checkout) {
+        Some(reason) => Err(reason.into()),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,179 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::models::policy::{CirculationPolicy, CreatePolicy, LoanTerms, ResolveQuery, UpdatePolicy, ANY};
+
+pub async fn create_policy(
+    pool: web::Data<MySqlPool>,
+    policy: web::Json<CreatePolicy>,
+) -> AppResult<HttpResponse> {
+    policy.validate()?;
+
+    let now = Utc::now().naive_local();
+    let new_policy = CirculationPolicy {
+        id: Uuid::new_v4().to_string(),
+        patron_category: policy.patron_category.clone(),
+        book_type: policy.book_type.clone(),
+        loan_period_hours: policy.loan_period_hours,
+        max_renewals: policy.max_renewals,
+        fine_rate_cents: policy.fine_rate_cents,
+        max_loans: policy.max_loans,
+        created_at: now,
+        updated_at: now,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO circulation_policies
+            (id, patron_category, book_type, loan_period_hours, max_renewals, fine_rate_cents, max_loans, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        new_policy.id,
+        new_policy.patron_category,
+        new_policy.book_type,
+        new_policy.loan_period_hours,
+        new_policy.max_renewals,
+        new_policy.fine_rate_cents,
+        new_policy.max_loans,
+        new_policy.created_at,
+        new_policy.updated_at
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Created().json(new_policy))
+}
+
+pub async fn list_policies(pool: web::Data<MySqlPool>) -> AppResult<HttpResponse> {
+    let policies = sqlx::query_as!(
+        CirculationPolicy,
+        r#"
+        SELECT * FROM circulation_policies ORDER BY patron_category, book_type
+        "#
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": policies.len(),
+        "data": policies
+    })))
+}
+
+pub async fn get_policy(
+    pool: web::Data<MySqlPool>,
+    policy_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let policy = fetch_policy(pool.get_ref(), &policy_id.to_string()).await?;
+    Ok(HttpResponse::Ok().json(policy))
+}
+
+pub async fn update_policy(
+    pool: web::Data<MySqlPool>,
+    policy_id: web::Path<Uuid>,
+    update: web::Json<UpdatePolicy>,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+    let policy_id = policy_id.to_string();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE circulation_policies
+        SET loan_period_hours = ?, max_renewals = ?, fine_rate_cents = ?, max_loans = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+        update.loan_period_hours,
+        update.max_renewals,
+        update.fine_rate_cents,
+        update.max_loans,
+        Utc::now().naive_local(),
+        policy_id
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Policy not found"));
+    }
+
+    let policy = fetch_policy(pool.get_ref(), &policy_id).await?;
+    Ok(HttpResponse::Ok().json(policy))
+}
+
+pub async fn delete_policy(
+    pool: web::Data<MySqlPool>,
+    policy_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM circulation_policies WHERE id = ?
+        "#,
+        policy_id.to_string()
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Policy not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// 查询某读者类别借某类图书时实际适用的借阅条件
+pub async fn resolve_policy(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    query: web::Query<ResolveQuery>,
+) -> AppResult<HttpResponse> {
+    let mut conn = pool.acquire().await?;
+    let terms = resolve_terms(&mut conn, &query.patron_category, &query.book_type, &config).await?;
+    Ok(HttpResponse::Ok().json(terms))
+}
+
+/// 匹配优先级：类别和类型都精确匹配 > 仅类型匹配 > 仅类别匹配 > ('*', '*')，都没有时使用配置默认值。
+/// 针对某类图书的规则（如工具书短借）比读者类别的通用规则更具体，因此优先
+pub(crate) async fn resolve_terms(
+    conn: &mut MySqlConnection,
+    patron_category: &str,
+    book_type: &str,
+    config: &AppConfig,
+) -> Result<LoanTerms, sqlx::Error> {
+    let policy = sqlx::query_as!(
+        CirculationPolicy,
+        r#"
+        SELECT * FROM circulation_policies
+        WHERE patron_category IN (?, ?) AND book_type IN (?, ?)
+        ORDER BY book_type = ?, patron_category = ?
+        LIMIT 1
+        "#,
+        patron_category,
+        ANY,
+        book_type,
+        ANY,
+        ANY,
+        ANY
+    )
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    Ok(policy.map(LoanTerms::from).unwrap_or_else(|| LoanTerms::fallback(config)))
+}
+
+async fn fetch_policy(pool: &MySqlPool, policy_id: &str) -> AppResult<CirculationPolicy> {
+    sqlx::query_as!(
+        CirculationPolicy,
+        r#"
+        SELECT * FROM circulation_policies WHERE id = ?
+        "#,
+        policy_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Policy not found"))
+}
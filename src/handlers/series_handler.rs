@@ -0,0 +1,202 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
+use crate::handlers::book_handler::book_responses;
+use crate::models::book::Book;
+use crate::models::series::{CreateSeries, Series, SeriesQuery, UpdateSeries};
+
+pub async fn list_series(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<SeriesQuery>,
+) -> AppResult<HttpResponse> {
+    let page_no = query.page_no.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10);
+    let offset = (page_no - 1) * page_size;
+    let name = query.name.as_ref().map(|name| format!("%{}%", name));
+
+    let series: Vec<Series> = sqlx::query_as!(
+        Series,
+        r#"
+        SELECT * FROM series
+        WHERE (? IS NULL OR name LIKE ?)
+        ORDER BY name, created_at
+        LIMIT ? OFFSET ?
+        "#,
+        name,
+        name,
+        page_size,
+        offset
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let total: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM series WHERE (? IS NULL OR name LIKE ?)
+        "#,
+        name,
+        name
+    )
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": total,
+        "page_no": page_no,
+        "page_size": page_size,
+        "data": series
+    })))
+}
+
+pub async fn create_series(
+    pool: web::Data<MySqlPool>,
+    series: web::Json<CreateSeries>,
+) -> AppResult<HttpResponse> {
+    series.validate()?;
+
+    let now = Utc::now().naive_local();
+    let new_series = Series {
+        id: Uuid::new_v4().to_string(),
+        name: series.name.trim().to_string(),
+        description: series.description.clone(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO series (id, name, description, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        new_series.id,
+        new_series.name,
+        new_series.description,
+        new_series.created_at,
+        new_series.updated_at
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Created().json(new_series))
+}
+
+pub async fn get_series(
+    pool: web::Data<MySqlPool>,
+    series_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let series = fetch_series(pool.get_ref(), &series_id.to_string()).await?;
+    Ok(HttpResponse::Ok().json(series))
+}
+
+pub async fn update_series(
+    pool: web::Data<MySqlPool>,
+    series_id: web::Path<Uuid>,
+    update: web::Json<UpdateSeries>,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+    let series_id = series_id.to_string();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE series
+        SET name = COALESCE(?, name),
+            description = COALESCE(?, description),
+            updated_at = ?
+        WHERE id = ?
+        "#,
+        update.name.as_deref().map(str::trim),
+        update.description,
+        Utc::now().naive_local(),
+        series_id
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Series not found"));
+    }
+
+    let series = fetch_series(pool.get_ref(), &series_id).await?;
+    Ok(HttpResponse::Ok().json(series))
+}
+
+pub async fn delete_series(
+    pool: web::Data<MySqlPool>,
+    series_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    // 仍有图书关联的丛书由外键拦截，返回 409
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM series WHERE id = ?
+        "#,
+        series_id.to_string()
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Series not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// 丛书的各卷，按卷次排列，同一卷次的多个版本按出版年排列
+pub async fn list_series_books(
+    pool: web::Data<MySqlPool>,
+    series_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let series_id = series_id.to_string();
+    fetch_series(pool.get_ref(), &series_id).await?;
+
+    let books = sqlx::query_as::<_, Book>(
+        "SELECT * FROM books
+         WHERE series_id = ?
+         ORDER BY series_volume IS NULL, series_volume, publication_year, created_at",
+    )
+    .bind(&series_id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let data = book_responses(pool.get_ref(), books).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": data.len(),
+        "data": data
+    })))
+}
+
+async fn fetch_series(pool: &MySqlPool, series_id: &str) -> AppResult<Series> {
+    sqlx::query_as!(
+        Series,
+        r#"
+        SELECT * FROM series WHERE id = ?
+        "#,
+        series_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Series not found"))
+}
+
+/// 创建或修改图书时校验指定的丛书存在
+pub(crate) async fn ensure_series(conn: &mut MySqlConnection, series_id: &str) -> AppResult<()> {
+    let exists: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM series WHERE id = ?
+        "#,
+        series_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if exists == 0 {
+        return Err(AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({ "series_id": [{ "code": "series", "message": "Series not found" }] })),
+        ));
+    }
+    Ok(())
+}
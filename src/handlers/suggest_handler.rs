@@ -0,0 +1,146 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use actix_web::{web, HttpResponse};
+use serde::Deserialize;
+use sqlx::MySqlPool;
+use tokio::sync::Mutex;
+
+use crate::error::{AppError, AppResult};
+use crate::models::suggest::SuggestIndex;
+
+const DEFAULT_LIMIT: usize = 5;
+const MAX_LIMIT: usize = 20;
+const MAX_INPUT_CHARS: usize = 100;
+
+/// 补全索引缓存。索引超过 max_age 后仍先返回旧索引，同时在后台重建，避免输入时等待数据库
+pub struct SuggestCache {
+    snapshot: RwLock<Option<(Instant, Arc<SuggestIndex>)>>,
+    /// 首次构建时持有，并发的首批请求等待同一次构建而不是各自读库
+    building: Mutex<()>,
+    refreshing: AtomicBool,
+    max_age: Duration,
+}
+
+impl SuggestCache {
+    pub fn new(max_age: Duration) -> SuggestCache {
+        SuggestCache {
+            snapshot: RwLock::new(None),
+            building: Mutex::new(()),
+            refreshing: AtomicBool::new(false),
+            max_age,
+        }
+    }
+
+    fn current(&self) -> Option<(Instant, Arc<SuggestIndex>)> {
+        self.snapshot.read().expect("suggest cache poisoned").clone()
+    }
+}
+
+/// 后台重建结束时清除 refreshing，重建出错或 panic 时也会清除，否则之后再也不会重建
+struct RefreshGuard<'a>(&'a AtomicBool);
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    pub q: String,
+    /// 每类最多返回的候选项数，默认 5，最大 20
+    pub limit: Option<usize>,
+}
+
+pub async fn suggest(
+    pool: web::Data<MySqlPool>,
+    cache: web::Data<SuggestCache>,
+    query: web::Query<SuggestQuery>,
+) -> AppResult<HttpResponse> {
+    if query.q.trim().is_empty() || query.q.chars().count() > MAX_INPUT_CHARS {
+        return Err(AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({
+                "q": [{ "code": "length", "message": format!("q must be 1 to {} characters", MAX_INPUT_CHARS) }]
+            })),
+        ));
+    }
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({
+                "limit": [{ "code": "range", "message": format!("limit must be between 1 and {}", MAX_LIMIT) }]
+            })),
+        ));
+    }
+
+    let index = current_index(&cache, &pool).await?;
+    Ok(HttpResponse::Ok().json(index.suggest(&query.q, limit)))
+}
+
+/// 首次请求时同步构建索引，之后过期的索引在后台重建
+async fn current_index(cache: &web::Data<SuggestCache>, pool: &web::Data<MySqlPool>) -> AppResult<Arc<SuggestIndex>> {
+    let Some((built_at, index)) = cache.current() else {
+        let _building = cache.building.lock().await;
+        // 等待期间其他请求可能已构建完成
+        if let Some((_, index)) = cache.current() {
+            return Ok(index);
+        }
+        return refresh(cache, pool).await;
+    };
+
+    if built_at.elapsed() > cache.max_age && !cache.refreshing.swap(true, Ordering::SeqCst) {
+        let cache = cache.clone();
+        let pool = pool.clone();
+        actix_rt::spawn(async move {
+            let _refreshing = RefreshGuard(&cache.refreshing);
+            if let Err(e) = refresh(&cache, &pool).await {
+                log::warn!("Failed to refresh suggestion index: {}", e);
+            }
+        });
+    }
+    Ok(index)
+}
+
+async fn refresh(cache: &SuggestCache, pool: &MySqlPool) -> AppResult<Arc<SuggestIndex>> {
+    let index = Arc::new(load_index(pool).await?);
+    *cache.snapshot.write().expect("suggest cache poisoned") = Some((Instant::now(), index.clone()));
+    Ok(index)
+}
+
+/// 从数据库读取全部题名、ISBN 和作者，热度为借阅次数
+async fn load_index(pool: &MySqlPool) -> AppResult<SuggestIndex> {
+    let books = sqlx::query!(
+        r#"
+        SELECT b.id, b.title, b.title_pinyin, b.title_initials, b.isbn,
+               (SELECT COUNT(*) FROM loans l WHERE l.book_id = b.id) AS `loans!: i64`
+        FROM books b
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let authors = sqlx::query!(
+        r#"
+        SELECT a.id, a.name, COUNT(DISTINCT l.id) AS `loans!: i64`
+        FROM authors a
+        LEFT JOIN book_authors ba ON ba.author_id = a.id
+        LEFT JOIN loans l ON l.book_id = ba.book_id
+        GROUP BY a.id, a.name
+        "#
+    )
+    .fetch_all(pool)
+    .await?;
+
+    let mut index = SuggestIndex::default();
+    for book in &books {
+        index.add_book(&book.id, &book.title, &book.title_pinyin, &book.title_initials, &book.isbn, book.loans);
+    }
+    for author in &authors {
+        index.add_author(&author.id, &author.name, author.loans);
+    }
+    Ok(index.finish())
+}
@@ -0,0 +1,325 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::handlers::branch_handler::resolve_branch;
+use crate::handlers::hold_handler::{allocate_holds, available_copies, lock_book, refresh_holds, reserve_for_patron};
+use crate::middleware::auth::AuthenticatedUser;
+use crate::models::hold::HoldStatus;
+use crate::models::item::ItemStatus;
+use crate::models::transfer::{CreateTransfer, ShipTransfer, Transfer, TransferQuery, TransferStatus};
+use crate::models::user::Role;
+
+pub async fn create_transfer(
+    pool: web::Data<MySqlPool>,
+    caller: AuthenticatedUser,
+    transfer: web::Json<CreateTransfer>,
+) -> AppResult<HttpResponse> {
+    // 读者只能为本人申请；图书管理员不指定读者时仅调整馆藏分布
+    let user_id = match &transfer.user_id {
+        Some(user_id) => Some(user_id.clone()),
+        None if caller.has_role(Role::Librarian) => None,
+        None => Some(caller.user_id.clone()),
+    };
+    if let Some(user_id) = &user_id {
+        if !caller.can_act_for(user_id) {
+            return Err(AppError::forbidden("Cannot request transfers for another user"));
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    lock_book(&mut tx, &transfer.book_id).await?;
+    let to_branch_id = resolve_branch(&mut tx, Some(&transfer.to_branch_id)).await?;
+
+    let already_there: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM items WHERE book_id = ? AND current_branch_id = ? AND status = ?
+        "#,
+        transfer.book_id,
+        to_branch_id,
+        ItemStatus::Available.as_str()
+    )
+    .fetch_one(&mut *tx)
+    .await?;
+    if already_there > 0 {
+        return Err(AppError::conflict("A copy is already available at this branch"));
+    }
+
+    let now = Utc::now().naive_local();
+    let new_transfer = Transfer {
+        id: Uuid::new_v4().to_string(),
+        book_id: transfer.book_id.clone(),
+        item_id: None,
+        from_branch_id: None,
+        to_branch_id,
+        status: TransferStatus::Requested.as_str().to_string(),
+        user_id,
+        requested_by: caller.user_id.clone(),
+        shipped_at: None,
+        received_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO transfers
+            (id, book_id, item_id, from_branch_id, to_branch_id, status, user_id, requested_by,
+             shipped_at, received_at, created_at, updated_at)
+        VALUES (?, ?, NULL, NULL, ?, ?, ?, ?, NULL, NULL, ?, ?)
+        "#,
+        new_transfer.id,
+        new_transfer.book_id,
+        new_transfer.to_branch_id,
+        new_transfer.status,
+        new_transfer.user_id,
+        new_transfer.requested_by,
+        new_transfer.created_at,
+        new_transfer.updated_at
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Created().json(new_transfer))
+}
+
+pub async fn get_transfer(
+    pool: web::Data<MySqlPool>,
+    caller: AuthenticatedUser,
+    transfer_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let mut conn = pool.acquire().await?;
+    let transfer = fetch_transfer(&mut conn, &transfer_id.to_string(), false).await?;
+    if !can_view(&caller, &transfer) {
+        return Err(AppError::forbidden("Cannot view another user's transfers"));
+    }
+
+    Ok(HttpResponse::Ok().json(transfer))
+}
+
+pub async fn list_transfers(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<TransferQuery>,
+) -> AppResult<HttpResponse> {
+    let transfers = sqlx::query_as!(
+        Transfer,
+        r#"
+        SELECT * FROM transfers
+        WHERE (? IS NULL OR status = ?)
+          AND (? IS NULL OR from_branch_id = ? OR to_branch_id = ?)
+        ORDER BY created_at ASC
+        "#,
+        query.status.map(|status| status.as_str()),
+        query.status.map(|status| status.as_str()),
+        query.branch_id,
+        query.branch_id,
+        query.branch_id
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": transfers.len(),
+        "data": transfers
+    })))
+}
+
+pub async fn ship_transfer(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    transfer_id: web::Path<Uuid>,
+    ship: web::Json<ShipTransfer>,
+) -> AppResult<HttpResponse> {
+    let transfer_id = transfer_id.to_string();
+    let book_id = transfer_book(pool.get_ref(), &transfer_id).await?;
+
+    // 与借还书相同，先锁图书行再锁调拨记录
+    let mut tx = pool.begin().await?;
+    lock_book(&mut tx, &book_id).await?;
+    let mut transfer = fetch_transfer(&mut tx, &transfer_id, true).await?;
+    if transfer.status != TransferStatus::Requested.as_str() {
+        return Err(AppError::conflict("Transfer has already been shipped or closed"));
+    }
+
+    // 已为预约读者留好的副本不能调走，调拨留给读者的单册也不能再被调走
+    refresh_holds(&mut tx, &book_id, config.hold_pickup_days).await?;
+    if available_copies(&mut tx, &book_id).await? <= 0 {
+        return Err(AppError::conflict("No copies of this book are available"));
+    }
+
+    let (item_id, from_branch_id): (String, String) = sqlx::query_as(
+        "SELECT i.id, i.current_branch_id FROM items i
+         WHERE i.book_id = ? AND i.status = ? AND i.current_branch_id <> ? AND (? IS NULL OR i.barcode = ?)
+           AND NOT EXISTS (SELECT 1 FROM holds h WHERE h.item_id = i.id AND h.status = ?)
+         ORDER BY i.barcode
+         LIMIT 1",
+    )
+    .bind(&book_id)
+    .bind(ItemStatus::Available.as_str())
+    .bind(&transfer.to_branch_id)
+    .bind(&ship.barcode)
+    .bind(&ship.barcode)
+    .bind(HoldStatus::Ready.as_str())
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| AppError::conflict("Item is not available for transfer"))?;
+
+    let now = Utc::now().naive_local();
+    sqlx::query!(
+        r#"
+        UPDATE items SET status = ?, updated_at = ? WHERE id = ?
+        "#,
+        ItemStatus::InTransit.as_str(),
+        now,
+        item_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    transfer.item_id = Some(item_id);
+    transfer.from_branch_id = Some(from_branch_id);
+    transfer.status = TransferStatus::InTransit.as_str().to_string();
+    transfer.shipped_at = Some(now);
+    transfer.updated_at = now;
+    sqlx::query!(
+        r#"
+        UPDATE transfers
+        SET item_id = ?, from_branch_id = ?, status = ?, shipped_at = ?, updated_at = ?
+        WHERE id = ?
+        "#,
+        transfer.item_id,
+        transfer.from_branch_id,
+        transfer.status,
+        transfer.shipped_at,
+        transfer.updated_at,
+        transfer_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(transfer))
+}
+
+pub async fn receive_transfer(
+    pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
+    transfer_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let transfer_id = transfer_id.to_string();
+    let book_id = transfer_book(pool.get_ref(), &transfer_id).await?;
+
+    let mut tx = pool.begin().await?;
+    lock_book(&mut tx, &book_id).await?;
+    let mut transfer = fetch_transfer(&mut tx, &transfer_id, true).await?;
+    if transfer.status != TransferStatus::InTransit.as_str() {
+        return Err(AppError::conflict("Transfer is not in transit"));
+    }
+
+    let now = Utc::now().naive_local();
+    sqlx::query!(
+        r#"
+        UPDATE items SET current_branch_id = ?, status = ?, updated_at = ? WHERE id = ? AND status = ?
+        "#,
+        transfer.to_branch_id,
+        ItemStatus::Available.as_str(),
+        now,
+        transfer.item_id,
+        ItemStatus::InTransit.as_str()
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    transfer.status = TransferStatus::Received.as_str().to_string();
+    transfer.received_at = Some(now);
+    transfer.updated_at = now;
+    sqlx::query!(
+        r#"
+        UPDATE transfers SET status = ?, received_at = ?, updated_at = ? WHERE id = ?
+        "#,
+        transfer.status,
+        transfer.received_at,
+        transfer.updated_at,
+        transfer_id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    // 到馆的副本先留给申请调拨的读者，其余按预约队列分配
+    if let (Some(user_id), Some(item_id)) = (&transfer.user_id, &transfer.item_id) {
+        reserve_for_patron(&mut tx, &book_id, item_id, user_id, config.hold_pickup_days).await?;
+    }
+    allocate_holds(&mut tx, &book_id, config.hold_pickup_days).await?;
+    tx.commit().await?;
+
+    Ok(HttpResponse::Ok().json(transfer))
+}
+
+pub async fn cancel_transfer(
+    pool: web::Data<MySqlPool>,
+    caller: AuthenticatedUser,
+    transfer_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let transfer_id = transfer_id.to_string();
+    let mut conn = pool.acquire().await?;
+    let transfer = fetch_transfer(&mut conn, &transfer_id, false).await?;
+    if !can_view(&caller, &transfer) {
+        return Err(AppError::forbidden("Cannot cancel another user's transfers"));
+    }
+
+    // 已发出的调拨只能到馆后处理
+    let result = sqlx::query!(
+        r#"
+        UPDATE transfers SET status = ?, updated_at = ? WHERE id = ? AND status = ?
+        "#,
+        TransferStatus::Cancelled.as_str(),
+        Utc::now().naive_local(),
+        transfer_id,
+        TransferStatus::Requested.as_str()
+    )
+    .execute(&mut *conn)
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::conflict("Only requested transfers can be cancelled"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+fn can_view(caller: &AuthenticatedUser, transfer: &Transfer) -> bool {
+    caller.can_act_for(&transfer.requested_by)
+        || transfer.user_id.as_deref().is_some_and(|user_id| caller.can_act_for(user_id))
+}
+
+async fn transfer_book(pool: &MySqlPool, transfer_id: &str) -> AppResult<String> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT book_id FROM transfers WHERE id = ?
+        "#,
+        transfer_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Transfer not found"))
+}
+
+async fn fetch_transfer(conn: &mut MySqlConnection, transfer_id: &str, for_update: bool) -> AppResult<Transfer> {
+    let query_str = if for_update {
+        "SELECT * FROM transfers WHERE id = ? FOR UPDATE"
+    } else {
+        "SELECT * FROM transfers WHERE id = ?"
+    };
+
+    sqlx::query_as::<_, Transfer>(query_str)
+        .bind(transfer_id)
+        .fetch_optional(&mut *conn)
+        .await?
+        .ok_or_else(|| AppError::not_found("Transfer not found"))
+}
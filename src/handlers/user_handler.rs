@@ -1,28 +1,27 @@
-use actix_web::{web, HttpResponse, Responder, http::header::HeaderValue, HttpRequest};
+use actix_web::{web, HttpResponse, HttpRequest};
 use bcrypt::{hash, verify, DEFAULT_COST};
-use jsonwebtoken::{encode, EncodingKey, Header};
-use serde::{Deserialize, Serialize};
 use sqlx::MySqlPool;
 use uuid::Uuid;
-use chrono::{Utc, Duration, DateTime};
+use validator::Validate;
+use chrono::Utc;
 
-use crate::models::user::{User, CreateUser, LoginUser, UserResponse};
-
-#[derive(Debug, Serialize, Deserialize)]
-struct Claims {
-    sub: String,
-    exp: usize,
-}
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
+use crate::middleware::auth::bearer_token;
+use crate::models::user::{User, CreateUser, LoginUser, UserResponse, Role, UpdateRole, UpdateCategory, PATRON_CATEGORIES};
+use crate::utils::jwt::create_token;
 
 pub async fn register(
     pool: web::Data<MySqlPool>,
     user: web::Json<CreateUser>,
-) -> impl Responder {
-    let hashed_password = hash(user.password.as_bytes(), DEFAULT_COST).unwrap();
+) -> AppResult<HttpResponse> {
+    user.validate()?;
+
+    let hashed_password = hash(user.password.as_bytes(), DEFAULT_COST)?;
     let user_id = Uuid::new_v4();
     let now = Utc::now();
 
-    match sqlx::query!(
+    sqlx::query!(
         r#"
         INSERT INTO users (id, username, password, email, password_hash, role, created_at, updated_at)
         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
@@ -32,35 +31,34 @@ pub async fn register(
         hashed_password,
         user.email,
         hashed_password,
-        "user", // 默认角色
+        Role::Member.as_str(), // 默认角色
         now,
         now
     )
     .execute(pool.get_ref())
-    .await
-    {
-        Ok(_) => {
-            let user_response = UserResponse {
-                id: user_id,
-                username: user.username.clone(),
-                email: user.email.clone(),
-                role: "user".to_string(),
-                created_at: now,
-                updated_at: now,
-            };
-            HttpResponse::Created().json(user_response)
-        }
-        Err(e) => {
-            eprintln!("Error creating user: {}", e);
-            HttpResponse::InternalServerError().finish()
-        }
-    }
+    .await?;
+
+    let user_response = UserResponse {
+        id: user_id,
+        username: user.username.clone(),
+        email: user.email.clone(),
+        role: Role::Member.as_str().to_string(),
+        patron_category: PATRON_CATEGORIES[0].to_string(),
+        blocked: false,
+        block_note: None,
+        block_updated_by: None,
+        block_updated_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+    Ok(HttpResponse::Created().json(user_response))
 }
 
 pub async fn login(
     pool: web::Data<MySqlPool>,
+    config: web::Data<AppConfig>,
     credentials: web::Json<LoginUser>,
-) -> impl Responder {
+) -> AppResult<HttpResponse> {
     let user = sqlx::query_as!(
         User,
         r#"
@@ -69,95 +67,200 @@ pub async fn login(
         credentials.username
     )
     .fetch_optional(pool.get_ref())
-    .await;
-
-    match user {
-        Ok(Some(user)) => {
-            if verify(&credentials.password, &user.password).unwrap_or(false) {
-                let expiration = Utc::now()
-                    .checked_add_signed(Duration::hours(24))
-                    .expect("valid timestamp")
-                    .timestamp() as usize;
-
-                let claims = Claims {
-                    sub: user.id.to_string(),
-                    exp: expiration,
-                };
-
-                let token = encode(
-                    &Header::default(),
-                    &claims,
-                    &EncodingKey::from_secret(b"your-secret-key"),
-                )
-                .unwrap();
-
-                // 存储 token 到数据库
-                let token_id = Uuid::new_v4();
-                let expires_at = Utc::now()
-                    .checked_add_signed(Duration::hours(24))
-                    .expect("valid timestamp");
-                let now = Utc::now();
-
-                match sqlx::query!(
-                    r#"
-                    INSERT INTO tokens (id, user_id, token, expires_at, created_at)
-                    VALUES (?, ?, ?, ?, ?)
-                    "#,
-                    token_id,
-                    user.id,
-                    token,
-                    expires_at,
-                    now
-                )
-                .execute(pool.get_ref())
-                .await
-                {
-                    Ok(_) => HttpResponse::Ok().json(serde_json::json!({
-                        "token": token,
-                        "expires_at": expires_at
-                    })),
-                    Err(e) => {
-                        eprintln!("Error storing token: {}", e);
-                        HttpResponse::InternalServerError().finish()
-                    }
-                }
-            } else {
-                HttpResponse::Unauthorized().finish()
-            }
-        }
-        _ => HttpResponse::Unauthorized().finish(),
+    .await?
+    .ok_or_else(|| AppError::unauthorized("Invalid username or password"))?;
+
+    if !verify(&credentials.password, &user.password).unwrap_or(false) {
+        return Err(AppError::unauthorized("Invalid username or password"));
     }
+
+    let (token, expires_at) = create_token(&user.id.to_string(), &config)?;
+
+    // 存储 token 到数据库
+    let token_id = Uuid::new_v4();
+    let now = Utc::now();
+
+    sqlx::query!(
+        r#"
+        INSERT INTO tokens (id, user_id, token, expires_at, created_at)
+        VALUES (?, ?, ?, ?, ?)
+        "#,
+        token_id,
+        user.id,
+        token,
+        expires_at,
+        now
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "token": token,
+        "expires_at": expires_at
+    })))
 }
 
 pub async fn logout(
     pool: web::Data<MySqlPool>,
     req: HttpRequest,
-) -> impl Responder {
-    let token_str = match req.headers().get("Authorization") {
-        Some(header_value) => {
-            let value = header_value.to_str().unwrap_or("");
-            if value.starts_with("Bearer ") {
-                &value[7..]
-            } else {
-                value
-            }
-        }
-        None => return HttpResponse::BadRequest().finish(),
-    };
+) -> AppResult<HttpResponse> {
+    let token_str = bearer_token(&req)
+        .ok_or_else(|| AppError::validation("Missing bearer token", None))?;
 
-    match sqlx::query!(
+    sqlx::query!(
         r#"
         DELETE FROM tokens WHERE token = ?
         "#,
         token_str
     )
     .execute(pool.get_ref())
-    .await
+    .await?;
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+pub async fn update_user_role(
+    pool: web::Data<MySqlPool>,
+    user_id: web::Path<Uuid>,
+    update: web::Json<UpdateRole>,
+) -> AppResult<HttpResponse> {
+    let user_id = user_id.to_string();
+
+    let current_role: String = sqlx::query_scalar!(
+        r#"
+        SELECT role FROM users WHERE id = ?
+        "#,
+        user_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| AppError::not_found("User not found"))?;
+
+    // 不允许降级最后一个管理员
+    if Role::parse(&current_role) == Some(Role::Admin)
+        && update.role != Role::Admin
+        && count_admins(pool.get_ref()).await? <= 1
     {
-        Ok(_) => HttpResponse::NoContent().finish(),
-        Err(e) => {
-            eprintln!("Error deleting token: {}", e);
-            HttpResponse::InternalServerError().finish()
-        }
+        return Err(AppError::conflict("Cannot demote the last admin"));
+    }
+
+    sqlx::query!(
+        r#"
+        UPDATE users SET role = ?, updated_at = ? WHERE id = ?
+        "#,
+        update.role.as_str(),
+        Utc::now(),
+        user_id
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        SELECT * FROM users WHERE id = ?
+        "#,
+        user_id
+    )
+    .fetch_optional(pool.get_ref())
+    .await?
+    .ok_or_else(|| AppError::not_found("User not found"))?;
+
+    Ok(HttpResponse::Ok().json(UserResponse::from(user)))
+}
+
+pub async fn update_user_category(
+    pool: web::Data<MySqlPool>,
+    user_id: web::Path<Uuid>,
+    update: web::Json<UpdateCategory>,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+    let user_id = user_id.to_string();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE users SET patron_category = ?, updated_at = ? WHERE id = ?
+        "#,
+        update.patron_category,
+        Utc::now(),
+        user_id
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("User not found"));
+    }
+
+    let user = sqlx::query_as!(
+        User,
+        r#"
+        SELECT * FROM users WHERE id = ?
+        "#,
+        user_id
+    )
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(UserResponse::from(user)))
+}
+
+async fn count_admins(pool: &MySqlPool) -> Result<i64, sqlx::Error> {
+    sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM users WHERE role = ?
+        "#,
+        Role::Admin.as_str()
+    )
+    .fetch_one(pool)
+    .await
+}
+
+/// 启动时创建首个管理员：若系统中尚无管理员，则新建该账号。
+/// 用户名已被注册时不会提升现有账号，否则任何人抢先注册该用户名即可获得管理员权限
+pub async fn bootstrap_admin(
+    pool: &MySqlPool,
+    username: &str,
+    email: &str,
+    password: &str,
+) -> AppResult<bool> {
+    if count_admins(pool).await? > 0 {
+        return Ok(false);
     }
-} 
\ No newline at end of file
+
+    let taken = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM users WHERE username = ?
+        "#,
+        username
+    )
+    .fetch_one(pool)
+    .await?;
+    if taken > 0 {
+        return Err(AppError::conflict(format!(
+            "Username '{}' is already registered; choose another admin username",
+            username
+        )));
+    }
+
+    let now = Utc::now();
+    let hashed_password = hash(password.as_bytes(), DEFAULT_COST)?;
+    sqlx::query!(
+        r#"
+        INSERT INTO users (id, username, password, email, password_hash, role, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        Uuid::new_v4(),
+        username,
+        hashed_password,
+        email,
+        hashed_password,
+        Role::Admin.as_str(),
+        now,
+        now
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(true)
+}
@@ -0,0 +1,208 @@
+use actix_web::{web, HttpResponse};
+use chrono::Utc;
+use sqlx::{MySqlConnection, MySqlPool};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::error::{AppError, AppResult};
+use crate::handlers::book_handler::book_responses;
+use crate::models::book::Book;
+use crate::models::work::{CreateWork, UpdateWork, Work, WorkQuery};
+
+pub async fn list_works(
+    pool: web::Data<MySqlPool>,
+    query: web::Query<WorkQuery>,
+) -> AppResult<HttpResponse> {
+    let page_no = query.page_no.unwrap_or(1);
+    let page_size = query.page_size.unwrap_or(10);
+    let offset = (page_no - 1) * page_size;
+    let title = query.title.as_ref().map(|title| format!("%{}%", title));
+
+    let works: Vec<Work> = sqlx::query_as!(
+        Work,
+        r#"
+        SELECT * FROM works
+        WHERE (? IS NULL OR title LIKE ? OR original_title LIKE ?)
+        ORDER BY title, created_at
+        LIMIT ? OFFSET ?
+        "#,
+        title,
+        title,
+        title,
+        page_size,
+        offset
+    )
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let total: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM works WHERE (? IS NULL OR title LIKE ? OR original_title LIKE ?)
+        "#,
+        title,
+        title,
+        title
+    )
+    .fetch_one(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": total,
+        "page_no": page_no,
+        "page_size": page_size,
+        "data": works
+    })))
+}
+
+pub async fn create_work(
+    pool: web::Data<MySqlPool>,
+    work: web::Json<CreateWork>,
+) -> AppResult<HttpResponse> {
+    work.validate()?;
+
+    let now = Utc::now().naive_local();
+    let new_work = Work {
+        id: Uuid::new_v4().to_string(),
+        title: work.title.trim().to_string(),
+        original_title: work.original_title.clone(),
+        description: work.description.clone(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    sqlx::query!(
+        r#"
+        INSERT INTO works (id, title, original_title, description, created_at, updated_at)
+        VALUES (?, ?, ?, ?, ?, ?)
+        "#,
+        new_work.id,
+        new_work.title,
+        new_work.original_title,
+        new_work.description,
+        new_work.created_at,
+        new_work.updated_at
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    Ok(HttpResponse::Created().json(new_work))
+}
+
+pub async fn get_work(
+    pool: web::Data<MySqlPool>,
+    work_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let work = fetch_work(pool.get_ref(), &work_id.to_string()).await?;
+    Ok(HttpResponse::Ok().json(work))
+}
+
+pub async fn update_work(
+    pool: web::Data<MySqlPool>,
+    work_id: web::Path<Uuid>,
+    update: web::Json<UpdateWork>,
+) -> AppResult<HttpResponse> {
+    update.validate()?;
+    let work_id = work_id.to_string();
+
+    let result = sqlx::query!(
+        r#"
+        UPDATE works
+        SET title = COALESCE(?, title),
+            original_title = COALESCE(?, original_title),
+            description = COALESCE(?, description),
+            updated_at = ?
+        WHERE id = ?
+        "#,
+        update.title.as_deref().map(str::trim),
+        update.original_title,
+        update.description,
+        Utc::now().naive_local(),
+        work_id
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Work not found"));
+    }
+
+    let work = fetch_work(pool.get_ref(), &work_id).await?;
+    Ok(HttpResponse::Ok().json(work))
+}
+
+pub async fn delete_work(
+    pool: web::Data<MySqlPool>,
+    work_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    // 仍有版本关联的作品由外键拦截，返回 409
+    let result = sqlx::query!(
+        r#"
+        DELETE FROM works WHERE id = ?
+        "#,
+        work_id.to_string()
+    )
+    .execute(pool.get_ref())
+    .await?;
+
+    if result.rows_affected() == 0 {
+        return Err(AppError::not_found("Work not found"));
+    }
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// 作品的全部版本，按出版年倒序
+pub async fn list_work_books(
+    pool: web::Data<MySqlPool>,
+    work_id: web::Path<Uuid>,
+) -> AppResult<HttpResponse> {
+    let work_id = work_id.to_string();
+    fetch_work(pool.get_ref(), &work_id).await?;
+
+    let books = sqlx::query_as::<_, Book>(
+        "SELECT * FROM books
+         WHERE work_id = ?
+         ORDER BY publication_year IS NULL, publication_year DESC, created_at DESC",
+    )
+    .bind(&work_id)
+    .fetch_all(pool.get_ref())
+    .await?;
+
+    let data = book_responses(pool.get_ref(), books).await?;
+    Ok(HttpResponse::Ok().json(serde_json::json!({
+        "total": data.len(),
+        "data": data
+    })))
+}
+
+async fn fetch_work(pool: &MySqlPool, work_id: &str) -> AppResult<Work> {
+    sqlx::query_as!(
+        Work,
+        r#"
+        SELECT * FROM works WHERE id = ?
+        "#,
+        work_id
+    )
+    .fetch_optional(pool)
+    .await?
+    .ok_or_else(|| AppError::not_found("Work not found"))
+}
+
+/// 创建或修改图书时校验指定的作品存在
+pub(crate) async fn ensure_work(conn: &mut MySqlConnection, work_id: &str) -> AppResult<()> {
+    let exists: i64 = sqlx::query_scalar!(
+        r#"
+        SELECT COUNT(*) FROM works WHERE id = ?
+        "#,
+        work_id
+    )
+    .fetch_one(&mut *conn)
+    .await?;
+    if exists == 0 {
+        return Err(AppError::validation(
+            "Request validation failed",
+            Some(serde_json::json!({ "work_id": [{ "code": "work", "message": "Work not found" }] })),
+        ));
+    }
+    Ok(())
+}
@@ -1,10 +1,39 @@
 pub mod config;
+pub mod error;
 pub mod models;
 pub mod handlers;
+pub mod middleware;
 pub mod utils;
+pub mod storage;
+#[cfg(feature = "search-index")]
+pub mod search_index;
 
+// 集成测试导入了 actix_web::test，会遮蔽内置的 #[test] 属性；
+// 不涉及数据库的纯计算测试因此放在各测试文件的子模块中，子模块不导入 actix_web::test
 #[cfg(test)]
 mod tests {
     pub mod user_auth_test;
     pub mod book_test;
+    pub mod auth_middleware_test;
+    pub mod validation_test;
+    pub mod isbn_test;
+    pub mod helpers;
+    pub mod loan_test;
+    pub mod hold_test;
+    pub mod fine_test;
+    pub mod patron_test;
+    pub mod policy_test;
+    pub mod item_test;
+    pub mod transfer_test;
+    pub mod call_number_test;
+    pub mod author_test;
+    pub mod category_test;
+    pub mod work_test;
+    pub mod cover_test;
+    pub mod search_test;
+    pub mod pinyin_test;
+    pub mod facet_test;
+    pub mod suggest_test;
+    #[cfg(feature = "search-index")]
+    pub mod search_index_test;
 } 
\ No newline at end of file
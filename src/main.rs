@@ -1,33 +1,439 @@
 mod models;
 mod handlers;
 mod config;
+mod error;
+mod middleware;
+mod utils;
+mod storage;
+#[cfg(feature = "search-index")]
+mod search_index;
 
-use actix_web::{web, App, HttpServer};
-use handlers::{book_handler, user_handler};
+use std::sync::Arc;
+
+use actix_web::{middleware::from_fn, web, App, HttpServer};
+use handlers::{
+    author_handler, book_handler, branch_handler, category_handler, cover_handler, fine_handler,
+    hold_handler, item_handler, loan_handler, patron_handler, policy_handler, series_handler,
+    suggest_handler, transfer_handler, user_handler, work_handler,
+};
+use middleware::auth::{require_admin, require_auth, require_librarian};
+use config::AppConfig;
+use storage::{LocalStorage, Storage};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv::dotenv().ok();
-    env_logger::init();
 
-    let pool = config::database::establish_connection()
+    let config = match AppConfig::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Invalid configuration: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    env_logger::Builder::new()
+        .parse_filters(&config.log_level)
+        .init();
+
+    let pool = config::database::establish_connection(&config)
         .await
         .expect("Failed to create pool");
 
-    println!("Server running at http://localhost:8080");
+    // 启用 search-index 特性时打开进程内搜索索引，索引为空时从数据库构建。
+    // `library_management rebuild-search-index` 从数据库重建索引后退出，需先停止正在运行的服务
+    #[cfg(feature = "search-index")]
+    let search_index = {
+        let index = match search_index::SearchIndex::open(&config.search_index_dir) {
+            Ok(index) => index,
+            Err(e) => {
+                eprintln!("Failed to open search index {}: {}", config.search_index_dir, e);
+                std::process::exit(1);
+            }
+        };
+        let rebuild = std::env::args().nth(1).as_deref() == Some("rebuild-search-index");
+        if rebuild || index.num_docs() == 0 {
+            match search_index::rebuild_from_database(&index, &pool).await {
+                Ok(count) => println!("Indexed {} books into {}", count, config.search_index_dir),
+                Err(e) => {
+                    eprintln!("Error rebuilding search index: {}", e);
+                    std::process::exit(1);
+                }
+            }
+            if rebuild {
+                return Ok(());
+            }
+        }
+        web::Data::new(index)
+    };
+
+    // 首个管理员：通过 ADMIN_USERNAME / ADMIN_EMAIL / ADMIN_PASSWORD 或配置文件 [admin] 引导创建
+    if let Some(admin) = &config.admin {
+        match user_handler::bootstrap_admin(&pool, &admin.username, &admin.email, &admin.password).await {
+            Ok(true) => println!("Bootstrapped admin account '{}'", admin.username),
+            Ok(false) => {}
+            Err(e) => eprintln!("Error bootstrapping admin: {}", e),
+        }
+    }
+
+    // 定期清理过期未取的预约，把副本顺延给队列中的下一位
+    let sweep_pool = pool.clone();
+    let pickup_days = config.hold_pickup_days;
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(std::time::Duration::from_secs(600));
+        loop {
+            interval.tick().await;
+            if let Err(e) = hold_handler::process_expired_holds(&sweep_pool, pickup_days).await {
+                log::error!("Error expiring holds: {}", e);
+            }
+        }
+    });
+
+    // 定期为未归还的逾期借阅补记罚款
+    let fine_pool = pool.clone();
+    let fine_config = config.clone();
+    actix_rt::spawn(async move {
+        let mut interval = actix_rt::time::interval(std::time::Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            if let Err(e) = fine_handler::assess_overdue_fines(&fine_pool, &fine_config).await {
+                log::error!("Error assessing overdue fines: {}", e);
+            }
+        }
+    });
+
+    // 封面等上传文件默认保存在本地目录；storage_base_url 为路径时由本服务直接提供访问
+    if let Err(e) = std::fs::create_dir_all(&config.storage_dir) {
+        eprintln!("Failed to create storage directory {}: {}", config.storage_dir, e);
+        std::process::exit(1);
+    }
+    let storage: Arc<dyn Storage> = Arc::new(LocalStorage::new(&config.storage_dir, &config.storage_base_url));
+    let storage = web::Data::from(storage);
+
+    let suggest_cache = web::Data::new(suggest_handler::SuggestCache::new(std::time::Duration::from_secs(
+        config.suggest_refresh_seconds,
+    )));
+
+    let bind_address = config.bind_address();
+    println!("Server running at http://{}:{}", bind_address.0, bind_address.1);
+
+    let config = web::Data::new(config);
 
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .app_data(config.clone())
+            .app_data(storage.clone())
+            .app_data(suggest_cache.clone())
+            .app_data(error::json_config())
+            .app_data(error::path_config())
+            .app_data(error::query_config())
             .service(
                 web::scope("/api")
                     .service(
                         web::scope("/books")
+                            .wrap(from_fn(require_auth))
                             .route("", web::get().to(book_handler::list_books))
-                            .route("", web::post().to(book_handler::create_book))
+                            .route(
+                                "",
+                                web::post()
+                                    .to(book_handler::create_book)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/suggest", web::get().to(suggest_handler::suggest))
                             .route("/{id}", web::get().to(book_handler::get_book))
-                            .route("/{id}", web::put().to(book_handler::update_book))
-                            .route("/{id}", web::delete().to(book_handler::delete_book)),
+                            .route(
+                                "/{id}",
+                                web::put()
+                                    .to(book_handler::update_book)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}",
+                                web::delete()
+                                    .to(book_handler::delete_book)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}/cover",
+                                web::put()
+                                    .to(cover_handler::upload_cover)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}/cover",
+                                web::delete()
+                                    .to(cover_handler::delete_cover)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}/items", web::get().to(item_handler::list_items))
+                            .route(
+                                "/{id}/items",
+                                web::post()
+                                    .to(item_handler::create_item)
+                                    .wrap(from_fn(require_librarian)),
+                            ),
+                    )
+                    .service(
+                        web::scope("/authors")
+                            .wrap(from_fn(require_auth))
+                            .route("", web::get().to(author_handler::list_authors))
+                            .route(
+                                "",
+                                web::post()
+                                    .to(author_handler::create_author)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}", web::get().to(author_handler::get_author))
+                            .route(
+                                "/{id}",
+                                web::put()
+                                    .to(author_handler::update_author)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}",
+                                web::delete()
+                                    .to(author_handler::delete_author)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}/books", web::get().to(author_handler::list_author_books)),
+                    )
+                    .service(
+                        web::scope("/works")
+                            .wrap(from_fn(require_auth))
+                            .route("", web::get().to(work_handler::list_works))
+                            .route(
+                                "",
+                                web::post()
+                                    .to(work_handler::create_work)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}", web::get().to(work_handler::get_work))
+                            .route(
+                                "/{id}",
+                                web::put()
+                                    .to(work_handler::update_work)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}",
+                                web::delete()
+                                    .to(work_handler::delete_work)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}/books", web::get().to(work_handler::list_work_books)),
+                    )
+                    .service(
+                        web::scope("/series")
+                            .wrap(from_fn(require_auth))
+                            .route("", web::get().to(series_handler::list_series))
+                            .route(
+                                "",
+                                web::post()
+                                    .to(series_handler::create_series)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}", web::get().to(series_handler::get_series))
+                            .route(
+                                "/{id}",
+                                web::put()
+                                    .to(series_handler::update_series)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}",
+                                web::delete()
+                                    .to(series_handler::delete_series)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}/books", web::get().to(series_handler::list_series_books)),
+                    )
+                    .service(
+                        web::scope("/categories")
+                            .wrap(from_fn(require_auth))
+                            .route("", web::get().to(category_handler::list_categories))
+                            .route(
+                                "",
+                                web::post()
+                                    .to(category_handler::create_category)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}", web::get().to(category_handler::get_category))
+                            .route(
+                                "/{id}",
+                                web::put()
+                                    .to(category_handler::update_category)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}",
+                                web::delete()
+                                    .to(category_handler::delete_category)
+                                    .wrap(from_fn(require_librarian)),
+                            ),
+                    )
+                    .service(
+                        web::scope("/items")
+                            .wrap(from_fn(require_auth))
+                            .route("/{id}", web::get().to(item_handler::get_item))
+                            .route(
+                                "/{id}",
+                                web::put()
+                                    .to(item_handler::update_item)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}",
+                                web::delete()
+                                    .to(item_handler::delete_item)
+                                    .wrap(from_fn(require_librarian)),
+                            ),
+                    )
+                    .service(
+                        web::scope("/users")
+                            .wrap(from_fn(require_auth))
+                            .route("/{id}", web::get().to(patron_handler::get_profile))
+                            .route(
+                                "/{id}/role",
+                                web::put()
+                                    .to(user_handler::update_user_role)
+                                    .wrap(from_fn(require_admin)),
+                            )
+                            .route(
+                                "/{id}/category",
+                                web::put()
+                                    .to(user_handler::update_user_category)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}/block",
+                                web::post()
+                                    .to(patron_handler::block_user)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}/unblock",
+                                web::post()
+                                    .to(patron_handler::unblock_user)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}/loans", web::get().to(loan_handler::list_user_loans))
+                            .route("/{id}/holds", web::get().to(hold_handler::list_user_holds))
+                            .route("/{id}/fines", web::get().to(fine_handler::list_fines))
+                            .route("/{id}/fines/balance", web::get().to(fine_handler::get_balance))
+                            .route(
+                                "/{id}/fines/payments",
+                                web::post()
+                                    .to(fine_handler::record_payment)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}/fines/waivers",
+                                web::post()
+                                    .to(fine_handler::waive_fine)
+                                    .wrap(from_fn(require_librarian)),
+                            ),
+                    )
+                    .service(
+                        web::scope("/loans")
+                            .wrap(from_fn(require_auth))
+                            .route("", web::post().to(loan_handler::checkout))
+                            .route("/{id}/renew", web::post().to(loan_handler::renew_loan))
+                            .route(
+                                "/{id}/return",
+                                web::post()
+                                    .to(loan_handler::return_loan)
+                                    .wrap(from_fn(require_librarian)),
+                            ),
+                    )
+                    .service(
+                        web::scope("/policies")
+                            .wrap(from_fn(require_librarian))
+                            .wrap(from_fn(require_auth))
+                            .route("", web::get().to(policy_handler::list_policies))
+                            .route(
+                                "",
+                                web::post()
+                                    .to(policy_handler::create_policy)
+                                    .wrap(from_fn(require_admin)),
+                            )
+                            .route("/resolve", web::get().to(policy_handler::resolve_policy))
+                            .route("/{id}", web::get().to(policy_handler::get_policy))
+                            .route(
+                                "/{id}",
+                                web::put()
+                                    .to(policy_handler::update_policy)
+                                    .wrap(from_fn(require_admin)),
+                            )
+                            .route(
+                                "/{id}",
+                                web::delete()
+                                    .to(policy_handler::delete_policy)
+                                    .wrap(from_fn(require_admin)),
+                            ),
+                    )
+                    .service(
+                        web::scope("/holds")
+                            .wrap(from_fn(require_auth))
+                            .route("", web::post().to(hold_handler::place_hold))
+                            .route(
+                                "/ready",
+                                web::get()
+                                    .to(hold_handler::list_ready_holds)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}", web::get().to(hold_handler::get_hold))
+                            .route("/{id}", web::delete().to(hold_handler::cancel_hold)),
+                    )
+                    .service(
+                        web::scope("/branches")
+                            .wrap(from_fn(require_auth))
+                            .route("", web::get().to(branch_handler::list_branches))
+                            .route(
+                                "",
+                                web::post()
+                                    .to(branch_handler::create_branch)
+                                    .wrap(from_fn(require_admin)),
+                            )
+                            .route("/{id}", web::get().to(branch_handler::get_branch))
+                            .route(
+                                "/{id}",
+                                web::put()
+                                    .to(branch_handler::update_branch)
+                                    .wrap(from_fn(require_admin)),
+                            )
+                            .route(
+                                "/{id}",
+                                web::delete()
+                                    .to(branch_handler::delete_branch)
+                                    .wrap(from_fn(require_admin)),
+                            ),
+                    )
+                    .service(
+                        web::scope("/transfers")
+                            .wrap(from_fn(require_auth))
+                            .route(
+                                "",
+                                web::get()
+                                    .to(transfer_handler::list_transfers)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("", web::post().to(transfer_handler::create_transfer))
+                            .route("/{id}", web::get().to(transfer_handler::get_transfer))
+                            .route(
+                                "/{id}/ship",
+                                web::post()
+                                    .to(transfer_handler::ship_transfer)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route(
+                                "/{id}/receive",
+                                web::post()
+                                    .to(transfer_handler::receive_transfer)
+                                    .wrap(from_fn(require_librarian)),
+                            )
+                            .route("/{id}/cancel", web::post().to(transfer_handler::cancel_transfer)),
                     )
                     .service(
                         web::scope("/auth")
@@ -36,8 +442,15 @@ async fn main() -> std::io::Result<()> {
                             .route("/logout", web::post().to(user_handler::logout)),
                     ),
             )
+            .configure(|cfg| {
+                #[cfg(feature = "search-index")]
+                cfg.app_data(search_index.clone());
+                if config.storage_base_url.starts_with('/') {
+                    cfg.service(actix_files::Files::new(&config.storage_base_url, &config.storage_dir));
+                }
+            })
     })
-    .bind("127.0.0.1:8080")?
+    .bind(bind_address)?
     .run()
     .await
 }
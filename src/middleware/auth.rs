@@ -0,0 +1,143 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use actix_web::{
+    body::MessageBody,
+    dev::{Payload, ServiceRequest, ServiceResponse},
+    middleware::Next,
+    web, Error, FromRequest, HttpMessage, HttpRequest,
+};
+use chrono::Utc;
+use sqlx::MySqlPool;
+
+use crate::config::AppConfig;
+use crate::error::AppError;
+use crate::models::user::Role;
+use crate::utils::jwt::verify_token;
+
+/// 通过认证的调用方，由认证中间件写入请求扩展，处理函数可直接作为参数提取
+#[derive(Debug, Clone)]
+pub struct AuthenticatedUser {
+    pub user_id: String,
+    pub role: Role,
+}
+
+impl AuthenticatedUser {
+    pub fn has_role(&self, required: Role) -> bool {
+        self.role >= required
+    }
+
+    /// 本人或图书管理员及以上角色可以操作该用户的数据
+    pub fn can_act_for(&self, user_id: &str) -> bool {
+        self.user_id == user_id || self.has_role(Role::Librarian)
+    }
+}
+
+/// 作用于需要鉴权的 scope：校验 Bearer 令牌并将调用方信息写入请求扩展
+pub async fn require_auth(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    let user = authenticate(req.request()).await?;
+    req.extensions_mut().insert(user);
+    next.call(req).await
+}
+
+/// 路由级权限声明：要求调用方至少为图书管理员，需位于 require_auth 之内
+pub async fn require_librarian(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    check_role(&req, Role::Librarian)?;
+    next.call(req).await
+}
+
+/// 路由级权限声明：要求调用方为管理员，需位于 require_auth 之内
+pub async fn require_admin(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> Result<ServiceResponse<impl MessageBody>, Error> {
+    check_role(&req, Role::Admin)?;
+    next.call(req).await
+}
+
+fn check_role(req: &ServiceRequest, required: Role) -> Result<(), Error> {
+    match req.extensions().get::<AuthenticatedUser>() {
+        Some(user) if user.has_role(required) => Ok(()),
+        Some(_) => Err(forbidden("Insufficient role for this operation")),
+        None => Err(unauthorized("Missing bearer token")),
+    }
+}
+
+impl FromRequest for AuthenticatedUser {
+    type Error = Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let req = req.clone();
+        Box::pin(async move {
+            let cached = req.extensions().get::<AuthenticatedUser>().cloned();
+            match cached {
+                Some(user) => Ok(user),
+                None => authenticate(&req).await,
+            }
+        })
+    }
+}
+
+pub(crate) fn bearer_token(req: &HttpRequest) -> Option<&str> {
+    let value = req.headers().get("Authorization")?.to_str().ok()?;
+    let token = value.strip_prefix("Bearer ")?.trim();
+    if token.is_empty() {
+        None
+    } else {
+        Some(token)
+    }
+}
+
+async fn authenticate(req: &HttpRequest) -> Result<AuthenticatedUser, Error> {
+    let token = bearer_token(req).ok_or_else(|| unauthorized("Missing bearer token"))?;
+    let config = req
+        .app_data::<web::Data<AppConfig>>()
+        .ok_or_else(|| internal_error("Application config not configured"))?;
+    let claims = verify_token(token, config).map_err(|_| unauthorized("Invalid or expired token"))?;
+
+    let pool = req
+        .app_data::<web::Data<MySqlPool>>()
+        .ok_or_else(|| internal_error("Database pool not configured"))?;
+
+    // 令牌必须仍存在于 tokens 表中，登出后的令牌会被拒绝
+    let session = sqlx::query!(
+        r#"
+        SELECT u.id, u.role
+        FROM tokens t
+        JOIN users u ON u.id = t.user_id
+        WHERE t.token = ? AND t.expires_at > ?
+        "#,
+        token,
+        Utc::now()
+    )
+    .fetch_optional(pool.get_ref())
+    .await
+    .map_err(AppError::from)?;
+
+    match session {
+        Some(session) if session.id == claims.sub => Ok(AuthenticatedUser {
+            role: Role::parse(&session.role).unwrap_or(Role::Member),
+            user_id: session.id,
+        }),
+        _ => Err(unauthorized("Token has been revoked")),
+    }
+}
+
+fn unauthorized(message: &'static str) -> Error {
+    AppError::unauthorized(message).into()
+}
+
+fn forbidden(message: &'static str) -> Error {
+    AppError::forbidden(message).into()
+}
+
+fn internal_error(message: &'static str) -> Error {
+    AppError::Internal(message.to_string()).into()
+}
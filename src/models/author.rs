@@ -0,0 +1,106 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::{Validate, ValidationError};
+
+use crate::models::book::not_blank;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Author {
+    pub id: String,
+    pub name: String,
+    /// 简介或生卒年等，用于区分同名作者
+    pub bio: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateAuthor {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub name: String,
+    #[validate(length(max = 5000))]
+    pub bio: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateAuthor {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub name: Option<String>,
+    #[validate(length(max = 5000))]
+    pub bio: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorQuery {
+    pub page_no: Option<i64>,
+    pub page_size: Option<i64>,
+    /// 按姓名模糊匹配
+    pub name: Option<String>,
+}
+
+/// 作者在某本书中的责任方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthorRole {
+    #[default]
+    Author,
+    Translator,
+    Editor,
+    Illustrator,
+}
+
+impl AuthorRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthorRole::Author => "author",
+            AuthorRole::Translator => "translator",
+            AuthorRole::Editor => "editor",
+            AuthorRole::Illustrator => "illustrator",
+        }
+    }
+}
+
+/// 创建或修改图书时指定的责任者，按数组顺序排列
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookAuthorInput {
+    pub author_id: String,
+    #[serde(default)]
+    pub role: AuthorRole,
+}
+
+/// 图书响应中的责任者
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct BookAuthor {
+    #[serde(skip)]
+    pub book_id: String,
+    pub id: String,
+    pub name: String,
+    pub role: String,
+    pub position: i32,
+}
+
+/// 同一作者不能以相同的责任方式重复出现
+pub(crate) fn distinct_authors(authors: &[BookAuthorInput]) -> Result<(), ValidationError> {
+    for (i, author) in authors.iter().enumerate() {
+        if authors[..i]
+            .iter()
+            .any(|other| other.author_id == author.author_id && other.role == author.role)
+        {
+            return Err(ValidationError::new("duplicate_author"));
+        }
+    }
+    Ok(())
+}
+
+/// 拆分著录的作者字符串，如 "刘慈欣、王晋康" 或 "Kernighan and Ritchie"；与迁移脚本的拆分规则一致
+pub fn split_author_names(author: &str) -> Vec<String> {
+    author
+        .replace(" and ", "、")
+        .replace([',', '，'], "、")
+        .split('、')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
@@ -3,36 +3,489 @@ use sqlx::FromRow;
 use chrono::{DateTime, Utc, NaiveDateTime};
 use sqlx::types::chrono::NaiveDateTime as SqlxNaiveDateTime;
 use uuid::Uuid;
+use std::fmt;
+use std::str::FromStr;
+use validator::{Validate, ValidationError};
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+use crate::models::author::{distinct_authors, BookAuthor, BookAuthorInput};
+use crate::models::call_number::CallNumber;
+use crate::models::category::{distinct_categories, BookCategory};
+use crate::models::cover::BookCover;
+use crate::models::item::ItemCounts;
+use crate::models::search::SearchQuery;
+
+/// books.type 允许的取值
+pub const BOOK_TYPES: &[&str] = &["general", "reference", "textbook", "periodical", "thesis", "rare"];
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Book {
     pub id: String,
     pub title: String,
+    /// 题名的全拼和首字母，用于按拼音检索，不返回给客户端
+    #[serde(skip)]
+    pub title_pinyin: String,
+    #[serde(skip)]
+    pub title_initials: String,
+    pub subtitle: Option<String>,
+    pub original_title: Option<String>,
     pub author: String,
+    #[serde(skip)]
+    pub author_pinyin: String,
+    #[serde(skip)]
+    pub author_initials: String,
     pub isbn: String,
     pub description: Option<String>,
     pub r#type: String,
-    pub quantity: i32,
+    pub publisher: Option<String>,
+    pub publication_year: Option<i32>,
+    /// 版本说明，如 "第2版"、"修订版"
+    pub edition: Option<String>,
+    /// ISO 639 语种代码，如 zh、en
+    pub language: Option<String>,
+    pub pages: Option<i32>,
+    pub call_number: Option<String>,
+    /// 按排架顺序排序用的键，由索书号计算得出，不返回给客户端
+    #[serde(skip)]
+    pub call_number_sort: Option<String>,
+    /// 所属作品，同一作品的其他版本共享该 id
+    pub work_id: Option<String>,
+    pub series_id: Option<String>,
+    /// 在丛书中的卷次
+    pub series_volume: Option<i32>,
     pub created_at: chrono::NaiveDateTime,
     pub updated_at: chrono::NaiveDateTime,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize)]
+pub struct BookResponse {
+    #[serde(flatten)]
+    pub book: Book,
+    pub isbn_display: String,
+    /// 按顺序排列的责任者
+    pub authors: Vec<BookAuthor>,
+    pub categories: Vec<BookCategory>,
+    /// 未上传封面时为 null
+    pub cover: Option<BookCover>,
+    #[serde(flatten)]
+    pub counts: ItemCounts,
+}
+
+/// 单本图书详情：附带同一作品的其他版本和丛书的下一卷
+#[derive(Debug, Serialize)]
+pub struct BookDetail {
+    #[serde(flatten)]
+    pub book: BookResponse,
+    pub other_editions: Vec<BookSummary>,
+    pub next_in_series: Option<BookSummary>,
+}
+
+/// 全文检索结果：附带相关度和命中字段的高亮片段
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub book: BookResponse,
+    pub score: f64,
+    pub highlights: Highlights,
+}
+
+/// 用 <mark></mark> 标出命中部分，其余文本已做 HTML 转义；未命中的字段为 null
+#[derive(Debug, Serialize)]
+pub struct Highlights {
+    pub title: Option<String>,
+    pub subtitle: Option<String>,
+    pub author: Option<String>,
+    /// 简介只截取第一处命中附近的片段
+    pub description: Option<String>,
+}
+
+const SNIPPET_CHARS: usize = 160;
+
+impl SearchHit {
+    pub fn new(book: BookResponse, score: f64, search: &SearchQuery) -> Self {
+        let highlights = Highlights {
+            title: search.highlight(&book.book.title, usize::MAX),
+            subtitle: book.book.subtitle.as_deref().and_then(|subtitle| search.highlight(subtitle, usize::MAX)),
+            author: search.highlight(&book.book.author, usize::MAX),
+            description: book
+                .book
+                .description
+                .as_deref()
+                .and_then(|description| search.highlight(description, SNIPPET_CHARS)),
+        };
+        SearchHit { book, score, highlights }
+    }
+}
+
+/// 图书列表的分面统计，均按当前过滤条件计算，按数量倒序排列。
+/// 每个取值的 value 可直接作为对应过滤参数：type、category_id、author_id、language、decade、available
+#[derive(Debug, Serialize)]
+pub struct BookFacets {
+    pub r#type: Vec<FacetCount>,
+    /// 已按分类过滤时为所选分类的子分类，否则为顶级分类；数量包含子孙分类中的图书
+    pub category: Vec<FacetCount>,
+    pub author: Vec<FacetCount>,
+    pub language: Vec<FacetCount>,
+    /// 出版年所在年代，如 "1990" 表示 1990~1999 年
+    pub decade: Vec<FacetCount>,
+    /// "true" 为有在架可借单册的图书
+    pub availability: Vec<FacetCount>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct FacetCount {
+    pub value: String,
+    /// 分类、作者的显示名称
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub count: i64,
+}
+
+/// 关联图书的简要信息
+#[derive(Debug, Serialize, FromRow)]
+pub struct BookSummary {
+    pub id: String,
+    pub title: String,
+    pub author: String,
+    pub edition: Option<String>,
+    pub publication_year: Option<i32>,
+    pub language: Option<String>,
+    pub series_volume: Option<i32>,
+}
+
+impl BookResponse {
+    pub fn new(
+        book: Book,
+        authors: Vec<BookAuthor>,
+        categories: Vec<BookCategory>,
+        cover: Option<BookCover>,
+        counts: ItemCounts,
+    ) -> Self {
+        let isbn_display = Isbn::parse(&book.isbn)
+            .map(|isbn| isbn.hyphenated())
+            .unwrap_or_else(|_| book.isbn.clone());
+        BookResponse { book, isbn_display, authors, categories, cover, counts }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateBook {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
     pub title: String,
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub subtitle: Option<String>,
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub original_title: Option<String>,
+    /// 著录的责任者说明，未指定 authors 时按其中的作者姓名自动关联
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
     pub author: String,
+    #[validate(length(min = 1, max = 50), custom(function = "distinct_authors"))]
+    pub authors: Option<Vec<BookAuthorInput>>,
+    #[validate(custom(function = "valid_isbn"))]
     pub isbn: String,
+    #[validate(length(max = 5000))]
     pub description: Option<String>,
+    /// 馆藏类型，决定适用的借阅规则；主题分类使用 category_ids
+    #[validate(custom(function = "book_type"))]
     pub r#type: String,
+    #[validate(length(max = 20), custom(function = "distinct_categories"))]
+    pub category_ids: Option<Vec<String>>,
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub publisher: Option<String>,
+    #[validate(range(min = 1000, max = 9999))]
+    pub publication_year: Option<i32>,
+    #[validate(length(min = 1, max = 100), custom(function = "not_blank"))]
+    pub edition: Option<String>,
+    #[validate(custom(function = "language_code"))]
+    pub language: Option<String>,
+    #[validate(range(min = 1, max = 100000))]
+    pub pages: Option<i32>,
+    #[validate(length(max = 64), custom(function = "valid_call_number"))]
+    pub call_number: Option<String>,
+    pub work_id: Option<String>,
+    pub series_id: Option<String>,
+    /// 指定卷次时必须同时指定丛书
+    #[validate(range(min = 1, max = 10000))]
+    pub series_volume: Option<i32>,
+    /// 创建时自动生成的占位单册数量
+    #[validate(range(min = 0, max = 10000))]
     pub quantity: i32,
+    /// 占位单册所属分馆，为空时使用总馆
+    pub branch_id: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// 可为空的著录字段（副题名、出版信息、索书号等）以及作品、丛书传 null 表示清除，不传则不变
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct UpdateBook {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
     pub title: Option<String>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub subtitle: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub original_title: Option<Option<String>>,
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
     pub author: Option<String>,
+    /// 指定时替换图书现有的全部责任者
+    #[validate(length(min = 1, max = 50), custom(function = "distinct_authors"))]
+    pub authors: Option<Vec<BookAuthorInput>>,
+    #[validate(custom(function = "valid_isbn"))]
     pub isbn: Option<String>,
+    #[validate(length(max = 5000))]
     pub description: Option<String>,
+    #[validate(custom(function = "book_type"))]
     pub r#type: Option<String>,
-    pub quantity: Option<i32>,
-} 
\ No newline at end of file
+    /// 指定时替换图书现有的全部分类
+    #[validate(length(max = 20), custom(function = "distinct_categories"))]
+    pub category_ids: Option<Vec<String>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub publisher: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1000, max = 9999))]
+    pub publication_year: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = 1, max = 100), custom(function = "not_blank"))]
+    pub edition: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "language_code"))]
+    pub language: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 100000))]
+    pub pages: Option<Option<i32>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 64), custom(function = "valid_call_number"))]
+    pub call_number: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    pub work_id: Option<Option<String>>,
+    /// 换到其他丛书（或移出丛书）而不传 series_volume 时，原卷次一并清除
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    pub series_id: Option<Option<String>>,
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1, max = 10000))]
+    pub series_volume: Option<Option<i32>>,
+}
+
+pub(crate) fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new("blank"));
+    }
+    Ok(())
+}
+
+fn valid_isbn(value: &str) -> Result<(), ValidationError> {
+    Isbn::parse(value).map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("isbn");
+        error.message = Some(e.to_string().into());
+        error
+    })
+}
+
+fn valid_call_number(value: &str) -> Result<(), ValidationError> {
+    CallNumber::parse(value).map(|_| ()).map_err(|e| {
+        let mut error = ValidationError::new("call_number");
+        error.message = Some(e.to_string().into());
+        error
+    })
+}
+
+/// ISO 639-1 / 639-2 语种代码：2~3 位小写字母
+fn language_code(value: &str) -> Result<(), ValidationError> {
+    if !(2..=3).contains(&value.len()) || !value.chars().all(|c| c.is_ascii_lowercase()) {
+        return Err(ValidationError::new("language"));
+    }
+    Ok(())
+}
+
+fn book_type(value: &str) -> Result<(), ValidationError> {
+    if !BOOK_TYPES.contains(&value) {
+        return Err(ValidationError::new("book_type"));
+    }
+    Ok(())
+}
+
+/// ISBN，内部统一保存为 13 位数字（ISBN-10 会转换为 978 前缀的 ISBN-13）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Isbn(String);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IsbnError {
+    InvalidCharacter(char),
+    InvalidLength(usize),
+    InvalidPrefix,
+    InvalidChecksum,
+}
+
+impl fmt::Display for IsbnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IsbnError::InvalidCharacter(c) => write!(f, "invalid character '{}' in ISBN", c),
+            IsbnError::InvalidLength(len) => write!(f, "ISBN must have 10 or 13 digits, got {}", len),
+            IsbnError::InvalidPrefix => write!(f, "ISBN-13 must start with 978 or 979"),
+            IsbnError::InvalidChecksum => write!(f, "ISBN check digit does not match"),
+        }
+    }
+}
+
+impl std::error::Error for IsbnError {}
+
+impl Isbn {
+    /// 解析 ISBN-10 / ISBN-13，允许使用连字符或空格分隔
+    pub fn parse(input: &str) -> Result<Isbn, IsbnError> {
+        let mut chars = Vec::with_capacity(13);
+        for c in input.trim().chars() {
+            match c {
+                '-' | ' ' => continue,
+                '0'..='9' => chars.push(c),
+                'x' | 'X' => chars.push('X'),
+                _ => return Err(IsbnError::InvalidCharacter(c)),
+            }
+        }
+
+        match chars.len() {
+            10 => Self::from_isbn10(&chars),
+            13 => Self::from_isbn13(&chars),
+            len => Err(IsbnError::InvalidLength(len)),
+        }
+    }
+
+    fn from_isbn10(chars: &[char]) -> Result<Isbn, IsbnError> {
+        let mut sum = 0;
+        for (i, c) in chars.iter().enumerate() {
+            let value = match c {
+                'X' if i == 9 => 10,
+                'X' => return Err(IsbnError::InvalidCharacter('X')),
+                _ => c.to_digit(10).unwrap(),
+            };
+            sum += value * (10 - i as u32);
+        }
+        if sum % 11 != 0 {
+            return Err(IsbnError::InvalidChecksum);
+        }
+
+        let mut digits: Vec<u32> = vec![9, 7, 8];
+        digits.extend(chars[..9].iter().map(|c| c.to_digit(10).unwrap()));
+        digits.push(isbn13_check_digit(&digits));
+        Ok(Isbn(digits.iter().map(|d| d.to_string()).collect()))
+    }
+
+    fn from_isbn13(chars: &[char]) -> Result<Isbn, IsbnError> {
+        if chars.contains(&'X') {
+            return Err(IsbnError::InvalidCharacter('X'));
+        }
+        let digits: Vec<u32> = chars.iter().map(|c| c.to_digit(10).unwrap()).collect();
+        if digits[..3] != [9, 7, 8] && digits[..3] != [9, 7, 9] {
+            return Err(IsbnError::InvalidPrefix);
+        }
+        if isbn13_check_digit(&digits[..12]) != digits[12] {
+            return Err(IsbnError::InvalidChecksum);
+        }
+        Ok(Isbn(chars.iter().collect()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// 带连字符的显示形式，如 978-7-111-12345-6；
+    /// 未收录出版者号段的组区只拆分前缀、组区号和校验位
+    pub fn hyphenated(&self) -> String {
+        let digits = &self.0;
+        let prefix = &digits[..3];
+        let body = &digits[3..12];
+        let check = &digits[12..];
+
+        let group_len = group_length(prefix, body);
+        let (group, rest) = body.split_at(group_len);
+
+        match registrant_length(prefix, group, rest) {
+            Some(len) if len < rest.len() => {
+                let (registrant, publication) = rest.split_at(len);
+                format!("{}-{}-{}-{}-{}", prefix, group, registrant, publication, check)
+            }
+            _ => format!("{}-{}-{}-{}", prefix, group, rest, check),
+        }
+    }
+}
+
+impl FromStr for Isbn {
+    type Err = IsbnError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Isbn::parse(s)
+    }
+}
+
+impl fmt::Display for Isbn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+fn isbn13_check_digit(digits: &[u32]) -> u32 {
+    let sum: u32 = digits
+        .iter()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { *d } else { d * 3 })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+fn group_length(prefix: &str, body: &str) -> usize {
+    let leading = |n: usize| body[..n].parse::<u32>().unwrap();
+    if prefix == "979" {
+        return if body.starts_with('8') { 1 } else { 2 };
+    }
+    match leading(1) {
+        0..=5 | 7 => 1,
+        6 => 3,
+        8 => 2,
+        _ => match leading(3) {
+            900..=949 => 2,
+            950..=989 => 3,
+            _ if leading(4) <= 9989 => 4,
+            _ => 5,
+        },
+    }
+}
+
+/// 常用组区（英语区 0、1 与中国 7）的出版者号段，区间按 7 位补齐后比较
+fn registrant_length(prefix: &str, group: &str, rest: &str) -> Option<usize> {
+    const ENGLISH_0: &[(u32, u32, usize)] = &[
+        (0, 1_999_999, 2),
+        (2_000_000, 6_999_999, 3),
+        (7_000_000, 8_499_999, 4),
+        (8_500_000, 8_999_999, 5),
+        (9_000_000, 9_499_999, 6),
+        (9_500_000, 9_999_999, 7),
+    ];
+    const ENGLISH_1: &[(u32, u32, usize)] = &[
+        (0, 999_999, 2),
+        (1_000_000, 3_999_999, 3),
+        (4_000_000, 5_499_999, 4),
+        (5_500_000, 8_697_999, 5),
+        (8_698_000, 9_989_999, 6),
+        (9_990_000, 9_999_999, 7),
+    ];
+    const CHINA: &[(u32, u32, usize)] = &[
+        (0, 999_999, 2),
+        (1_000_000, 4_999_999, 3),
+        (5_000_000, 7_999_999, 4),
+        (8_000_000, 8_999_999, 5),
+        (9_000_000, 9_999_999, 6),
+    ];
+
+    let ranges = match (prefix, group) {
+        ("978", "0") => ENGLISH_0,
+        ("978", "1") => ENGLISH_1,
+        ("978", "7") => CHINA,
+        _ => return None,
+    };
+
+    let padded = format!("{:0<7}", &rest[..rest.len().min(7)]);
+    let value: u32 = padded.parse().ok()?;
+    ranges
+        .iter()
+        .find(|(start, end, _)| (*start..=*end).contains(&value))
+        .map(|(_, _, len)| *len)
+}
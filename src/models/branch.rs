@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::{Validate, ValidationError};
+
+use crate::models::book::not_blank;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Branch {
+    pub id: String,
+    pub code: String,
+    pub name: String,
+    pub address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateBranch {
+    #[validate(custom(function = "branch_code"))]
+    pub code: String,
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub name: String,
+    #[validate(length(max = 500))]
+    pub address: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateBranch {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub name: Option<String>,
+    #[validate(length(max = 500))]
+    pub address: Option<String>,
+}
+
+/// 分馆代码：大写字母、数字或下划线，最长 20 位
+fn branch_code(value: &str) -> Result<(), ValidationError> {
+    if value.is_empty()
+        || value.len() > 20
+        || !value.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+    {
+        return Err(ValidationError::new("branch_code"));
+    }
+    Ok(())
+}
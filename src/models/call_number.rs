@@ -0,0 +1,215 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// 索书号采用的分类法，由首字符区分：字母开头为中图法，数字开头为杜威十进分类法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Classification {
+    Clc,
+    Dewey,
+}
+
+/// 索书号 = 分类号 + 书次号（种次号或著者号），如 `TP311.13/123`、`005.133 K46 2019`。
+/// 比较按排架顺序而非字符串顺序：分类号逐位按小数比较（TP311.1 排在 TP311.13 之前），
+/// 种次号按整数比较（/9 排在 /10 之前），著者号中的数字按小数比较（K46 排在 K5 之前）
+#[derive(Debug, Clone)]
+pub struct CallNumber {
+    classification: Classification,
+    normalized: String,
+    sort_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CallNumberError {
+    Empty,
+    InvalidCharacter(char),
+    InvalidClass,
+    NumberTooLong,
+}
+
+impl fmt::Display for CallNumberError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CallNumberError::Empty => write!(f, "call number is empty"),
+            CallNumberError::InvalidCharacter(c) => write!(f, "invalid character '{}' in call number", c),
+            CallNumberError::InvalidClass => write!(f, "call number does not start with a valid class number"),
+            CallNumberError::NumberTooLong => write!(f, "number in call number is too long"),
+        }
+    }
+}
+
+impl std::error::Error for CallNumberError {}
+
+// 排序键中各部分的结束符，小于其他所有字符，保证较短的号码排在前面
+const END: char = '!';
+
+impl CallNumber {
+    /// 解析索书号：去掉首尾空白、字母转为大写、连续空白合并为一个空格，/ 两侧不留空格
+    pub fn parse(input: &str) -> Result<CallNumber, CallNumberError> {
+        let normalized = input
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .replace(" /", "/")
+            .replace("/ ", "/")
+            .to_ascii_uppercase();
+        let first = normalized.chars().next().ok_or(CallNumberError::Empty)?;
+
+        let (classification, sort_key) = match first {
+            'A'..='Z' => (Classification::Clc, clc_key(&normalized)?),
+            '0'..='9' => (Classification::Dewey, dewey_key(&normalized)?),
+            c => return Err(CallNumberError::InvalidCharacter(c)),
+        };
+
+        Ok(CallNumber { classification, normalized, sort_key })
+    }
+
+    pub fn classification(&self) -> Classification {
+        self.classification
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.normalized
+    }
+
+    /// 按字节比较即为排架顺序的排序键，存入数据库供 ORDER BY 使用
+    pub fn sort_key(&self) -> &str {
+        &self.sort_key
+    }
+}
+
+/// 中图法分类号：1~2 位字母类目 + 数字（每三位以 . 分隔），可带复分等辅助符号
+fn clc_key(normalized: &str) -> Result<String, CallNumberError> {
+    let (class, book_number) = split_book_number(normalized);
+    let letters: String = class.chars().take_while(|c| c.is_ascii_uppercase()).collect();
+    if letters.len() > 2 {
+        return Err(CallNumberError::InvalidClass);
+    }
+
+    let mut key = format!("1{}{}", letters, END);
+    let mut in_quote = false;
+    for c in class[letters.len()..].chars() {
+        // 辅助符号排在同级数字之前（总论复分 TP311-43 排在 TP311.1 之前），闭合符号排在最前
+        let mapped = match c {
+            '0'..='9' => c,
+            '.' => continue,
+            ')' | '>' => '"',
+            '"' if in_quote => '"',
+            '-' => '#',
+            '(' => '$',
+            '=' => '%',
+            '"' => '&',
+            '<' => '\'',
+            ':' => '(',
+            '+' => ')',
+            c => return Err(CallNumberError::InvalidCharacter(c)),
+        };
+        if c == '"' {
+            in_quote = !in_quote;
+        }
+        key.push(mapped);
+    }
+    key.push(END);
+
+    key.push_str(&book_number_key(book_number)?);
+    Ok(key)
+}
+
+/// 杜威分类号：三位整数部分，可带小数部分，如 005.133
+fn dewey_key(normalized: &str) -> Result<String, CallNumberError> {
+    let (class, book_number) = split_book_number(normalized);
+    let (integer, fraction) = class.split_once('.').unwrap_or((class, ""));
+    if integer.is_empty() || integer.len() > 3 || (class.contains('.') && fraction.is_empty()) {
+        return Err(CallNumberError::InvalidClass);
+    }
+    if let Some(c) = integer.chars().chain(fraction.chars()).find(|c| !c.is_ascii_digit()) {
+        return Err(CallNumberError::InvalidCharacter(c));
+    }
+
+    let mut key = format!("2{:0>3}{}{}", integer, fraction, END);
+    key.push_str(&book_number_key(book_number)?);
+    Ok(key)
+}
+
+/// 分类号与书次号之间以 / 或空格分隔
+fn split_book_number(normalized: &str) -> (&str, &str) {
+    match normalized.find(['/', ' ']) {
+        Some(index) => (&normalized[..index], &normalized[index + 1..]),
+        None => (normalized, ""),
+    }
+}
+
+/// 书次号、卷册号、年份等：字母原样比较，紧跟字母的数字按小数比较，其余数字按整数比较
+fn book_number_key(book_number: &str) -> Result<String, CallNumberError> {
+    let mut key = String::new();
+    for word in book_number.split([' ', '/', '.', ':', '-', ',']) {
+        let mut chars = word.chars().peekable();
+        let mut after_letters = false;
+        while let Some(&c) = chars.peek() {
+            if c.is_ascii_uppercase() {
+                while let Some(c) = chars.next_if(char::is_ascii_uppercase) {
+                    key.push(c);
+                }
+                after_letters = true;
+            } else if c.is_ascii_digit() {
+                let mut digits = String::new();
+                while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(c);
+                }
+                if after_letters {
+                    key.push_str(&digits);
+                } else {
+                    // 整数前加位数，使按字节比较的结果与数值大小一致
+                    let digits = match digits.trim_start_matches('0') {
+                        "" => "0",
+                        trimmed => trimmed,
+                    };
+                    if digits.len() > 9 {
+                        return Err(CallNumberError::NumberTooLong);
+                    }
+                    key.push(char::from(b'0' + digits.len() as u8));
+                    key.push_str(digits);
+                }
+                after_letters = false;
+            } else {
+                return Err(CallNumberError::InvalidCharacter(c));
+            }
+            key.push(END);
+        }
+    }
+    Ok(key)
+}
+
+impl PartialEq for CallNumber {
+    fn eq(&self, other: &Self) -> bool {
+        self.sort_key == other.sort_key
+    }
+}
+
+impl Eq for CallNumber {}
+
+impl PartialOrd for CallNumber {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CallNumber {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.sort_key.cmp(&other.sort_key)
+    }
+}
+
+impl FromStr for CallNumber {
+    type Err = CallNumberError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        CallNumber::parse(s)
+    }
+}
+
+impl fmt::Display for CallNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.normalized)
+    }
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::{Validate, ValidationError};
+
+use crate::models::book::not_blank;
+
+/// 主题分类，parent_id 为空表示顶级分类
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct Category {
+    pub id: String,
+    pub parent_id: Option<String>,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 分类树中的节点
+#[derive(Debug, Serialize)]
+pub struct CategoryNode {
+    #[serde(flatten)]
+    pub category: Category,
+    pub children: Vec<CategoryNode>,
+}
+
+impl CategoryNode {
+    /// 由平铺列表构建 parent_id 下的子树，同级分类保持列表中的顺序
+    pub fn children(parent_id: Option<&str>, categories: &[Category]) -> Vec<CategoryNode> {
+        categories
+            .iter()
+            .filter(|category| category.parent_id.as_deref() == parent_id)
+            .map(|category| CategoryNode {
+                category: category.clone(),
+                children: CategoryNode::children(Some(&category.id), categories),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateCategory {
+    #[validate(length(min = 1, max = 100), custom(function = "not_blank"))]
+    pub name: String,
+    pub parent_id: Option<String>,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateCategory {
+    #[validate(length(min = 1, max = 100), custom(function = "not_blank"))]
+    pub name: Option<String>,
+    /// 移动到其他分类下，不能移动到自身或其子分类下；传 null 移到顶级，不传则不变
+    #[serde(default, deserialize_with = "crate::models::present", skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Option<String>>,
+    #[validate(length(max = 1000))]
+    pub description: Option<String>,
+}
+
+/// 图书响应中的分类
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct BookCategory {
+    #[serde(skip)]
+    pub book_id: String,
+    pub id: String,
+    pub name: String,
+}
+
+/// 同一分类不能重复指定
+pub(crate) fn distinct_categories(category_ids: &[String]) -> Result<(), ValidationError> {
+    for (i, category_id) in category_ids.iter().enumerate() {
+        if category_ids[..i].contains(category_id) {
+            return Err(ValidationError::new("duplicate_category"));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::io::Cursor;
+
+use chrono::NaiveDateTime;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat, ImageReader, Limits};
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// 允许上传的封面格式：(Content-Type, 图片格式, 原图扩展名)
+const COVER_TYPES: &[(&str, ImageFormat, &str)] = &[
+    ("image/jpeg", ImageFormat::Jpeg, "jpg"),
+    ("image/png", ImageFormat::Png, "png"),
+    ("image/webp", ImageFormat::WebP, "webp"),
+];
+
+/// 原图宽高上限，防止解码超大图片占满内存
+const MAX_DIMENSION: u32 = 8000;
+const THUMBNAIL_QUALITY: u8 = 85;
+
+/// 缩略图尺寸：等比缩放到不超过 width × height，小图不放大
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ThumbnailSize {
+    pub name: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+pub const SMALL: ThumbnailSize = ThumbnailSize { name: "small", width: 120, height: 180 };
+pub const MEDIUM: ThumbnailSize = ThumbnailSize { name: "medium", width: 300, height: 450 };
+
+/// 图书响应中的封面，缩略图统一为 JPEG
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct BookCover {
+    #[serde(skip)]
+    pub book_id: String,
+    /// 存储中该版本封面的目录，更换或删除封面时整体删除
+    #[serde(skip)]
+    pub storage_key: String,
+    pub content_type: String,
+    pub width: i32,
+    pub height: i32,
+    pub url: String,
+    pub small_url: String,
+    pub medium_url: String,
+    pub updated_at: NaiveDateTime,
+}
+
+/// 校验并解码后的封面及其缩略图
+#[derive(Debug)]
+pub struct ProcessedCover {
+    pub extension: &'static str,
+    pub width: u32,
+    pub height: u32,
+    pub small: Vec<u8>,
+    pub medium: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CoverError {
+    UnsupportedType(String),
+    /// 文件内容与声明的 Content-Type 不符
+    TypeMismatch,
+    TooLarge,
+    Invalid(String),
+}
+
+impl fmt::Display for CoverError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoverError::UnsupportedType(content_type) => write!(
+                f,
+                "unsupported content type '{}', expected one of image/jpeg, image/png, image/webp",
+                content_type
+            ),
+            CoverError::TypeMismatch => write!(f, "file content does not match its content type"),
+            CoverError::TooLarge => write!(
+                f,
+                "image dimensions must not exceed {}x{}",
+                MAX_DIMENSION, MAX_DIMENSION
+            ),
+            CoverError::Invalid(message) => write!(f, "invalid image: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for CoverError {}
+
+/// 允许上传的 Content-Type，不区分大小写，忽略参数
+pub fn is_cover_type(content_type: &str) -> bool {
+    cover_type(content_type).is_some()
+}
+
+fn cover_type(content_type: &str) -> Option<(ImageFormat, &'static str)> {
+    let essence = content_type.split(';').next().unwrap_or("").trim();
+    COVER_TYPES
+        .iter()
+        .find(|(mime, _, _)| mime.eq_ignore_ascii_case(essence))
+        .map(|(_, format, extension)| (*format, *extension))
+}
+
+/// 校验图片格式与尺寸，生成小、中两种缩略图
+pub fn process_cover(content_type: &str, data: &[u8]) -> Result<ProcessedCover, CoverError> {
+    let (format, extension) =
+        cover_type(content_type).ok_or_else(|| CoverError::UnsupportedType(content_type.to_string()))?;
+    if image::guess_format(data).ok() != Some(format) {
+        return Err(CoverError::TypeMismatch);
+    }
+
+    let mut reader = ImageReader::with_format(Cursor::new(data), format);
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DIMENSION);
+    limits.max_image_height = Some(MAX_DIMENSION);
+    reader.limits(limits);
+    let image = reader.decode().map_err(|e| match e {
+        image::ImageError::Limits(_) => CoverError::TooLarge,
+        e => CoverError::Invalid(e.to_string()),
+    })?;
+
+    Ok(ProcessedCover {
+        extension,
+        width: image.width(),
+        height: image.height(),
+        small: thumbnail(&image, SMALL)?,
+        medium: thumbnail(&image, MEDIUM)?,
+    })
+}
+
+fn thumbnail(image: &DynamicImage, size: ThumbnailSize) -> Result<Vec<u8>, CoverError> {
+    let resized = if image.width() <= size.width && image.height() <= size.height {
+        image.clone()
+    } else {
+        image.thumbnail(size.width, size.height)
+    };
+
+    let mut buffer = Vec::new();
+    JpegEncoder::new_with_quality(&mut buffer, THUMBNAIL_QUALITY)
+        .encode_image(&resized.to_rgb8())
+        .map_err(|e| CoverError::Invalid(e.to_string()))?;
+    Ok(buffer)
+}
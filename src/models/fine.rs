@@ -0,0 +1,67 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+use crate::models::book::not_blank;
+
+/// 账目类型：罚款记为正数，缴费和减免记为负数，余额即所有金额之和
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FineKind {
+    Charge,
+    Payment,
+    Waiver,
+}
+
+impl FineKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FineKind::Charge => "charge",
+            FineKind::Payment => "payment",
+            FineKind::Waiver => "waiver",
+        }
+    }
+}
+
+/// 金额单位均为分
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct FineTransaction {
+    pub id: String,
+    pub user_id: String,
+    pub loan_id: Option<String>,
+    pub kind: String,
+    pub amount_cents: i64,
+    pub note: Option<String>,
+    pub recorded_by: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+/// 图书管理员登记缴费或减免
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct FineAdjustment {
+    #[validate(range(min = 1))]
+    pub amount_cents: i64,
+    #[validate(length(min = 1, max = 500), custom(function = "not_blank"))]
+    pub note: String,
+    pub loan_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FineBalance {
+    pub user_id: String,
+    pub balance_cents: i64,
+    pub charged_cents: i64,
+    pub paid_cents: i64,
+    pub waived_cents: i64,
+}
+
+/// 截至 end 的逾期罚款：不足一天按一天计，单本上限为 max_cents
+pub fn overdue_fine(due_at: NaiveDateTime, end: NaiveDateTime, daily_rate_cents: i64, max_cents: i64) -> i64 {
+    let overdue_seconds = (end - due_at).num_seconds();
+    if overdue_seconds <= 0 {
+        return 0;
+    }
+    let days = (overdue_seconds + 86_399) / 86_400;
+    days.saturating_mul(daily_rate_cents).min(max_cents)
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HoldStatus {
+    Waiting,
+    Ready,
+    Fulfilled,
+    Cancelled,
+    Expired,
+}
+
+impl HoldStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HoldStatus::Waiting => "waiting",
+            HoldStatus::Ready => "ready",
+            HoldStatus::Fulfilled => "fulfilled",
+            HoldStatus::Cancelled => "cancelled",
+            HoldStatus::Expired => "expired",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Hold {
+    pub id: String,
+    pub book_id: String,
+    pub user_id: String,
+    /// 调拨到馆后留给该读者的单册；按队列分配的预约不指定单册
+    pub item_id: Option<String>,
+    pub status: String,
+    pub ready_at: Option<chrono::NaiveDateTime>,
+    pub expires_at: Option<chrono::NaiveDateTime>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateHold {
+    pub book_id: String,
+    /// 为空时为调用方本人预约；图书管理员可代其他用户预约
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HoldResponse {
+    #[serde(flatten)]
+    pub hold: Hold,
+    /// 排队中的预约在队列中的位置（从 1 开始），其他状态为 null
+    pub queue_position: Option<i64>,
+}
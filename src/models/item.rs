@@ -0,0 +1,112 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::{Validate, ValidationError};
+
+/// 单册状态；on_loan 只能由借还书流程设置，in_transit 只能由调拨流程设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemStatus {
+    Available,
+    OnLoan,
+    Lost,
+    Damaged,
+    InRepair,
+    Withdrawn,
+    InTransit,
+}
+
+impl ItemStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ItemStatus::Available => "available",
+            ItemStatus::OnLoan => "on_loan",
+            ItemStatus::Lost => "lost",
+            ItemStatus::Damaged => "damaged",
+            ItemStatus::InRepair => "in_repair",
+            ItemStatus::Withdrawn => "withdrawn",
+            ItemStatus::InTransit => "in_transit",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Item {
+    pub id: String,
+    pub book_id: String,
+    pub home_branch_id: String,
+    pub current_branch_id: String,
+    pub barcode: String,
+    /// 排架位置，如 "3F-A12"（楼层-书架号）
+    pub shelf_location: Option<String>,
+    pub status: String,
+    pub acquired_at: Option<NaiveDate>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateItem {
+    #[validate(custom(function = "valid_barcode"))]
+    pub barcode: String,
+    /// 所属分馆，为空时使用最早创建的分馆（即总馆）
+    pub branch_id: Option<String>,
+    #[validate(length(min = 1, max = 100))]
+    pub shelf_location: Option<String>,
+    #[validate(custom(function = "settable_status"))]
+    pub status: Option<ItemStatus>,
+    pub acquired_at: Option<NaiveDate>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateItem {
+    #[validate(custom(function = "valid_barcode"))]
+    pub barcode: Option<String>,
+    pub home_branch_id: Option<String>,
+    #[validate(length(min = 1, max = 100))]
+    pub shelf_location: Option<String>,
+    #[validate(custom(function = "settable_status"))]
+    pub status: Option<ItemStatus>,
+    pub acquired_at: Option<NaiveDate>,
+}
+
+/// 图书响应中的馆藏统计：quantity 为未注销的册数，available 为可借册数，branches 按当前所在分馆细分
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ItemCounts {
+    pub quantity: i64,
+    pub available: i64,
+    pub branches: Vec<BranchCounts>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchCounts {
+    pub branch_id: String,
+    pub code: String,
+    pub name: String,
+    pub quantity: i64,
+    pub available: i64,
+}
+
+/// 按馆藏数量自动生成的条码，与迁移脚本生成的格式一致
+pub fn placeholder_barcode(book_id: &str, n: usize) -> String {
+    format!("P{}-{:04}", book_id.replace('-', ""), n)
+}
+
+fn valid_barcode(value: &str) -> Result<(), ValidationError> {
+    if value.is_empty()
+        || value.len() > 64
+        || !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        return Err(ValidationError::new("barcode"));
+    }
+    Ok(())
+}
+
+fn settable_status(status: &ItemStatus) -> Result<(), ValidationError> {
+    if matches!(status, ItemStatus::OnLoan | ItemStatus::InTransit) {
+        let mut error = ValidationError::new("status");
+        error.message = Some("on_loan and in_transit are set by circulation".into());
+        return Err(error);
+    }
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Loan {
+    pub id: String,
+    pub book_id: String,
+    pub item_id: Option<String>,
+    pub user_id: String,
+    pub checkout_at: chrono::NaiveDateTime,
+    pub due_at: chrono::NaiveDateTime,
+    pub returned_at: Option<chrono::NaiveDateTime>,
+    pub renewal_count: i32,
+    /// 借书时按流通规则确定的借阅条件，早期借阅为空（使用配置默认值）
+    pub loan_period_hours: Option<i32>,
+    pub max_renewals: Option<i32>,
+    pub fine_rate_cents: Option<i32>,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateLoan {
+    pub book_id: String,
+    /// 指定借出的单册条码，为空时自动选择一册在架的副本
+    pub barcode: Option<String>,
+    /// 借书所在分馆，指定后只从该分馆在架的单册中选择
+    pub branch_id: Option<String>,
+    /// 为空时为调用方本人借书；图书管理员可代其他用户办理
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoanStatus {
+    Active,
+    Returned,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LoanQuery {
+    pub status: Option<LoanStatus>,
+}
+
+/// 拒绝续借的原因，以 reason 字段返回给客户端
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenewalRefusal {
+    HoldsPending,
+    LimitReached,
+    Overdue,
+}
+
+impl RenewalRefusal {
+    pub fn code(&self) -> &'static str {
+        match self {
+            RenewalRefusal::HoldsPending => "HOLDS_PENDING",
+            RenewalRefusal::LimitReached => "RENEWAL_LIMIT_REACHED",
+            RenewalRefusal::Overdue => "OVERDUE",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            RenewalRefusal::HoldsPending => "Other patrons are waiting for this book",
+            RenewalRefusal::LimitReached => "Loan has reached the maximum number of renewals",
+            RenewalRefusal::Overdue => "Loan is overdue beyond the renewal grace period",
+        }
+    }
+}
@@ -1,2 +1,30 @@
+use serde::{Deserialize, Deserializer};
+
 pub mod user;
-pub mod book; 
\ No newline at end of file
+pub mod book;
+pub mod loan;
+pub mod hold;
+pub mod fine;
+pub mod policy;
+pub mod item;
+pub mod branch;
+pub mod transfer;
+pub mod call_number;
+pub mod author;
+pub mod category;
+pub mod work;
+pub mod series;
+pub mod cover;
+pub mod search;
+pub mod pinyin;
+pub mod suggest;
+
+/// 区分“未传”（None）和“传了 null”（Some(None)），用于修改接口中可以清空的字段，
+/// 字段需同时标注 #[serde(default)]，并以 skip_serializing_if 在序列化时省略未传的字段
+pub(crate) fn present<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
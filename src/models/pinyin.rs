@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// 常用汉字（U+4E00–U+9FFF）的拼音表，每行为 `拼音 汉字...`，不带声调，ü 记作 v。
+/// 由 ICU 的 Han-Latin 转写逐字生成，多音字只取默认读音
+const PINYIN_TABLE: &str = include_str!("pinyin.txt");
+
+fn table() -> &'static HashMap<char, &'static str> {
+    static TABLE: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        PINYIN_TABLE
+            .lines()
+            .filter_map(|line| line.split_once(' '))
+            .flat_map(|(pinyin, chars)| chars.chars().map(move |c| (c, pinyin)))
+            .collect()
+    })
+}
+
+/// 单个汉字的拼音，不在表中时返回 None
+pub fn pinyin_of(c: char) -> Option<&'static str> {
+    table().get(&c).copied()
+}
+
+/// 全拼：只转换汉字，其余字符（包括英文字母和数字）都去掉，如 "三体2：黑暗森林" → "santiheiansenlin"。
+/// 英文原文已由原文匹配覆盖，若也写入拼音列，英文检索词会在拼接后的文字中误命中，如 "ok" 命中 "The Book"
+pub fn full(text: &str) -> String {
+    convert(text, |pinyin| pinyin)
+}
+
+/// 首字母：每个汉字取拼音首字母，其余字符同 full 去掉，如 "三体" → "st"
+pub fn initials(text: &str) -> String {
+    convert(text, |pinyin| &pinyin[..1])
+}
+
+fn convert(text: &str, part: fn(&'static str) -> &'static str) -> String {
+    let mut output = String::new();
+    for pinyin in text.chars().filter_map(pinyin_of) {
+        output.push_str(part(pinyin));
+    }
+    output
+}
+
+/// 用户输入可能是拼音时（只含 ASCII 字母数字和空白，且至少有一个字母）返回去掉空白的小写形式，
+/// 用于匹配全拼和首字母列，如 "San Ti" → "santi"
+pub fn search_key(input: &str) -> Option<String> {
+    let key: String = input
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    let valid = key.chars().all(|c| c.is_ascii_alphanumeric()) && key.chars().any(|c| c.is_ascii_alphabetic());
+    valid.then_some(key)
+}
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::{Validate, ValidationError};
+
+use crate::config::AppConfig;
+use crate::models::book::BOOK_TYPES;
+use crate::models::user::PATRON_CATEGORIES;
+
+/// 规则键中表示“任意”的取值
+pub const ANY: &str = "*";
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CirculationPolicy {
+    pub id: String,
+    pub patron_category: String,
+    pub book_type: String,
+    pub loan_period_hours: i32,
+    pub max_renewals: i32,
+    pub fine_rate_cents: i32,
+    pub max_loans: i32,
+    pub created_at: chrono::NaiveDateTime,
+    pub updated_at: chrono::NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreatePolicy {
+    #[validate(custom(function = "policy_category"))]
+    pub patron_category: String,
+    #[validate(custom(function = "policy_book_type"))]
+    pub book_type: String,
+    #[validate(range(min = 1, max = 8760))]
+    pub loan_period_hours: i32,
+    #[validate(range(min = 0, max = 100))]
+    pub max_renewals: i32,
+    #[validate(range(min = 0, max = 100000))]
+    pub fine_rate_cents: i32,
+    #[validate(range(min = 1, max = 1000))]
+    pub max_loans: i32,
+}
+
+/// 规则键（读者类别、图书类型）不可修改，如需调整请删除后重建
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdatePolicy {
+    #[validate(range(min = 1, max = 8760))]
+    pub loan_period_hours: i32,
+    #[validate(range(min = 0, max = 100))]
+    pub max_renewals: i32,
+    #[validate(range(min = 0, max = 100000))]
+    pub fine_rate_cents: i32,
+    #[validate(range(min = 1, max = 1000))]
+    pub max_loans: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveQuery {
+    pub patron_category: String,
+    pub book_type: String,
+}
+
+/// 某次借阅适用的借阅条件；policy_id 为空表示使用配置默认值
+#[derive(Debug, Clone, Serialize)]
+pub struct LoanTerms {
+    pub policy_id: Option<String>,
+    pub loan_period_hours: i32,
+    pub max_renewals: i32,
+    pub fine_rate_cents: i32,
+    pub max_loans: i32,
+}
+
+impl LoanTerms {
+    /// AppConfig::load 已拒绝超出 i32 范围的配置；其他途径构造的配置超出范围时取上限，不会回绕成负数
+    pub fn fallback(config: &AppConfig) -> Self {
+        LoanTerms {
+            policy_id: None,
+            loan_period_hours: config.loan_period_hours().unwrap_or(i32::MAX),
+            max_renewals: config.max_renewals,
+            fine_rate_cents: i32::try_from(config.fine_daily_rate_cents).unwrap_or(i32::MAX),
+            max_loans: i32::try_from(config.max_concurrent_loans).unwrap_or(i32::MAX),
+        }
+    }
+}
+
+impl From<CirculationPolicy> for LoanTerms {
+    fn from(policy: CirculationPolicy) -> Self {
+        LoanTerms {
+            policy_id: Some(policy.id),
+            loan_period_hours: policy.loan_period_hours,
+            max_renewals: policy.max_renewals,
+            fine_rate_cents: policy.fine_rate_cents,
+            max_loans: policy.max_loans,
+        }
+    }
+}
+
+fn policy_category(value: &str) -> Result<(), ValidationError> {
+    if value != ANY && !PATRON_CATEGORIES.contains(&value) {
+        return Err(ValidationError::new("patron_category"));
+    }
+    Ok(())
+}
+
+fn policy_book_type(value: &str) -> Result<(), ValidationError> {
+    if value != ANY && !BOOK_TYPES.contains(&value) {
+        return Err(ValidationError::new("book_type"));
+    }
+    Ok(())
+}
@@ -0,0 +1,221 @@
+use std::fmt;
+
+/// 检索词：普通词、以 * 结尾的前缀词、双引号括起的短语
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchTerm {
+    Word(String),
+    Prefix(String),
+    Phrase(String),
+}
+
+impl SearchTerm {
+    pub fn text(&self) -> &str {
+        match self {
+            SearchTerm::Word(text) | SearchTerm::Prefix(text) | SearchTerm::Phrase(text) => text,
+        }
+    }
+}
+
+/// 解析后的检索式，各检索词之间为“与”的关系
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    terms: Vec<SearchTerm>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchError {
+    Empty,
+    TooManyTerms,
+}
+
+impl fmt::Display for SearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SearchError::Empty => write!(f, "search query contains no searchable terms"),
+            SearchError::TooManyTerms => write!(f, "search query has more than {} terms", MAX_TERMS),
+        }
+    }
+}
+
+impl std::error::Error for SearchError {}
+
+const MAX_TERMS: usize = 10;
+
+// MySQL 布尔模式中有特殊含义的字符，用户输入中一律当作分隔符
+const OPERATORS: &[char] = &['+', '-', '<', '>', '(', ')', '~', '@', '"', '*', '\''];
+
+impl SearchQuery {
+    /// 解析用户输入，如 `三体 "黑暗 森林" data*`；未闭合的引号视为短语直到末尾
+    pub fn parse(input: &str) -> Result<SearchQuery, SearchError> {
+        let mut terms = Vec::new();
+        let mut rest = input;
+
+        while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+            rest = &rest[start..];
+            if let Some(quoted) = rest.strip_prefix('"') {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                let words = words(&quoted[..end]);
+                match words.len() {
+                    0 => {}
+                    1 => terms.push(SearchTerm::Word(words.into_iter().next().unwrap())),
+                    _ => terms.push(SearchTerm::Phrase(words.join(" "))),
+                }
+                rest = quoted.get(end + 1..).unwrap_or("");
+            } else {
+                let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+                let token = &rest[..end];
+                let token_words = words(token);
+                let last = token_words.len().saturating_sub(1);
+                for (i, word) in token_words.into_iter().enumerate() {
+                    // 只有紧挨 * 的最后一段作为前缀
+                    if token.ends_with('*') && i == last {
+                        terms.push(SearchTerm::Prefix(word));
+                    } else {
+                        terms.push(SearchTerm::Word(word));
+                    }
+                }
+                rest = &rest[end..];
+            }
+        }
+
+        terms.dedup();
+        if terms.is_empty() {
+            return Err(SearchError::Empty);
+        }
+        if terms.len() > MAX_TERMS {
+            return Err(SearchError::TooManyTerms);
+        }
+        Ok(SearchQuery { terms })
+    }
+
+    pub fn terms(&self) -> &[SearchTerm] {
+        &self.terms
+    }
+
+    /// 转换为 MATCH ... AGAINST (? IN BOOLEAN MODE) 的检索式，每个检索词都必须出现
+    pub fn boolean_mode(&self) -> String {
+        self.terms
+            .iter()
+            .map(|term| match term {
+                SearchTerm::Word(word) => format!("+{}", word),
+                SearchTerm::Prefix(prefix) => format!("+{}*", prefix),
+                SearchTerm::Phrase(phrase) => format!("+\"{}\"", phrase),
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// 用 <mark></mark> 标出文本中命中的检索词，超过 max_chars 时截取第一处命中附近的片段。
+    /// 其余文本做 HTML 转义；没有命中时返回 None
+    pub fn highlight(&self, text: &str, max_chars: usize) -> Option<String> {
+        let chars: Vec<char> = text.chars().collect();
+        let ranges = self.match_ranges(&chars);
+        let first = ranges.first()?;
+
+        // 片段从第一处命中前约四分之一处开始，尽量让命中位置靠前
+        let (start, end) = if chars.len() <= max_chars {
+            (0, chars.len())
+        } else {
+            let start = first.0.saturating_sub(max_chars / 4).min(chars.len() - max_chars);
+            (start, start + max_chars)
+        };
+
+        let mut snippet = String::new();
+        if start > 0 {
+            snippet.push('…');
+        }
+        let mut position = start;
+        for &(match_start, match_end) in &ranges {
+            if match_end <= start || match_start >= end {
+                continue;
+            }
+            let match_start = match_start.max(start);
+            let match_end = match_end.min(end);
+            push_escaped(&mut snippet, &chars[position..match_start]);
+            snippet.push_str("<mark>");
+            push_escaped(&mut snippet, &chars[match_start..match_end]);
+            snippet.push_str("</mark>");
+            position = match_end;
+        }
+        push_escaped(&mut snippet, &chars[position..end]);
+        if end < chars.len() {
+            snippet.push('…');
+        }
+        Some(snippet)
+    }
+
+    /// 所有命中位置（字符下标，左闭右开），按起点排序并合并重叠部分
+    fn match_ranges(&self, chars: &[char]) -> Vec<(usize, usize)> {
+        let mut ranges = Vec::new();
+        for term in &self.terms {
+            let (needle, prefix) = match term {
+                SearchTerm::Word(word) => (word.as_str(), false),
+                SearchTerm::Prefix(prefix) => (prefix.as_str(), true),
+                SearchTerm::Phrase(phrase) => (phrase.as_str(), false),
+            };
+            let needle: Vec<char> = needle.chars().collect();
+            let mut i = 0;
+            while i + needle.len() <= chars.len() {
+                if matches_at(chars, i, &needle) {
+                    let mut end = i + needle.len();
+                    // 前缀词高亮到所在单词末尾
+                    if prefix {
+                        while end < chars.len() && chars[end].is_alphanumeric() && !is_cjk(chars[end]) {
+                            end += 1;
+                        }
+                    }
+                    ranges.push((i, end));
+                    i = end;
+                } else {
+                    i += 1;
+                }
+            }
+        }
+
+        ranges.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+    }
+}
+
+/// 去掉布尔模式运算符后按空白拆分
+fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| c.is_whitespace() || OPERATORS.contains(&c))
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// 不区分大小写比较；短语中的空格可以匹配任意连续空白
+fn matches_at(chars: &[char], start: usize, needle: &[char]) -> bool {
+    needle.iter().enumerate().all(|(offset, expected)| {
+        let actual = chars[start + offset];
+        if expected.is_whitespace() {
+            actual.is_whitespace()
+        } else {
+            actual == *expected || actual.to_lowercase().eq(expected.to_lowercase())
+        }
+    })
+}
+
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c, '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}' | '\u{ac00}'..='\u{d7af}' | '\u{f900}'..='\u{faff}')
+}
+
+fn push_escaped(output: &mut String, chars: &[char]) {
+    for &c in chars {
+        match c {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '&' => output.push_str("&amp;"),
+            '"' => output.push_str("&quot;"),
+            c => output.push(c),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+use crate::models::book::not_blank;
+
+/// 丛书，图书通过 series_volume 标明在丛书中的卷次
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Series {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateSeries {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub name: String,
+    #[validate(length(max = 5000))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateSeries {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub name: Option<String>,
+    #[validate(length(max = 5000))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SeriesQuery {
+    pub page_no: Option<i64>,
+    pub page_size: Option<i64>,
+    /// 按名称模糊匹配
+    pub name: Option<String>,
+}
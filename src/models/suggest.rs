@@ -0,0 +1,156 @@
+use std::cmp::Reverse;
+
+use serde::Serialize;
+
+use crate::models::pinyin;
+
+/// 补全候选项；题名和 ISBN 的 id 为图书 id，作者的 id 为作者 id
+#[derive(Debug, Clone, Serialize)]
+pub struct Suggestion {
+    pub text: String,
+    pub id: String,
+    /// 热度：借阅次数，作者为其全部图书的借阅次数之和
+    pub popularity: i64,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Suggestions {
+    pub titles: Vec<Suggestion>,
+    pub authors: Vec<Suggestion>,
+    pub isbns: Vec<Suggestion>,
+}
+
+/// 输入补全用的内存索引，由数据库定期整体重建，查询时不访问数据库
+#[derive(Debug, Default)]
+pub struct SuggestIndex {
+    titles: PrefixTable,
+    authors: PrefixTable,
+    isbns: PrefixTable,
+}
+
+/// 每个候选项可以有多个键（原文、各单词开头、全拼、首字母），键排序后按前缀二分查找
+#[derive(Debug, Default)]
+struct PrefixTable {
+    items: Vec<Suggestion>,
+    keys: Vec<(String, usize)>,
+}
+
+impl PrefixTable {
+    fn insert(&mut self, item: Suggestion, keys: impl IntoIterator<Item = String>) {
+        let index = self.items.len();
+        self.items.push(item);
+        self.keys.extend(keys.into_iter().filter(|key| !key.is_empty()).map(|key| (key, index)));
+    }
+
+    fn finish(&mut self) {
+        self.keys.sort_unstable();
+        self.keys.dedup();
+    }
+
+    fn matches<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = usize> + 'a {
+        let start = self.keys.partition_point(|(key, _)| key.as_str() < prefix);
+        self.keys[start..]
+            .iter()
+            .take_while(move |(key, _)| key.starts_with(prefix))
+            .map(|(_, index)| *index)
+    }
+
+    /// 任一前缀命中的候选项按热度倒序，同热度时短的在前；同名候选项只保留热度最高的一个。
+    /// 短前缀可能命中大量候选项，只维护有序的前 limit 个，不收集全部命中再排序
+    fn lookup(&self, prefixes: &[String], limit: usize) -> Vec<Suggestion> {
+        let mut top: Vec<&Suggestion> = Vec::with_capacity(limit + 1);
+        for prefix in prefixes {
+            for index in self.matches(prefix) {
+                let item = &self.items[index];
+                if top.len() == limit && top.last().is_some_and(|last| rank(item) >= rank(last)) {
+                    continue;
+                }
+                if let Some(position) = top.iter().position(|kept| kept.text == item.text) {
+                    if rank(item) >= rank(top[position]) {
+                        continue;
+                    }
+                    top.remove(position);
+                }
+                let position = top.partition_point(|kept| rank(kept) < rank(item));
+                top.insert(position, item);
+                top.truncate(limit);
+            }
+        }
+        top.into_iter().cloned().collect()
+    }
+}
+
+/// 排序键，越小越靠前
+fn rank(item: &Suggestion) -> (Reverse<i64>, usize, &str, &str) {
+    (Reverse(item.popularity), item.text.chars().count(), &item.text, &item.id)
+}
+
+impl SuggestIndex {
+    /// title_pinyin、title_initials 为图书表中已生成的拼音列
+    pub fn add_book(&mut self, id: &str, title: &str, title_pinyin: &str, title_initials: &str, isbn: &str, popularity: i64) {
+        let mut keys = word_keys(title);
+        keys.push(title_pinyin.to_string());
+        keys.push(title_initials.to_string());
+        self.titles.insert(Suggestion { text: title.to_string(), id: id.to_string(), popularity }, keys);
+        self.isbns.insert(
+            Suggestion { text: isbn.to_string(), id: id.to_string(), popularity },
+            [isbn.to_ascii_lowercase()],
+        );
+    }
+
+    pub fn add_author(&mut self, id: &str, name: &str, popularity: i64) {
+        let mut keys = word_keys(name);
+        keys.push(pinyin::full(name));
+        keys.push(pinyin::initials(name));
+        self.authors.insert(Suggestion { text: name.to_string(), id: id.to_string(), popularity }, keys);
+    }
+
+    /// 全部添加完成后调用
+    pub fn finish(mut self) -> SuggestIndex {
+        self.titles.finish();
+        self.authors.finish();
+        self.isbns.finish();
+        self
+    }
+
+    /// 按输入前缀返回每类最多 limit 个候选项。题名、作者匹配原文或任一单词的开头，
+    /// 只含字母数字时也匹配全拼和首字母；输入去掉连字符和空格后全是数字时匹配 ISBN
+    pub fn suggest(&self, input: &str, limit: usize) -> Suggestions {
+        let text = normalize(input);
+        if text.is_empty() {
+            return Suggestions::default();
+        }
+        let mut prefixes = vec![text];
+        if let Some(key) = pinyin::search_key(input) {
+            if !prefixes.contains(&key) {
+                prefixes.push(key);
+            }
+        }
+
+        let digits: String = input.chars().filter(|c| !matches!(c, '-' | ' ')).collect();
+        let isbns = if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) {
+            self.isbns.lookup(&[digits], limit)
+        } else {
+            Vec::new()
+        };
+
+        Suggestions {
+            titles: self.titles.lookup(&prefixes, limit),
+            authors: self.authors.lookup(&prefixes, limit),
+            isbns,
+        }
+    }
+}
+
+/// 转小写并把连续空白合并为一个空格
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+/// 原文以及从第二个单词起每个单词开头的后缀，如 "the rust book" → ["the rust book", "rust book", "book"]
+fn word_keys(text: &str) -> Vec<String> {
+    let text = normalize(text);
+    let mut keys = vec![text.clone()];
+    keys.extend(text.match_indices(' ').map(|(position, _)| text[position + 1..].to_string()));
+    keys
+}
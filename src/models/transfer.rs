@@ -0,0 +1,60 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    Requested,
+    InTransit,
+    Received,
+    Cancelled,
+}
+
+impl TransferStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransferStatus::Requested => "requested",
+            TransferStatus::InTransit => "in_transit",
+            TransferStatus::Received => "received",
+            TransferStatus::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Transfer {
+    pub id: String,
+    pub book_id: String,
+    pub item_id: Option<String>,
+    pub from_branch_id: Option<String>,
+    pub to_branch_id: String,
+    pub status: String,
+    /// 到馆后为其留书的读者，为空表示仅调整馆藏分布
+    pub user_id: Option<String>,
+    pub requested_by: String,
+    pub shipped_at: Option<NaiveDateTime>,
+    pub received_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateTransfer {
+    pub book_id: String,
+    pub to_branch_id: String,
+    /// 为空时为调用方本人申请；图书管理员可代其他读者申请
+    pub user_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ShipTransfer {
+    /// 发出的单册条码，为空时自动选择一册其他分馆在架的副本
+    pub barcode: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TransferQuery {
+    pub status: Option<TransferStatus>,
+    pub branch_id: Option<String>,
+}
@@ -2,6 +2,13 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use chrono::{DateTime, Utc, NaiveDateTime};
 use sqlx::types::chrono::NaiveDateTime as SqlxNaiveDateTime;
+use validator::{Validate, ValidationError};
+
+use crate::models::book::not_blank;
+use crate::utils::password::validate_password_strength;
+
+/// users.patron_category 允许的取值，用于匹配流通规则
+pub const PATRON_CATEGORIES: &[&str] = &["general", "student", "faculty", "staff"];
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct User {
@@ -12,29 +19,82 @@ pub struct User {
     pub email: String,
     pub password_hash: String,
     pub role: String,
+    pub patron_category: String,
+    pub blocked: bool,
+    pub block_note: Option<String>,
+    pub block_updated_by: Option<String>,
+    pub block_updated_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    Member,
+    Librarian,
+    Admin,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Member => "member",
+            Role::Librarian => "librarian",
+            Role::Admin => "admin",
+        }
+    }
+
+    /// 解析 users.role 列，早期注册的 "user" 视为 member
+    pub fn parse(value: &str) -> Option<Role> {
+        match value {
+            "member" | "user" => Some(Role::Member),
+            "librarian" => Some(Role::Librarian),
+            "admin" => Some(Role::Admin),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
 pub struct CreateUser {
+    #[validate(length(min = 3, max = 64), custom(function = "username_chars"))]
     pub username: String,
+    #[validate(email, length(max = 255))]
     pub email: String,
+    #[validate(custom(function = "validate_password_strength"))]
     pub password: String,
 }
 
+fn username_chars(value: &str) -> Result<(), ValidationError> {
+    if !value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(ValidationError::new("username_format"));
+    }
+    Ok(())
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct LoginUser {
     pub username: String,
     pub password: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateRole {
+    pub role: Role,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UserResponse {
     pub id: Uuid,
     pub username: String,
     pub email: String,
     pub role: String,
+    pub patron_category: String,
+    pub blocked: bool,
+    pub block_note: Option<String>,
+    pub block_updated_by: Option<String>,
+    pub block_updated_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -53,8 +113,90 @@ impl From<User> for UserResponse {
             username: user.username,
             email: user.email,
             role: user.role,
+            patron_category: user.patron_category,
+            blocked: user.blocked,
+            block_note: user.block_note,
+            block_updated_by: user.block_updated_by,
+            block_updated_at: user.block_updated_at,
             created_at: user.created_at,
             updated_at: user.updated_at,
         }
     }
-} 
\ No newline at end of file
+} 
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateCategory {
+    #[validate(custom(function = "patron_category"))]
+    pub patron_category: String,
+}
+
+fn patron_category(value: &str) -> Result<(), ValidationError> {
+    if !PATRON_CATEGORIES.contains(&value) {
+        return Err(ValidationError::new("patron_category"));
+    }
+    Ok(())
+}
+
+/// 图书管理员停借或解除停借时填写的说明
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct BlockUpdate {
+    #[validate(length(min = 1, max = 500), custom(function = "not_blank"))]
+    pub note: String,
+}
+
+/// 不能借书（或续借）的原因
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BlockReason {
+    ManualBlock,
+    FinesOwed,
+    TooManyOverdue,
+    LoanLimitReached,
+}
+
+impl BlockReason {
+    pub fn code(&self) -> &'static str {
+        match self {
+            BlockReason::ManualBlock => "MANUAL_BLOCK",
+            BlockReason::FinesOwed => "FINES_OWED",
+            BlockReason::TooManyOverdue => "TOO_MANY_OVERDUE",
+            BlockReason::LoanLimitReached => "LOAN_LIMIT_REACHED",
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            BlockReason::ManualBlock => "Patron has been blocked by a librarian",
+            BlockReason::FinesOwed => "Patron owes more fines than allowed",
+            BlockReason::TooManyOverdue => "Patron has too many overdue items",
+            BlockReason::LoanLimitReached => "Patron has reached the maximum number of concurrent loans",
+        }
+    }
+}
+
+/// 读者借阅资格，reasons 为空时可以借书
+#[derive(Debug, Serialize)]
+pub struct PatronStanding {
+    pub can_borrow: bool,
+    pub reasons: Vec<BlockReason>,
+    pub balance_cents: i64,
+    pub overdue_loans: i64,
+    pub active_loans: i64,
+}
+
+impl PatronStanding {
+    /// 续借不增加在借数量，因此不受在借上限限制
+    pub fn refusal(&self, checkout: bool) -> Option<BlockReason> {
+        self.reasons
+            .iter()
+            .copied()
+            .find(|reason| checkout || *reason != BlockReason::LoanLimitReached)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProfileResponse {
+    #[serde(flatten)]
+    pub user: UserResponse,
+    pub standing: PatronStanding,
+}
@@ -0,0 +1,45 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+use crate::models::book::not_blank;
+
+/// 作品：同一作品的再版、重印和译本等各版本对应多条图书记录
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Work {
+    pub id: String,
+    pub title: String,
+    pub original_title: Option<String>,
+    pub description: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct CreateWork {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub title: String,
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub original_title: Option<String>,
+    #[validate(length(max = 5000))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+pub struct UpdateWork {
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub title: Option<String>,
+    #[validate(length(min = 1, max = 255), custom(function = "not_blank"))]
+    pub original_title: Option<String>,
+    #[validate(length(max = 5000))]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WorkQuery {
+    pub page_no: Option<i64>,
+    pub page_size: Option<i64>,
+    /// 按题名或原题名模糊匹配
+    pub title: Option<String>,
+}
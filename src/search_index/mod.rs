@@ -0,0 +1,231 @@
+pub mod tokenizer;
+
+use std::path::Path;
+use std::sync::Mutex;
+
+use sqlx::MySqlPool;
+use tantivy::collector::TopDocs;
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, TermQuery};
+use tantivy::schema::{
+    Field, IndexRecordOption, Schema, TextFieldIndexing, TextOptions, Value, STORED, STRING,
+};
+use tantivy::{Index, IndexReader, IndexWriter, ReloadPolicy, TantivyDocument, Term};
+
+use crate::error::AppResult;
+use crate::models::book::Book;
+use crate::models::search::{is_cjk, SearchQuery, SearchTerm};
+use tokenizer::{query_tokens, CjkTokenizer, CJK_TOKENIZER};
+
+/// 索引写入缓冲区大小
+const WRITER_MEMORY: usize = 20_000_000;
+
+/// 进程内倒排索引，作为 MySQL 全文索引之外的可选检索方式。
+/// 数据库仍是唯一的准确数据，索引损坏或与数据库不一致时可随时从数据库重建
+pub struct SearchIndex {
+    reader: IndexReader,
+    writer: Mutex<IndexWriter>,
+    fields: Fields,
+}
+
+#[derive(Clone, Copy)]
+struct Fields {
+    id: Field,
+    title: Field,
+    subtitle: Field,
+    author: Field,
+    description: Field,
+}
+
+impl Fields {
+    /// 参与检索的字段及其权重，与 SQL 检索的相关度计算保持一致：题名最高，其次是副题名和责任者
+    fn boosted(&self) -> [(Field, f32); 4] {
+        [(self.title, 3.0), (self.subtitle, 2.0), (self.author, 2.0), (self.description, 1.0)]
+    }
+}
+
+impl SearchIndex {
+    /// 打开目录中的索引，不存在时新建
+    pub fn open(path: impl AsRef<Path>) -> tantivy::Result<SearchIndex> {
+        std::fs::create_dir_all(path.as_ref())?;
+        let (schema, _) = schema();
+        let index = Index::open_or_create(MmapDirectory::open(path)?, schema)?;
+        SearchIndex::with_index(index)
+    }
+
+    /// 内存中的索引，用于测试
+    #[cfg(test)]
+    pub fn in_memory() -> tantivy::Result<SearchIndex> {
+        let (schema, _) = schema();
+        SearchIndex::with_index(Index::create_in_ram(schema))
+    }
+
+    fn with_index(index: Index) -> tantivy::Result<SearchIndex> {
+        index.tokenizers().register(CJK_TOKENIZER, CjkTokenizer);
+        let (_, fields) = schema();
+        // 每次写入后手动刷新读端，保证接口返回后即可检索到
+        let reader = index.reader_builder().reload_policy(ReloadPolicy::Manual).try_into()?;
+        let writer = index.writer_with_num_threads(1, WRITER_MEMORY)?;
+        Ok(SearchIndex {
+            reader,
+            writer: Mutex::new(writer),
+            fields,
+        })
+    }
+
+    pub fn num_docs(&self) -> u64 {
+        self.reader.searcher().num_docs()
+    }
+
+    /// 新增或替换一本图书
+    pub fn upsert(&self, book: &Book) -> tantivy::Result<()> {
+        let mut writer = self.writer.lock().expect("search index writer poisoned");
+        writer.delete_term(Term::from_field_text(self.fields.id, &book.id));
+        writer.add_document(self.document(book))?;
+        writer.commit()?;
+        self.reader.reload()
+    }
+
+    pub fn delete(&self, book_id: &str) -> tantivy::Result<()> {
+        let mut writer = self.writer.lock().expect("search index writer poisoned");
+        writer.delete_term(Term::from_field_text(self.fields.id, book_id));
+        writer.commit()?;
+        self.reader.reload()
+    }
+
+    /// 清空后按给定图书重建，返回写入的图书数
+    pub fn rebuild(&self, books: &[Book]) -> tantivy::Result<usize> {
+        let mut writer = self.writer.lock().expect("search index writer poisoned");
+        writer.delete_all_documents()?;
+        for book in books {
+            writer.add_document(self.document(book))?;
+        }
+        writer.commit()?;
+        self.reader.reload()?;
+        Ok(books.len())
+    }
+
+    /// 按相关度返回前 limit 本图书的 id 和得分，检索词之间为“与”的关系
+    pub fn search(&self, search: &SearchQuery, limit: usize) -> tantivy::Result<Vec<(String, f32)>> {
+        let clauses: Vec<(Occur, Box<dyn Query>)> = search
+            .terms()
+            .iter()
+            .filter_map(|term| self.term_query(term))
+            .map(|query| (Occur::Must, query))
+            .collect();
+        // 检索词全是标点等无法切分的内容时不会命中任何图书
+        if clauses.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let searcher = self.reader.searcher();
+        let top_docs = searcher.search(&BooleanQuery::new(clauses), &TopDocs::with_limit(limit))?;
+        let mut hits = Vec::with_capacity(top_docs.len());
+        for (score, address) in top_docs {
+            let document: TantivyDocument = searcher.doc(address)?;
+            if let Some(id) = document.get_first(self.fields.id).and_then(|value| value.as_str()) {
+                hits.push((id.to_string(), score));
+            }
+        }
+        Ok(hits)
+    }
+
+    fn document(&self, book: &Book) -> TantivyDocument {
+        let mut document = TantivyDocument::default();
+        document.add_text(self.fields.id, &book.id);
+        document.add_text(self.fields.title, &book.title);
+        document.add_text(self.fields.author, &book.author);
+        if let Some(subtitle) = &book.subtitle {
+            document.add_text(self.fields.subtitle, subtitle);
+        }
+        if let Some(description) = &book.description {
+            document.add_text(self.fields.description, description);
+        }
+        document
+    }
+
+    /// 单个检索词在任一字段命中即可，各字段按权重计分
+    fn term_query(&self, term: &SearchTerm) -> Option<Box<dyn Query>> {
+        let (text, prefix, fuzzy) = match term {
+            SearchTerm::Word(word) => (word, false, true),
+            SearchTerm::Prefix(prefix) => (prefix, true, false),
+            SearchTerm::Phrase(phrase) => (phrase, false, false),
+        };
+        let tokens = query_tokens(text);
+        if tokens.is_empty() {
+            return None;
+        }
+
+        let clauses = self
+            .fields
+            .boosted()
+            .into_iter()
+            .map(|(field, boost)| {
+                let query = field_query(field, &tokens, prefix, fuzzy);
+                (Occur::Should, Box::new(BoostQuery::new(query, boost)) as Box<dyn Query>)
+            })
+            .collect();
+        Some(Box::new(BooleanQuery::new(clauses)))
+    }
+}
+
+fn field_query(field: Field, tokens: &[(usize, String)], prefix: bool, fuzzy: bool) -> Box<dyn Query> {
+    if tokens.len() > 1 {
+        // 多个词（短语或连续的中文）要求按原顺序相邻出现
+        let terms = tokens
+            .iter()
+            .map(|(position, text)| (*position, Term::from_field_text(field, text)))
+            .collect();
+        return Box::new(PhraseQuery::new_with_offset(terms));
+    }
+
+    let text = &tokens[0].1;
+    let term = Term::from_field_text(field, text);
+    if prefix && !text.chars().any(is_cjk) {
+        return Box::new(FuzzyTermQuery::new_prefix(term, 0, true));
+    }
+    let exact: Box<dyn Query> = Box::new(TermQuery::new(term.clone(), IndexRecordOption::WithFreqs));
+    match edit_distance(text) {
+        // 模糊匹配的得分是固定值，再加上精确匹配的得分，让拼写完全一致的结果排在前面
+        Some(distance) if fuzzy => Box::new(BooleanQuery::new(vec![
+            (Occur::Should, exact),
+            (Occur::Should, Box::new(FuzzyTermQuery::new(term, distance, true))),
+        ])),
+        _ => exact,
+    }
+}
+
+/// 字母文字的词允许的编辑距离：4 个字符起容错 1 处，8 个字符起容错 2 处；中文不做模糊匹配
+fn edit_distance(text: &str) -> Option<u8> {
+    if text.chars().any(is_cjk) {
+        return None;
+    }
+    match text.chars().count() {
+        0..=3 => None,
+        4..=7 => Some(1),
+        _ => Some(2),
+    }
+}
+
+fn schema() -> (Schema, Fields) {
+    let text = TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(CJK_TOKENIZER)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    );
+    let mut builder = Schema::builder();
+    let fields = Fields {
+        id: builder.add_text_field("id", STRING | STORED),
+        title: builder.add_text_field("title", text.clone()),
+        subtitle: builder.add_text_field("subtitle", text.clone()),
+        author: builder.add_text_field("author", text.clone()),
+        description: builder.add_text_field("description", text),
+    };
+    (builder.build(), fields)
+}
+
+/// 从数据库读取全部图书重建索引，用于首次启用或索引损坏后的恢复
+pub async fn rebuild_from_database(index: &SearchIndex, pool: &MySqlPool) -> AppResult<usize> {
+    let books = sqlx::query_as::<_, Book>("SELECT * FROM books").fetch_all(pool).await?;
+    Ok(index.rebuild(&books)?)
+}
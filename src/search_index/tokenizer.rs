@@ -0,0 +1,113 @@
+use tantivy::tokenizer::{Token, TokenStream, Tokenizer};
+
+use crate::models::search::is_cjk;
+
+/// 分词器注册名
+pub const CJK_TOKENIZER: &str = "cjk";
+
+/// 中日韩文字按单字加二元组切分，其余文字按字母数字连续段切分并转小写。
+/// 建索引时单字和以它开头的二元组位于同一位置，查询时只用二元组（单字查询除外），
+/// 这样 "三体" 既能命中 "三体" 也不会被 "三" "体" 分开出现的文档干扰
+#[derive(Clone, Default)]
+pub struct CjkTokenizer;
+
+impl Tokenizer for CjkTokenizer {
+    type TokenStream<'a> = CjkTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> CjkTokenStream {
+        CjkTokenStream {
+            tokens: tokenize(text, false),
+            index: 0,
+        }
+    }
+}
+
+pub struct CjkTokenStream {
+    tokens: Vec<Token>,
+    index: usize,
+}
+
+impl TokenStream for CjkTokenStream {
+    fn advance(&mut self) -> bool {
+        if self.index < self.tokens.len() {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.index - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.index - 1]
+    }
+}
+
+/// 查询一侧的切分结果，只保留词文本，位置与建索引时一致
+pub fn query_tokens(text: &str) -> Vec<(usize, String)> {
+    tokenize(text, true)
+        .into_iter()
+        .map(|token| (token.position, token.text))
+        .collect()
+}
+
+fn tokenize(text: &str, for_query: bool) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut position = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if is_cjk(c) {
+            let mut run = Vec::new();
+            while let Some(&(offset, c)) = chars.peek() {
+                if !is_cjk(c) {
+                    break;
+                }
+                run.push((offset, c));
+                chars.next();
+            }
+            push_cjk_run(&mut tokens, &run, text, position, for_query);
+            position += run.len();
+        } else if c.is_alphanumeric() {
+            let mut end = start;
+            while let Some(&(offset, c)) = chars.peek() {
+                if !c.is_alphanumeric() || is_cjk(c) {
+                    break;
+                }
+                end = offset + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(token(start, end, position, text[start..end].to_lowercase()));
+            position += 1;
+        } else {
+            chars.next();
+        }
+    }
+    tokens
+}
+
+fn push_cjk_run(tokens: &mut Vec<Token>, run: &[(usize, char)], text: &str, position: usize, for_query: bool) {
+    let end_of = |i: usize| run[i].0 + run[i].1.len_utf8();
+    for i in 0..run.len() {
+        let start = run[i].0;
+        if !for_query || run.len() == 1 {
+            tokens.push(token(start, end_of(i), position + i, text[start..end_of(i)].to_string()));
+        }
+        if i + 1 < run.len() {
+            tokens.push(token(start, end_of(i + 1), position + i, text[start..end_of(i + 1)].to_string()));
+        }
+    }
+}
+
+fn token(offset_from: usize, offset_to: usize, position: usize, text: String) -> Token {
+    Token {
+        offset_from,
+        offset_to,
+        position,
+        text,
+        position_length: 1,
+    }
+}
@@ -0,0 +1,29 @@
+pub mod local;
+
+use std::io;
+
+use async_trait::async_trait;
+
+pub use local::LocalStorage;
+
+/// 文件存储后端，对象以 "/" 分隔的 key 寻址，如 `covers/{book_id}/{version}/small.jpg`。
+/// 默认使用本地文件系统，需要时可换成对象存储等其他实现
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// 写入对象，已存在时覆盖
+    async fn put(&self, key: &str, content_type: &str, data: Vec<u8>) -> io::Result<()>;
+
+    /// 删除 prefix 下的全部对象，不存在时不报错
+    async fn delete_prefix(&self, prefix: &str) -> io::Result<()>;
+
+    /// 客户端访问对象使用的 URL
+    fn url(&self, key: &str) -> String;
+}
+
+/// key 只能由普通路径段组成，避免写到存储目录之外
+pub(crate) fn valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .split('/')
+            .all(|segment| !segment.is_empty() && segment != "." && segment != ".." && !segment.contains('\\'))
+}
@@ -0,0 +1,182 @@
+use actix_web::{middleware::from_fn, test, web, App};
+use uuid::Uuid;
+use crate::{
+    models::user::{CreateUser, LoginUser},
+    handlers::book_handler::{list_books, create_book},
+    handlers::user_handler::{register, login, logout},
+    middleware::auth::{require_auth, require_librarian},
+    config::{database::init_test_pool, AppConfig},
+};
+
+#[actix_rt::test]
+async fn test_books_require_token() {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .app_data(web::Data::new(config))
+            .service(
+                web::scope("/api/books")
+                    .wrap(from_fn(require_auth))
+                    .route("", web::get().to(list_books))
+            )
+    ).await;
+
+    // 未携带令牌
+    let resp = test::TestRequest::get()
+        .uri("/api/books")
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 401);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "UNAUTHORIZED");
+    assert!(body["message"].is_string());
+
+    // 伪造的令牌
+    let resp = test::TestRequest::get()
+        .uri("/api/books")
+        .insert_header(("Authorization", "Bearer not-a-jwt"))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 401);
+}
+
+#[actix_rt::test]
+async fn test_member_cannot_create_book() {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .app_data(web::Data::new(config))
+            .route("/api/auth/register", web::post().to(register))
+            .route("/api/auth/login", web::post().to(login))
+            .service(
+                web::scope("/api/books")
+                    .wrap(from_fn(require_auth))
+                    .route("", web::get().to(list_books))
+                    .route("", web::post().to(create_book).wrap(from_fn(require_librarian)))
+            )
+    ).await;
+
+    let user_data = CreateUser {
+        username: format!("member_{}", Uuid::new_v4()),
+        password: "testpass123".to_string(),
+        email: format!("member_{}@example.com", Uuid::new_v4()),
+    };
+    let resp = test::TestRequest::post()
+        .uri("/api/auth/register")
+        .set_json(&user_data)
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["role"], "member");
+
+    let login_data = LoginUser {
+        username: user_data.username.clone(),
+        password: user_data.password.clone(),
+    };
+    let resp = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(&login_data)
+        .send_request(&app)
+        .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    // member 可以查询
+    let resp = test::TestRequest::get()
+        .uri("/api/books")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+
+    // 但不能新增图书
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .set_json(serde_json::json!({
+            "title": "Forbidden",
+            "author": "Nobody",
+            "isbn": "9787111123456",
+            "description": null,
+            "type": "general",
+            "quantity": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 403);
+}
+
+#[actix_rt::test]
+async fn test_revoked_token_rejected() {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .app_data(web::Data::new(config))
+            .route("/api/auth/register", web::post().to(register))
+            .route("/api/auth/login", web::post().to(login))
+            .route("/api/auth/logout", web::post().to(logout))
+            .service(
+                web::scope("/api/books")
+                    .wrap(from_fn(require_auth))
+                    .route("", web::get().to(list_books))
+            )
+    ).await;
+
+    let user_data = CreateUser {
+        username: format!("revoked_{}", Uuid::new_v4()),
+        password: "testpass123".to_string(),
+        email: format!("revoked_{}@example.com", Uuid::new_v4()),
+    };
+    let resp = test::TestRequest::post()
+        .uri("/api/auth/register")
+        .set_json(&user_data)
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+
+    let login_data = LoginUser {
+        username: user_data.username.clone(),
+        password: user_data.password.clone(),
+    };
+    let resp = test::TestRequest::post()
+        .uri("/api/auth/login")
+        .set_json(&login_data)
+        .send_request(&app)
+        .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let token = body["token"].as_str().unwrap().to_string();
+
+    let resp = test::TestRequest::get()
+        .uri("/api/books")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+
+    let resp = test::TestRequest::post()
+        .uri("/api/auth/logout")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    // 登出后令牌签名仍然有效，但已从 tokens 表撤销
+    let resp = test::TestRequest::get()
+        .uri("/api/books")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 401);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "UNAUTHORIZED");
+}
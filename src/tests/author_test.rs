@@ -0,0 +1,206 @@
+use actix_web::{test, web, App, dev::Service};
+use uuid::Uuid;
+use crate::{
+    config::database::init_test_pool,
+    handlers::author_handler::{create_author, delete_author, list_author_books},
+    handlers::book_handler::{create_book, get_book, list_books, update_book},
+    tests::book_test::random_isbn,
+};
+
+mod splitting {
+    use crate::models::author::split_author_names;
+
+    #[test]
+    fn test_split_author_names() {
+        assert_eq!(split_author_names("刘慈欣"), ["刘慈欣"]);
+        assert_eq!(split_author_names("刘慈欣、王晋康"), ["刘慈欣", "王晋康"]);
+        assert_eq!(split_author_names("Kernighan and Ritchie"), ["Kernighan", "Ritchie"]);
+        assert_eq!(split_author_names("A, B，C"), ["A", "B", "C"]);
+        assert_eq!(split_author_names(" A 、、 B "), ["A", "B"]);
+    }
+}
+
+async fn setup_test_app() -> impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .route("/api/authors", web::post().to(create_author))
+            .route("/api/authors/{id}", web::delete().to(delete_author))
+            .route("/api/authors/{id}/books", web::get().to(list_author_books))
+            .route("/api/books", web::post().to(create_book))
+            .route("/api/books", web::get().to(list_books))
+            .route("/api/books/{id}", web::get().to(get_book))
+            .route("/api/books/{id}", web::put().to(update_book))
+    ).await
+}
+
+async fn new_author(app: &impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, name: &str) -> String {
+    let resp = test::TestRequest::post()
+        .uri("/api/authors")
+        .set_json(serde_json::json!({ "name": name }))
+        .send_request(app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let author: serde_json::Value = test::read_body_json(resp).await;
+    author["id"].as_str().unwrap().to_string()
+}
+
+#[actix_rt::test]
+async fn test_book_authors_with_roles() {
+    let app = setup_test_app().await;
+    let author_id = new_author(&app, "Homonym").await;
+    let namesake_id = new_author(&app, "Homonym").await;
+    let translator_id = new_author(&app, &format!("Translator {}", Uuid::new_v4())).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("Test Book {}", Uuid::new_v4()),
+            "author": "Homonym 著，Translator 译",
+            "authors": [
+                { "author_id": author_id },
+                { "author_id": translator_id, "role": "translator" }
+            ],
+            "isbn": random_isbn(),
+            "type": "general",
+            "quantity": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    let book_id = book["id"].as_str().unwrap().to_string();
+    assert_eq!(book["authors"][0]["id"], author_id.as_str());
+    assert_eq!(book["authors"][0]["role"], "author");
+    assert_eq!(book["authors"][1]["id"], translator_id.as_str());
+    assert_eq!(book["authors"][1]["role"], "translator");
+    assert_eq!(book["authors"][1]["position"], 2);
+
+    // 同名作者按 id 区分
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?author_id={}", author_id))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 1);
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/authors/{}/books", namesake_id))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 0);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/authors/{}/books", translator_id))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["data"][0]["id"], book_id.as_str());
+
+    // 有关联图书的作者不能删除
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/authors/{}", translator_id))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    // 修改时整体替换责任者
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({ "authors": [{ "author_id": namesake_id, "role": "editor" }] }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["authors"].as_array().unwrap().len(), 1);
+    assert_eq!(book["authors"][0]["role"], "editor");
+
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/authors/{}", translator_id))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 204);
+}
+
+#[actix_rt::test]
+async fn test_author_string_is_split_into_authors() {
+    let app = setup_test_app().await;
+    let first = format!("First {}", Uuid::new_v4());
+    let second = format!("Second {}", Uuid::new_v4());
+    let existing_id = new_author(&app, &second).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("Test Book {}", Uuid::new_v4()),
+            "author": format!("{}、{}", first, second),
+            "isbn": random_isbn(),
+            "type": "general",
+            "quantity": 0
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    let authors = book["authors"].as_array().unwrap();
+    assert_eq!(authors.len(), 2);
+    assert_eq!(authors[0]["name"], first.as_str());
+    assert_eq!(authors[1]["id"], existing_id.as_str());
+
+    // 不存在的作者 id 返回 400
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("Test Book {}", Uuid::new_v4()),
+            "author": "Nobody",
+            "authors": [{ "author_id": Uuid::new_v4() }],
+            "isbn": random_isbn(),
+            "type": "general",
+            "quantity": 0
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_changing_author_string_relinks_authors() {
+    let app = setup_test_app().await;
+    let original = format!("Original {}", Uuid::new_v4());
+    let replacement = format!("Replacement {}", Uuid::new_v4());
+    let original_id = new_author(&app, &original).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("Test Book {}", Uuid::new_v4()),
+            "author": original,
+            "isbn": random_isbn(),
+            "type": "general",
+            "quantity": 0
+        }))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    let book_id = book["id"].as_str().unwrap().to_string();
+    assert_eq!(book["authors"][0]["id"], original_id.as_str());
+
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({ "author": replacement }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    let authors = book["authors"].as_array().unwrap();
+    assert_eq!(authors.len(), 1);
+    assert_eq!(authors[0]["name"], replacement.as_str());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?author_id={}", original_id))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 0);
+}
@@ -5,14 +5,17 @@ use crate::{
     models::book::{Book, CreateBook, UpdateBook},
     handlers::book_handler::{create_book, get_book, update_book, delete_book, list_books},
     config::database::init_test_pool,
+    tests::helpers::test_storage,
 };
 
-async fn setup_test_app() -> impl Service<actix_web::dev::ServiceRequest, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+async fn setup_test_app() -> impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
     let pool = init_test_pool().await.expect("Failed to create test database pool");
-    
+    let (storage, _) = test_storage();
+
     test::init_service(
         App::new()
             .app_data(web::Data::new(pool))
+            .app_data(storage)
             .service(
                 web::scope("/api")
                     .service(web::resource("/books").route(web::post().to(create_book)))
@@ -24,17 +27,45 @@ async fn setup_test_app() -> impl Service<actix_web::dev::ServiceRequest, Respon
     ).await
 }
 
+// 生成带正确校验位的 ISBN-13，避免与已有数据冲突
+pub fn random_isbn() -> String {
+    let seed = Uuid::new_v4().as_u128();
+    let mut digits: Vec<u32> = vec![9, 7, 8];
+    digits.extend((0..9).map(|i| ((seed >> (i * 4)) % 10) as u32));
+    let sum: u32 = digits
+        .iter()
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { *d } else { d * 3 })
+        .sum();
+    digits.push((10 - sum % 10) % 10);
+    digits.iter().map(|d| d.to_string()).collect()
+}
+
 #[actix_rt::test]
 async fn test_create_book() {
     let app = setup_test_app().await;
 
     let book_data = CreateBook {
         title: format!("Test Book {}", Uuid::new_v4()),
+        subtitle: None,
+        original_title: None,
         author: "Test Author".to_string(),
-        isbn: format!("{}", Uuid::new_v4()),
+        authors: None,
+        category_ids: None,
+        work_id: None,
+        series_id: None,
+        series_volume: None,
+        isbn: random_isbn(),
         description: Some("Test Description".to_string()),
-        r#type: "test".to_string(),
+        r#type: "general".to_string(),
+        publisher: None,
+        publication_year: None,
+        edition: None,
+        language: None,
+        pages: None,
+        call_number: None,
         quantity: 10,
+        branch_id: None,
     };
 
     let resp = test::TestRequest::post()
@@ -47,6 +78,8 @@ async fn test_create_book() {
     
     let body: serde_json::Value = test::read_body_json(resp).await;
     assert!(body["id"].is_string());
+    assert_eq!(body["quantity"], 10);
+    assert_eq!(body["available"], 10);
 }
 
 #[actix_rt::test]
@@ -56,11 +89,25 @@ async fn test_get_book() {
     // 先创建一本书
     let book_data = CreateBook {
         title: format!("Test Book {}", Uuid::new_v4()),
+        subtitle: None,
+        original_title: None,
         author: "Test Author".to_string(),
-        isbn: format!("{}", Uuid::new_v4()),
+        authors: None,
+        category_ids: None,
+        work_id: None,
+        series_id: None,
+        series_volume: None,
+        isbn: random_isbn(),
         description: Some("Test Description".to_string()),
-        r#type: "test".to_string(),
+        r#type: "general".to_string(),
+        publisher: None,
+        publication_year: None,
+        edition: None,
+        language: None,
+        pages: None,
+        call_number: None,
         quantity: 10,
+        branch_id: None,
     };
 
     let create_resp = test::TestRequest::post()
@@ -91,11 +138,25 @@ async fn test_update_book() {
     // 先创建一本书
     let book_data = CreateBook {
         title: format!("Test Book {}", Uuid::new_v4()),
+        subtitle: None,
+        original_title: None,
         author: "Test Author".to_string(),
-        isbn: format!("{}", Uuid::new_v4()),
+        authors: None,
+        category_ids: None,
+        work_id: None,
+        series_id: None,
+        series_volume: None,
+        isbn: random_isbn(),
         description: Some("Test Description".to_string()),
-        r#type: "test".to_string(),
+        r#type: "general".to_string(),
+        publisher: None,
+        publication_year: None,
+        edition: None,
+        language: None,
+        pages: None,
+        call_number: None,
         quantity: 10,
+        branch_id: None,
     };
 
     let create_resp = test::TestRequest::post()
@@ -110,11 +171,23 @@ async fn test_update_book() {
     // 测试更新图书
     let update_data = UpdateBook {
         title: Some("Updated Title".to_string()),
+        subtitle: None,
+        original_title: None,
         author: None,
+        authors: None,
+        category_ids: None,
+        work_id: None,
+        series_id: None,
+        series_volume: None,
         isbn: None,
         description: None,
         r#type: None,
-        quantity: None,
+        publisher: None,
+        publication_year: None,
+        edition: None,
+        language: None,
+        pages: None,
+        call_number: None,
     };
 
     let update_resp = test::TestRequest::put()
@@ -136,11 +209,25 @@ async fn test_delete_book() {
     // 先创建一本书
     let book_data = CreateBook {
         title: format!("Test Book {}", Uuid::new_v4()),
+        subtitle: None,
+        original_title: None,
         author: "Test Author".to_string(),
-        isbn: format!("{}", Uuid::new_v4()),
+        authors: None,
+        category_ids: None,
+        work_id: None,
+        series_id: None,
+        series_volume: None,
+        isbn: random_isbn(),
         description: Some("Test Description".to_string()),
-        r#type: "test".to_string(),
+        r#type: "general".to_string(),
+        publisher: None,
+        publication_year: None,
+        edition: None,
+        language: None,
+        pages: None,
+        call_number: None,
         quantity: 10,
+        branch_id: None,
     };
 
     let create_resp = test::TestRequest::post()
@@ -169,11 +256,25 @@ async fn test_list_books() {
     for _ in 0..3 {
         let book_data = CreateBook {
             title: format!("Test Book {}", Uuid::new_v4()),
+            subtitle: None,
+            original_title: None,
             author: "Test Author".to_string(),
-            isbn: format!("{}", Uuid::new_v4()),
+            authors: None,
+            category_ids: None,
+            work_id: None,
+            series_id: None,
+            series_volume: None,
+            isbn: random_isbn(),
             description: Some("Test Description".to_string()),
-            r#type: "test".to_string(),
+            r#type: "general".to_string(),
+            publisher: None,
+            publication_year: None,
+            edition: None,
+            language: None,
+            pages: None,
+            call_number: None,
             quantity: 10,
+            branch_id: None,
         };
 
         let create_resp = test::TestRequest::post()
@@ -208,4 +309,257 @@ async fn test_list_books() {
     let body: serde_json::Value = test::read_body_json(search_resp).await;
     assert!(body["total"].is_number());
     assert!(body["data"].is_array());
-} 
\ No newline at end of file
+}
+
+#[actix_rt::test]
+async fn test_create_book_validation() {
+    let app = setup_test_app().await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": "",
+            "author": "Test Author",
+            "isbn": random_isbn(),
+            "description": null,
+            "type": "unknown",
+            "quantity": -1
+        }))
+        .send_request(&app)
+        .await;
+
+    assert_eq!(resp.status(), 400);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "VALIDATION_FAILED");
+    assert!(body["details"]["title"].is_array());
+    assert!(body["details"]["type"].is_array());
+    assert!(body["details"]["quantity"].is_array());
+}
+
+#[actix_rt::test]
+async fn test_isbn_normalization() {
+    let app = setup_test_app().await;
+
+    let isbn = random_isbn();
+    let hyphenated = format!("{}-{}-{}", &isbn[..3], &isbn[3..8], &isbn[8..]);
+
+    let book_data = CreateBook {
+        title: format!("Test Book {}", Uuid::new_v4()),
+        subtitle: None,
+        original_title: None,
+        author: "Test Author".to_string(),
+        authors: None,
+        category_ids: None,
+        work_id: None,
+        series_id: None,
+        series_volume: None,
+        isbn: hyphenated.clone(),
+        description: None,
+        r#type: "general".to_string(),
+        publisher: None,
+        publication_year: None,
+        edition: None,
+        language: None,
+        pages: None,
+        call_number: None,
+        quantity: 1,
+        branch_id: None,
+    };
+
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(&book_data)
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["isbn"], isbn);
+    assert!(body["isbn_display"].as_str().unwrap().contains('-'));
+
+    // 同一 ISBN 的不同写法视为重复
+    let duplicate = CreateBook { isbn: isbn.clone(), ..book_data };
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(&duplicate)
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    // 按任意格式的 ISBN 查询
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?isbn={}", hyphenated))
+        .send_request(&app)
+        .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["total"], 1);
+}
+
+#[actix_rt::test]
+async fn test_list_books_in_shelf_order() {
+    let app = setup_test_app().await;
+    let tag = Uuid::new_v4().simple().to_string();
+
+    for call_number in ["TP311.13/2", "TP311.1/10", "TP311.1/9"] {
+        let book_data = CreateBook {
+            title: format!("Shelf {} {}", tag, call_number),
+            subtitle: None,
+            original_title: None,
+            author: "Test Author".to_string(),
+            authors: None,
+            category_ids: None,
+            work_id: None,
+            series_id: None,
+            series_volume: None,
+            isbn: random_isbn(),
+            description: None,
+            r#type: "general".to_string(),
+            publisher: None,
+            publication_year: None,
+            edition: None,
+            language: None,
+            pages: None,
+            call_number: Some(call_number.to_lowercase()),
+            quantity: 0,
+            branch_id: None,
+        };
+        let resp = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(&book_data)
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["call_number"], call_number);
+    }
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?title={}&sort=call_number", tag))
+        .send_request(&app)
+        .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    let call_numbers: Vec<&str> = body["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|book| book["call_number"].as_str().unwrap())
+        .collect();
+    assert_eq!(call_numbers, ["TP311.1/9", "TP311.1/10", "TP311.13/2"]);
+    assert!(body["data"][0].get("call_number_sort").is_none());
+}
+
+#[actix_rt::test]
+async fn test_bibliographic_fields_and_filters() {
+    let app = setup_test_app().await;
+    let publisher = format!("Publisher {}", Uuid::new_v4());
+
+    for (year, language) in [(1998, "en"), (2008, "zh"), (2015, "zh")] {
+        let resp = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(serde_json::json!({
+                "title": format!("Test Book {}", Uuid::new_v4()),
+                "subtitle": "A Subtitle",
+                "original_title": "Original Title",
+                "author": "Test Author",
+                "isbn": random_isbn(),
+                "type": "general",
+                "publisher": publisher,
+                "publication_year": year,
+                "edition": "第2版",
+                "language": language,
+                "pages": 300,
+                "quantity": 0
+            }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+        let body: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(body["publication_year"], year);
+        assert_eq!(body["edition"], "第2版");
+        assert_eq!(body["subtitle"], "A Subtitle");
+    }
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?publisher={}&year_from=2000&year_to=2010", publisher))
+        .send_request(&app)
+        .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["data"][0]["publication_year"], 2008);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?publisher={}&language=zh&year_from=2000", publisher))
+        .send_request(&app)
+        .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["total"], 2);
+
+    let resp = test::TestRequest::get()
+        .uri("/api/books?year_from=2010&year_to=2000")
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_update_book_clears_optional_fields() {
+    let app = setup_test_app().await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("Test Book {}", Uuid::new_v4()),
+            "subtitle": "A Subtitle",
+            "original_title": "Original Title",
+            "author": "Test Author",
+            "isbn": random_isbn(),
+            "type": "general",
+            "publisher": "Test Publisher",
+            "publication_year": 2008,
+            "edition": "第2版",
+            "language": "en",
+            "pages": 300,
+            "call_number": "TP311.13/123",
+            "quantity": 0
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    let book_id = book["id"].as_str().unwrap().to_string();
+
+    // 不传的字段保持不变
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({ "edition": "第3版" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["edition"], "第3版");
+    assert_eq!(book["publisher"], "Test Publisher");
+    assert_eq!(book["call_number"], "TP311.13/123");
+
+    // 传 null 清除
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({
+            "subtitle": null,
+            "original_title": null,
+            "publisher": null,
+            "publication_year": null,
+            "edition": null,
+            "language": null,
+            "pages": null,
+            "call_number": null
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    for field in ["subtitle", "original_title", "publisher", "publication_year", "edition", "language", "pages", "call_number"] {
+        assert!(book[field].is_null(), "{}", field);
+    }
+    assert_eq!(book["author"], "Test Author");
+}
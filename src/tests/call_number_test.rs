@@ -0,0 +1,61 @@
+use crate::models::call_number::{CallNumber, CallNumberError, Classification};
+
+fn sorted(call_numbers: &[&str]) -> Vec<String> {
+    let mut parsed: Vec<CallNumber> = call_numbers.iter().map(|s| CallNumber::parse(s).unwrap()).collect();
+    parsed.sort();
+    parsed.iter().map(|call_number| call_number.to_string()).collect()
+}
+
+#[test]
+fn test_clc_shelf_order() {
+    let expected = [
+        "TP3", "TP31", "TP311", "TP311-43", "TP311.1", "TP311.1/9", "TP311.1/10", "TP311.13",
+        "TP311.13/123", "TP311.13/123:2", "TP311.2", "TP312", "TQ1",
+    ];
+    let mut shuffled = expected.to_vec();
+    shuffled.reverse();
+    shuffled.swap(0, 5);
+    assert_eq!(sorted(&shuffled), expected);
+
+    // 单字母类目排在同首字母的双字母类目之前
+    assert!(CallNumber::parse("T-0").unwrap() < CallNumber::parse("T1").unwrap());
+    assert!(CallNumber::parse("T1").unwrap() < CallNumber::parse("TB1").unwrap());
+}
+
+#[test]
+fn test_dewey_shelf_order() {
+    let expected = ["005.1 K5", "005.133 K46", "005.133 K5", "005.133 K5 2019", "005.2", "100", "823.914 R68"];
+    let mut shuffled = expected.to_vec();
+    shuffled.reverse();
+    assert_eq!(sorted(&shuffled), expected);
+}
+
+#[test]
+fn test_normalization() {
+    let call_number = CallNumber::parse("  tp311.13 / 123 ").unwrap();
+    assert_eq!(call_number.as_str(), "TP311.13/123");
+    assert_eq!(call_number.classification(), Classification::Clc);
+    assert_eq!(call_number, CallNumber::parse("TP311.13 123").unwrap());
+    assert_eq!(CallNumber::parse("5.1").unwrap().classification(), Classification::Dewey);
+    assert_eq!(CallNumber::parse("5.1").unwrap(), CallNumber::parse("005.1").unwrap());
+}
+
+#[test]
+fn test_sort_key_matches_ordering() {
+    let a = CallNumber::parse("TP311.1/9").unwrap();
+    let b = CallNumber::parse("TP311.1/10").unwrap();
+    assert!(a < b);
+    assert!(a.sort_key() < b.sort_key());
+    // 字符串顺序与排架顺序相反的情形
+    assert!(a.as_str() > b.as_str());
+}
+
+#[test]
+fn test_invalid_call_numbers() {
+    assert_eq!(CallNumber::parse("   "), Err(CallNumberError::Empty));
+    assert_eq!(CallNumber::parse("TPX311"), Err(CallNumberError::InvalidClass));
+    assert_eq!(CallNumber::parse("1234.5"), Err(CallNumberError::InvalidClass));
+    assert_eq!(CallNumber::parse("TP311#1"), Err(CallNumberError::InvalidCharacter('#')));
+    assert_eq!(CallNumber::parse("#TP311"), Err(CallNumberError::InvalidCharacter('#')));
+    assert_eq!(CallNumber::parse("TP311/12345678901"), Err(CallNumberError::NumberTooLong));
+}
@@ -0,0 +1,196 @@
+use actix_web::{test, web, App, dev::Service};
+use uuid::Uuid;
+use crate::{
+    config::database::init_test_pool,
+    handlers::book_handler::{create_book, list_books, update_book},
+    handlers::category_handler::{create_category, delete_category, get_category, list_categories, update_category},
+    tests::book_test::random_isbn,
+};
+
+async fn setup_test_app() -> impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .route("/api/categories", web::get().to(list_categories))
+            .route("/api/categories", web::post().to(create_category))
+            .route("/api/categories/{id}", web::get().to(get_category))
+            .route("/api/categories/{id}", web::put().to(update_category))
+            .route("/api/categories/{id}", web::delete().to(delete_category))
+            .route("/api/books", web::post().to(create_book))
+            .route("/api/books", web::get().to(list_books))
+            .route("/api/books/{id}", web::put().to(update_book))
+    ).await
+}
+
+async fn new_category(app: &impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, parent_id: Option<&str>) -> String {
+    let resp = test::TestRequest::post()
+        .uri("/api/categories")
+        .set_json(serde_json::json!({
+            "name": format!("Category {}", Uuid::new_v4()),
+            "parent_id": parent_id
+        }))
+        .send_request(app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let category: serde_json::Value = test::read_body_json(resp).await;
+    category["id"].as_str().unwrap().to_string()
+}
+
+#[actix_rt::test]
+async fn test_category_tree_and_descendant_filter() {
+    let app = setup_test_app().await;
+    let science = new_category(&app, None).await;
+    let computing = new_category(&app, Some(&science)).await;
+    let databases = new_category(&app, Some(&computing)).await;
+    let other = new_category(&app, None).await;
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/categories/{}", science))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let tree: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(tree["children"][0]["id"], computing.as_str());
+    assert_eq!(tree["children"][0]["children"][0]["id"], databases.as_str());
+
+    let resp = test::TestRequest::get()
+        .uri("/api/categories")
+        .send_request(&app)
+        .await;
+    let roots: serde_json::Value = test::read_body_json(resp).await;
+    assert!(roots["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|node| node["id"] == science.as_str()));
+
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("Test Book {}", Uuid::new_v4()),
+            "author": "Test Author",
+            "isbn": random_isbn(),
+            "type": "general",
+            "category_ids": [databases, other],
+            "quantity": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    let book_id = book["id"].as_str().unwrap().to_string();
+    assert_eq!(book["categories"].as_array().unwrap().len(), 2);
+
+    // 按祖先分类过滤时包含子孙分类下的图书
+    for category_id in [&science, &computing, &databases, &other] {
+        let resp = test::TestRequest::get()
+            .uri(&format!("/api/books?category_id={}", category_id))
+            .send_request(&app)
+            .await;
+        let books: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(books["total"], 1);
+        assert_eq!(books["data"][0]["id"], book_id.as_str());
+    }
+
+    // 修改时整体替换分类
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({ "category_ids": [other] }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?category_id={}", science))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 0);
+
+    // 不存在的分类
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({ "category_ids": [Uuid::new_v4().to_string()] }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_category_move_and_delete() {
+    let app = setup_test_app().await;
+    let parent = new_category(&app, None).await;
+    let child = new_category(&app, Some(&parent)).await;
+    let grandchild = new_category(&app, Some(&child)).await;
+
+    // 不能移动到自身或子孙分类下
+    for target in [&parent, &grandchild] {
+        let resp = test::TestRequest::put()
+            .uri(&format!("/api/categories/{}", parent))
+            .set_json(serde_json::json!({ "parent_id": target }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    let other = new_category(&app, None).await;
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/categories/{}", child))
+        .set_json(serde_json::json!({ "parent_id": other }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let moved: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(moved["parent_id"], other.as_str());
+
+    // 仍有子分类的分类不能删除
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/categories/{}", child))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    for category_id in [&grandchild, &child, &parent, &other] {
+        let resp = test::TestRequest::delete()
+            .uri(&format!("/api/categories/{}", category_id))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 204);
+    }
+}
+
+#[actix_rt::test]
+async fn test_category_move_to_top_level() {
+    let app = setup_test_app().await;
+    let parent = new_category(&app, None).await;
+    let child = new_category(&app, Some(&parent)).await;
+
+    // 不传 parent_id 时保持原上级
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/categories/{}", child))
+        .set_json(serde_json::json!({ "description": "renamed" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let updated: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(updated["parent_id"], parent.as_str());
+
+    // 显式传 null 移到顶级
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/categories/{}", child))
+        .set_json(serde_json::json!({ "parent_id": null }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let moved: serde_json::Value = test::read_body_json(resp).await;
+    assert!(moved["parent_id"].is_null());
+    assert_eq!(moved["description"], "renamed");
+
+    for category_id in [&child, &parent] {
+        let resp = test::TestRequest::delete()
+            .uri(&format!("/api/categories/{}", category_id))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 204);
+    }
+}
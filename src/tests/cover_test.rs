@@ -0,0 +1,188 @@
+use std::io::Cursor;
+use actix_web::{test, web, App};
+use image::{DynamicImage, ImageFormat, Rgb, RgbImage};
+use uuid::Uuid;
+use crate::{
+    config::{database::init_test_pool, AppConfig},
+    handlers::book_handler::{create_book, delete_book, get_book},
+    handlers::cover_handler::{delete_cover, upload_cover},
+    tests::{book_test::random_isbn, helpers::test_storage},
+};
+
+fn encode(width: u32, height: u32, format: ImageFormat) -> Vec<u8> {
+    let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(width, height, Rgb([200, 80, 40])));
+    let mut buffer = Cursor::new(Vec::new());
+    image.write_to(&mut buffer, format).expect("Failed to encode test image");
+    buffer.into_inner()
+}
+
+mod processing {
+    use image::ImageFormat;
+    use super::encode;
+    use crate::models::cover::{is_cover_type, process_cover, CoverError};
+    use crate::storage::valid_key;
+
+    fn dimensions(data: &[u8]) -> (u32, u32) {
+        let image = image::load_from_memory_with_format(data, ImageFormat::Jpeg).expect("thumbnail is a JPEG");
+        (image.width(), image.height())
+    }
+
+    #[test]
+    fn test_thumbnails_keep_aspect_ratio() {
+        let cover = process_cover("image/png", &encode(600, 900, ImageFormat::Png)).unwrap();
+        assert_eq!(cover.extension, "png");
+        assert_eq!((cover.width, cover.height), (600, 900));
+        assert_eq!(dimensions(&cover.small), (120, 180));
+        assert_eq!(dimensions(&cover.medium), (300, 450));
+
+        // 宽图按宽度缩放
+        let cover = process_cover("image/jpeg", &encode(1200, 600, ImageFormat::Jpeg)).unwrap();
+        assert_eq!(cover.extension, "jpg");
+        assert_eq!(dimensions(&cover.small), (120, 60));
+    }
+
+    #[test]
+    fn test_small_images_are_not_enlarged() {
+        let cover = process_cover("image/png", &encode(100, 150, ImageFormat::Png)).unwrap();
+        assert_eq!(dimensions(&cover.small), (100, 150));
+        assert_eq!(dimensions(&cover.medium), (100, 150));
+    }
+
+    #[test]
+    fn test_rejects_invalid_images() {
+        let png = encode(10, 10, ImageFormat::Png);
+        assert_eq!(
+            process_cover("image/gif", &png).unwrap_err(),
+            CoverError::UnsupportedType("image/gif".to_string())
+        );
+        assert_eq!(process_cover("image/jpeg", &png).unwrap_err(), CoverError::TypeMismatch);
+        assert!(matches!(process_cover("image/png", &png[..png.len() / 2]), Err(CoverError::Invalid(_))));
+
+        assert!(is_cover_type("image/webp"));
+        assert!(is_cover_type("Image/PNG; charset=binary"));
+        assert!(!is_cover_type("text/plain"));
+    }
+
+    #[test]
+    fn test_storage_keys() {
+        assert!(valid_key("covers/abc/small.jpg"));
+        assert!(!valid_key(""));
+        assert!(!valid_key("/etc/passwd"));
+        assert!(!valid_key("covers/../secret"));
+        assert!(!valid_key("covers//small.jpg"));
+    }
+}
+
+fn multipart(content_type: &str, data: &[u8]) -> (String, Vec<u8>) {
+    let boundary = "library-test-boundary";
+    let mut body = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"cover\"\r\nContent-Type: {}\r\n\r\n",
+        boundary, content_type
+    )
+    .into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    (format!("multipart/form-data; boundary={}", boundary), body)
+}
+
+#[actix_rt::test]
+async fn test_upload_replace_and_delete_cover() {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let mut config = AppConfig::load().expect("Failed to load config");
+    config.cover_max_bytes = 256 * 1024;
+    let (storage, root) = test_storage();
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .app_data(web::Data::new(config))
+            .app_data(storage)
+            .route("/api/books", web::post().to(create_book))
+            .route("/api/books/{id}", web::get().to(get_book))
+            .route("/api/books/{id}", web::delete().to(delete_book))
+            .route("/api/books/{id}/cover", web::put().to(upload_cover))
+            .route("/api/books/{id}/cover", web::delete().to(delete_cover))
+    ).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("Test Book {}", Uuid::new_v4()),
+            "author": "Test Author",
+            "isbn": random_isbn(),
+            "type": "general",
+            "quantity": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    let book_id = book["id"].as_str().unwrap().to_string();
+    assert!(book["cover"].is_null());
+
+    let (content_type, body) = multipart("image/png", &encode(600, 900, ImageFormat::Png));
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/cover", book_id))
+        .insert_header(("Content-Type", content_type))
+        .set_payload(body)
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let cover: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(cover["width"], 600);
+    let small_url = cover["small_url"].as_str().unwrap().to_string();
+    assert!(small_url.starts_with(&format!("/uploads/covers/{}/", book_id)));
+    let small_path = root.join(small_url.trim_start_matches("/uploads/"));
+    assert!(small_path.exists());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}", book_id))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["cover"]["small_url"], small_url.as_str());
+
+    // 替换封面后旧文件被删除
+    let (content_type, body) = multipart("image/jpeg", &encode(300, 450, ImageFormat::Jpeg));
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}/cover", book_id))
+        .insert_header(("Content-Type", content_type))
+        .set_payload(body)
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let cover: serde_json::Value = test::read_body_json(resp).await;
+    assert_ne!(cover["small_url"], small_url.as_str());
+    assert!(!small_path.exists());
+
+    // 类型不符、不支持的类型和超出大小限制
+    for (content_type, data) in [
+        ("image/jpeg", encode(10, 10, ImageFormat::Png)),
+        ("text/plain", b"not an image".to_vec()),
+        ("image/png", vec![0u8; 256 * 1024 + 1]),
+    ] {
+        let (content_type, body) = multipart(content_type, &data);
+        let resp = test::TestRequest::put()
+            .uri(&format!("/api/books/{}/cover", book_id))
+            .insert_header(("Content-Type", content_type))
+            .set_payload(body)
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 400);
+    }
+
+    // 删除图书时一并清理封面文件
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/books/{}", book_id))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 204);
+    assert!(!root.join("covers").join(&book_id).exists());
+
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/books/{}/cover", book_id))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 404);
+
+    let _ = std::fs::remove_dir_all(root);
+}
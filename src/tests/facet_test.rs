@@ -0,0 +1,109 @@
+use actix_web::{test, web, App};
+use uuid::Uuid;
+use crate::{
+    config::database::init_test_pool,
+    handlers::book_handler::{create_book, list_books},
+    handlers::category_handler::create_category,
+    tests::{book_test::random_isbn, search_test::random_term},
+};
+
+fn facet_count(facets: &serde_json::Value, facet: &str, value: &str) -> Option<i64> {
+    facets[facet]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["value"] == value)
+        .map(|entry| entry["count"].as_i64().unwrap())
+}
+
+#[actix_rt::test]
+async fn test_list_books_facets() {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .route("/api/categories", web::post().to(create_category))
+            .route("/api/books", web::post().to(create_book))
+            .route("/api/books", web::get().to(list_books))
+    ).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/categories")
+        .set_json(serde_json::json!({ "name": format!("Category {}", Uuid::new_v4()) }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let category: serde_json::Value = test::read_body_json(resp).await;
+    let category_id = category["id"].as_str().unwrap().to_string();
+
+    let term = random_term();
+    let author = format!("Facet Author {}", term);
+    for (r#type, language, year, category_ids, quantity) in [
+        ("general", "en", 1995, vec![category_id.clone()], 1),
+        ("reference", "zh", 2003, vec![], 0),
+    ] {
+        let resp = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(serde_json::json!({
+                "title": format!("{} {}", term, r#type),
+                "author": author,
+                "isbn": random_isbn(),
+                "type": r#type,
+                "language": language,
+                "publication_year": year,
+                "category_ids": category_ids,
+                "quantity": quantity
+            }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+    }
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?title={}&facets=true", term))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 2);
+    let facets = &books["facets"];
+    assert_eq!(facet_count(facets, "type", "general"), Some(1));
+    assert_eq!(facet_count(facets, "type", "reference"), Some(1));
+    assert_eq!(facet_count(facets, "language", "zh"), Some(1));
+    assert_eq!(facet_count(facets, "decade", "1990"), Some(1));
+    assert_eq!(facet_count(facets, "decade", "2000"), Some(1));
+    assert_eq!(facet_count(facets, "availability", "true"), Some(1));
+    assert_eq!(facet_count(facets, "availability", "false"), Some(1));
+    assert_eq!(facet_count(facets, "category", &category_id), Some(1));
+    assert_eq!(facets["author"].as_array().unwrap().len(), 1);
+    assert_eq!(facets["author"][0]["name"], author.as_str());
+    assert_eq!(facets["author"][0]["count"], 2);
+
+    // 选中分面取值后结果和统计随之收窄
+    for filter in ["type=reference", "language=zh", "decade=2000", "available=false"] {
+        let resp = test::TestRequest::get()
+            .uri(&format!("/api/books?title={}&facets=true&{}", term, filter))
+            .send_request(&app)
+            .await;
+        let books: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(books["total"], 1, "{}", filter);
+        assert_eq!(books["data"][0]["type"], "reference");
+        assert_eq!(facet_count(&books["facets"], "decade", "2000"), Some(1));
+        assert_eq!(facet_count(&books["facets"], "decade", "1990"), None);
+        assert!(books["facets"]["category"].as_array().unwrap().is_empty());
+    }
+
+    // 不带 facets 时不返回统计
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?title={}", term))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert!(books.get("facets").is_none());
+
+    let resp = test::TestRequest::get()
+        .uri("/api/books?decade=1995")
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+}
@@ -0,0 +1,193 @@
+use actix_web::{test, web, App, dev::Service};
+use chrono::Duration;
+use sqlx::MySqlPool;
+use crate::{
+    config::{database::init_test_pool, AppConfig},
+    handlers::fine_handler::{assess_overdue_fines, get_balance, list_fines, record_payment, waive_fine},
+    handlers::loan_handler::{checkout, return_loan},
+    models::user::Role,
+    tests::helpers::{bearer, create_user_with_token, insert_book},
+};
+
+mod calculation {
+    use chrono::{NaiveDate, NaiveDateTime};
+    use crate::models::fine::overdue_fine;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2024, 3, day).unwrap().and_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_no_fine_before_due() {
+        assert_eq!(overdue_fine(at(10, 12), at(10, 12), 10, 2000), 0);
+        assert_eq!(overdue_fine(at(10, 12), at(9, 12), 10, 2000), 0);
+    }
+
+    #[test]
+    fn test_partial_day_counts_as_full_day() {
+        assert_eq!(overdue_fine(at(10, 12), at(10, 13), 10, 2000), 10);
+        assert_eq!(overdue_fine(at(10, 12), at(12, 12), 10, 2000), 20);
+        assert_eq!(overdue_fine(at(10, 12), at(12, 13), 10, 2000), 30);
+    }
+
+    #[test]
+    fn test_fine_capped_per_item() {
+        assert_eq!(overdue_fine(at(1, 0), at(31, 0), 100, 500), 500);
+    }
+}
+
+async fn setup_test_app() -> (impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, MySqlPool, AppConfig) {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .route("/api/loans", web::post().to(checkout))
+            .route("/api/loans/{id}/return", web::post().to(return_loan))
+            .route("/api/users/{id}/fines", web::get().to(list_fines))
+            .route("/api/users/{id}/fines/balance", web::get().to(get_balance))
+            .route("/api/users/{id}/fines/payments", web::post().to(record_payment))
+            .route("/api/users/{id}/fines/waivers", web::post().to(waive_fine))
+    ).await;
+    (app, pool, config)
+}
+
+#[actix_rt::test]
+async fn test_overdue_return_payment_and_waiver() {
+    let (app, pool, config) = setup_test_app().await;
+    let (user_id, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (librarian_id, librarian) = create_user_with_token(&pool, &config, Role::Librarian).await;
+    let book_id = insert_book(&pool, 1).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+    let loan_id = loan["id"].as_str().unwrap();
+
+    // 逾期两天多，不足一天按一天计，共三天
+    let due_at = chrono::Utc::now().naive_local() - Duration::days(3) + Duration::hours(1);
+    sqlx::query("UPDATE loans SET due_at = ? WHERE id = ?")
+        .bind(due_at)
+        .bind(loan_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/return", loan_id))
+        .insert_header(bearer(&librarian))
+        .send_request(&app)
+        .await;
+
+    let expected = (3 * config.fine_daily_rate_cents).min(config.fine_max_per_item_cents);
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}/fines/balance", user_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    let balance: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(balance["balance_cents"], expected);
+    assert_eq!(balance["charged_cents"], expected);
+
+    // 重复查询不会重复计罚
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}/fines", user_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    let history: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(history["total"], 1);
+    assert_eq!(history["data"][0]["kind"], "charge");
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/users/{}/fines/payments", user_id))
+        .insert_header(bearer(&librarian))
+        .set_json(serde_json::json!({ "amount_cents": expected + 1, "note": "cash" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/users/{}/fines/payments", user_id))
+        .insert_header(bearer(&librarian))
+        .set_json(serde_json::json!({ "amount_cents": expected - 10, "note": "cash" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let payment: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(payment["amount_cents"], -(expected - 10));
+    assert_eq!(payment["recorded_by"], librarian_id);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/users/{}/fines/waivers", user_id))
+        .insert_header(bearer(&librarian))
+        .set_json(serde_json::json!({ "amount_cents": 10, "note": "   " }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/users/{}/fines/waivers", user_id))
+        .insert_header(bearer(&librarian))
+        .set_json(serde_json::json!({ "amount_cents": 10, "note": "Book dropbox was jammed", "loan_id": loan_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}/fines/balance", user_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    let balance: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(balance["balance_cents"], 0);
+    assert_eq!(balance["waived_cents"], 10);
+}
+
+#[actix_rt::test]
+async fn test_unreturned_overdue_charged_by_sweep() {
+    let (app, pool, config) = setup_test_app().await;
+    let (user_id, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let book_id = insert_book(&pool, 1).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+
+    let due_at = chrono::Utc::now().naive_local() - Duration::days(2) + Duration::hours(1);
+    sqlx::query("UPDATE loans SET due_at = ? WHERE id = ?")
+        .bind(due_at)
+        .bind(loan["id"].as_str().unwrap())
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    // 查询余额只读，不会补记罚款
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}/fines/balance", user_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    let balance: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(balance["balance_cents"], 0);
+
+    assess_overdue_fines(&pool, &config).await.expect("Failed to assess fines");
+
+    let expected = (2 * config.fine_daily_rate_cents).min(config.fine_max_per_item_cents);
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}/fines/balance", user_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    let balance: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(balance["balance_cents"], expected);
+}
@@ -0,0 +1,128 @@
+use std::{path::PathBuf, sync::Arc};
+use actix_web::web;
+use chrono::Utc;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+use crate::{
+    config::AppConfig,
+    models::item::placeholder_barcode,
+    models::user::Role,
+    storage::{LocalStorage, Storage},
+    tests::book_test::random_isbn,
+    utils::jwt::create_token,
+};
+
+// 直接写库创建指定角色的用户并签发令牌，返回 (user_id, token)
+pub async fn create_user_with_token(pool: &MySqlPool, config: &AppConfig, role: Role) -> (String, String) {
+    let user_id = Uuid::new_v4().to_string();
+    let now = Utc::now();
+
+    sqlx::query(
+        "INSERT INTO users (id, username, password, email, password_hash, role, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&user_id)
+    .bind(format!("user_{}", user_id))
+    .bind("not-a-real-hash")
+    .bind(format!("{}@example.com", user_id))
+    .bind("not-a-real-hash")
+    .bind(role.as_str())
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .expect("Failed to create test user");
+
+    let (token, expires_at) = create_token(&user_id, config).expect("Failed to create token");
+    sqlx::query("INSERT INTO tokens (id, user_id, token, expires_at, created_at) VALUES (?, ?, ?, ?, ?)")
+        .bind(Uuid::new_v4().to_string())
+        .bind(&user_id)
+        .bind(&token)
+        .bind(expires_at)
+        .bind(now)
+        .execute(pool)
+        .await
+        .expect("Failed to store token");
+
+    (user_id, token)
+}
+
+// 直接写库创建一本图书及 quantity 册在架单册，返回 book_id
+pub async fn insert_book(pool: &MySqlPool, quantity: i32) -> String {
+    let book_id = Uuid::new_v4().to_string();
+    let now = Utc::now().naive_local();
+
+    sqlx::query(
+        "INSERT INTO books (id, title, author, isbn, description, type, created_at, updated_at)
+         VALUES (?, ?, ?, ?, NULL, 'general', ?, ?)",
+    )
+    .bind(&book_id)
+    .bind(format!("Test Book {}", book_id))
+    .bind("Test Author")
+    .bind(random_isbn())
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .expect("Failed to create test book");
+
+    let branch_id = default_branch(pool).await;
+    for n in 1..=quantity as usize {
+        sqlx::query(
+            "INSERT INTO items (id, book_id, home_branch_id, current_branch_id, barcode, status, acquired_at, created_at, updated_at)
+             VALUES (?, ?, ?, ?, ?, 'available', NULL, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&book_id)
+        .bind(&branch_id)
+        .bind(&branch_id)
+        .bind(placeholder_barcode(&book_id, n))
+        .bind(now)
+        .bind(now)
+        .execute(pool)
+        .await
+        .expect("Failed to create test item");
+    }
+
+    book_id
+}
+
+// 迁移时创建的总馆，未指定分馆的单册都放在这里
+pub async fn default_branch(pool: &MySqlPool) -> String {
+    sqlx::query_scalar("SELECT id FROM branches ORDER BY created_at, code LIMIT 1")
+        .fetch_one(pool)
+        .await
+        .expect("Failed to find default branch")
+}
+
+// 直接写库创建一个分馆，返回 branch_id
+pub async fn insert_branch(pool: &MySqlPool) -> String {
+    let branch_id = Uuid::new_v4().to_string();
+    let now = Utc::now().naive_local();
+
+    sqlx::query(
+        "INSERT INTO branches (id, code, name, address, created_at, updated_at)
+         VALUES (?, ?, ?, NULL, ?, ?)",
+    )
+    .bind(&branch_id)
+    .bind(format!("T{}", &branch_id.replace('-', "")[..12].to_uppercase()))
+    .bind(format!("Test Branch {}", branch_id))
+    .bind(now)
+    .bind(now)
+    .execute(pool)
+    .await
+    .expect("Failed to create test branch");
+
+    branch_id
+}
+
+// 每个测试使用独立的临时目录作为本地存储，返回 (storage, 存储目录)
+pub fn test_storage() -> (web::Data<dyn Storage>, PathBuf) {
+    let root = std::env::temp_dir().join(format!("library-storage-{}", Uuid::new_v4()));
+    let storage: Arc<dyn Storage> = Arc::new(LocalStorage::new(&root, "/uploads"));
+    (web::Data::from(storage), root)
+}
+
+pub fn bearer(token: &str) -> (&'static str, String) {
+    ("Authorization", format!("Bearer {}", token))
+}
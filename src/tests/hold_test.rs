@@ -0,0 +1,249 @@
+use actix_web::{test, web, App, dev::Service};
+use chrono::{Duration, Utc};
+use sqlx::MySqlPool;
+use crate::{
+    config::{database::init_test_pool, AppConfig},
+    handlers::hold_handler::{available_copies, cancel_hold, get_hold, place_hold, process_expired_holds},
+    handlers::loan_handler::{checkout, return_loan},
+    models::user::Role,
+    tests::helpers::{bearer, create_user_with_token, insert_book},
+};
+
+async fn setup_test_app() -> (impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, MySqlPool, AppConfig) {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .route("/api/loans", web::post().to(checkout))
+            .route("/api/loans/{id}/return", web::post().to(return_loan))
+            .route("/api/holds", web::post().to(place_hold))
+            .route("/api/holds/{id}", web::get().to(get_hold))
+            .route("/api/holds/{id}", web::delete().to(cancel_hold))
+    ).await;
+    (app, pool, config)
+}
+
+#[actix_rt::test]
+async fn test_hold_queue_and_pickup() {
+    let (app, pool, config) = setup_test_app().await;
+    let book_id = insert_book(&pool, 1).await;
+    let (_, borrower) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, first) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, second) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, librarian) = create_user_with_token(&pool, &config, Role::Librarian).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&borrower))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+
+    let mut holds = Vec::new();
+    for (token, position) in [(&first, 1), (&second, 2)] {
+        let resp = test::TestRequest::post()
+            .uri("/api/holds")
+            .insert_header(bearer(token))
+            .set_json(serde_json::json!({ "book_id": book_id }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+        let hold: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(hold["status"], "waiting");
+        assert_eq!(hold["queue_position"], position);
+        holds.push(hold["id"].as_str().unwrap().to_string());
+    }
+
+    // 还书后副本留给排在第一位的预约
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/return", loan["id"].as_str().unwrap()))
+        .insert_header(bearer(&librarian))
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[0]))
+        .insert_header(bearer(&first))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["status"], "ready");
+    assert!(hold["expires_at"].is_string());
+
+    // 第二位读者不能借走已留给他人的副本
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&second))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&first))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[0]))
+        .insert_header(bearer(&first))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["status"], "fulfilled");
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[1]))
+        .insert_header(bearer(&second))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["queue_position"], 1);
+}
+
+#[actix_rt::test]
+async fn test_cancel_ready_hold_promotes_next() {
+    let (app, pool, config) = setup_test_app().await;
+    let book_id = insert_book(&pool, 1).await;
+    let (_, borrower) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, first) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, second) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, librarian) = create_user_with_token(&pool, &config, Role::Librarian).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&borrower))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+
+    let mut holds = Vec::new();
+    for token in [&first, &second] {
+        let resp = test::TestRequest::post()
+            .uri("/api/holds")
+            .insert_header(bearer(token))
+            .set_json(serde_json::json!({ "book_id": book_id }))
+            .send_request(&app)
+            .await;
+        let hold: serde_json::Value = test::read_body_json(resp).await;
+        holds.push(hold["id"].as_str().unwrap().to_string());
+    }
+
+    // 他人不能取消预约
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/holds/{}", holds[0]))
+        .insert_header(bearer(&second))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 403);
+
+    test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/return", loan["id"].as_str().unwrap()))
+        .insert_header(bearer(&librarian))
+        .send_request(&app)
+        .await;
+
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/holds/{}", holds[0]))
+        .insert_header(bearer(&first))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[1]))
+        .insert_header(bearer(&second))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["status"], "ready");
+}
+
+#[actix_rt::test]
+async fn test_expired_pickup_passes_to_next_hold() {
+    let (app, pool, config) = setup_test_app().await;
+    let book_id = insert_book(&pool, 1).await;
+    let (_, borrower) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, first) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, second) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, librarian) = create_user_with_token(&pool, &config, Role::Librarian).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&borrower))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+
+    let mut holds = Vec::new();
+    for token in [&first, &second] {
+        let resp = test::TestRequest::post()
+            .uri("/api/holds")
+            .insert_header(bearer(token))
+            .set_json(serde_json::json!({ "book_id": book_id }))
+            .send_request(&app)
+            .await;
+        let hold: serde_json::Value = test::read_body_json(resp).await;
+        holds.push(hold["id"].as_str().unwrap().to_string());
+    }
+
+    test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/return", loan["id"].as_str().unwrap()))
+        .insert_header(bearer(&librarian))
+        .send_request(&app)
+        .await;
+
+    // 第一位超过取书期限未取，副本顺延给第二位
+    backdate_expiry(&pool, &holds[0]).await;
+    process_expired_holds(&pool, config.hold_pickup_days).await.expect("Failed to expire holds");
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[0]))
+        .insert_header(bearer(&first))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["status"], "expired");
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[1]))
+        .insert_header(bearer(&second))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["status"], "ready");
+
+    // 队列中已无他人，副本回到可借状态
+    backdate_expiry(&pool, &holds[1]).await;
+    process_expired_holds(&pool, config.hold_pickup_days).await.expect("Failed to expire holds");
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[1]))
+        .insert_header(bearer(&second))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["status"], "expired");
+
+    let mut conn = pool.acquire().await.expect("Failed to acquire connection");
+    assert_eq!(available_copies(&mut conn, &book_id).await.unwrap(), 1);
+}
+
+async fn backdate_expiry(pool: &MySqlPool, hold_id: &str) {
+    sqlx::query("UPDATE holds SET expires_at = ? WHERE id = ?")
+        .bind(Utc::now().naive_local() - Duration::days(1))
+        .bind(hold_id)
+        .execute(pool)
+        .await
+        .expect("Failed to backdate hold");
+}
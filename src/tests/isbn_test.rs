@@ -0,0 +1,41 @@
+use crate::models::book::{Isbn, IsbnError};
+
+#[test]
+fn test_parse_isbn13_formats() {
+    let plain = Isbn::parse("9787536692930").unwrap();
+    let hyphenated = Isbn::parse("978-7-5366-9293-0").unwrap();
+    let spaced = Isbn::parse("978 7 5366 9293 0").unwrap();
+
+    assert_eq!(plain, hyphenated);
+    assert_eq!(plain, spaced);
+    assert_eq!(plain.as_str(), "9787536692930");
+}
+
+#[test]
+fn test_isbn10_converts_to_isbn13() {
+    let isbn = Isbn::parse("0-306-40615-2").unwrap();
+    assert_eq!(isbn.as_str(), "9780306406157");
+    assert_eq!(isbn, Isbn::parse("978-0-306-40615-7").unwrap());
+
+    // 校验位为 X 的 ISBN-10
+    let isbn = Isbn::parse("7-5366-9293-5").unwrap();
+    assert_eq!(isbn.as_str(), "9787536692930");
+    assert!(Isbn::parse("0-8044-2957-X").is_ok());
+}
+
+#[test]
+fn test_invalid_isbn() {
+    assert_eq!(Isbn::parse("9787536692931"), Err(IsbnError::InvalidChecksum));
+    assert_eq!(Isbn::parse("0-306-40615-3"), Err(IsbnError::InvalidChecksum));
+    assert_eq!(Isbn::parse("12345"), Err(IsbnError::InvalidLength(5)));
+    assert_eq!(Isbn::parse("1234567890128"), Err(IsbnError::InvalidPrefix));
+    assert!(matches!(Isbn::parse("97875366929a0"), Err(IsbnError::InvalidCharacter('a'))));
+}
+
+#[test]
+fn test_hyphenated_display() {
+    assert_eq!(Isbn::parse("9787536692930").unwrap().hyphenated(), "978-7-5366-9293-0");
+    assert_eq!(Isbn::parse("9787111123453").unwrap().hyphenated(), "978-7-111-12345-3");
+    assert_eq!(Isbn::parse("9780306406157").unwrap().hyphenated(), "978-0-306-40615-7");
+    assert_eq!(Isbn::parse("9781491927281").unwrap().hyphenated(), "978-1-4919-2728-1");
+}
@@ -0,0 +1,232 @@
+use actix_web::{test, web, App, dev::Service};
+use sqlx::MySqlPool;
+use uuid::Uuid;
+use crate::{
+    config::{database::init_test_pool, AppConfig},
+    handlers::book_handler::get_book,
+    handlers::hold_handler::{get_hold, place_hold},
+    handlers::item_handler::{create_item, delete_item, list_items, update_item},
+    handlers::loan_handler::{checkout, return_loan},
+    models::user::Role,
+    tests::helpers::{bearer, create_user_with_token, insert_book},
+};
+
+async fn setup_test_app() -> (impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, MySqlPool, AppConfig) {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .route("/api/books/{id}", web::get().to(get_book))
+            .route("/api/books/{id}/items", web::get().to(list_items))
+            .route("/api/books/{id}/items", web::post().to(create_item))
+            .route("/api/items/{id}", web::put().to(update_item))
+            .route("/api/items/{id}", web::delete().to(delete_item))
+            .route("/api/holds", web::post().to(place_hold))
+            .route("/api/holds/{id}", web::get().to(get_hold))
+            .route("/api/loans", web::post().to(checkout))
+            .route("/api/loans/{id}/return", web::post().to(return_loan))
+    ).await;
+    (app, pool, config)
+}
+
+#[actix_rt::test]
+async fn test_item_statuses_drive_availability() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, librarian) = create_user_with_token(&pool, &config, Role::Librarian).await;
+    let book_id = insert_book(&pool, 0).await;
+
+    let mut item_ids = Vec::new();
+    for _ in 0..2 {
+        let barcode = format!("T{}", Uuid::new_v4().simple());
+        let resp = test::TestRequest::post()
+            .uri(&format!("/api/books/{}/items", book_id))
+            .set_json(serde_json::json!({ "barcode": barcode, "acquired_at": "2024-03-01" }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+        let item: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(item["status"], "available");
+        item_ids.push((item["id"].as_str().unwrap().to_string(), barcode));
+    }
+
+    // 条码唯一
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/items", book_id))
+        .set_json(serde_json::json!({ "barcode": item_ids[0].1 }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/items/{}", item_ids[0].0))
+        .set_json(serde_json::json!({ "status": "damaged" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}", book_id))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["quantity"], 2);
+    assert_eq!(book["available"], 1);
+
+    // 损坏的单册不能借出
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id, "barcode": item_ids[0].1 }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id, "barcode": item_ids[1].1 }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(loan["item_id"], item_ids[1].0.as_str());
+
+    // 借出中的单册不能手动改状态
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/items/{}", item_ids[1].0))
+        .set_json(serde_json::json!({ "status": "lost" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/return", loan["id"].as_str().unwrap()))
+        .insert_header(bearer(&librarian))
+        .send_request(&app)
+        .await;
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/items", book_id))
+        .send_request(&app)
+        .await;
+    let items: serde_json::Value = test::read_body_json(resp).await;
+    let statuses: Vec<&str> = items["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["status"].as_str().unwrap())
+        .collect();
+    assert!(statuses.contains(&"damaged"));
+    assert!(statuses.contains(&"available"));
+}
+
+#[actix_rt::test]
+async fn test_on_loan_status_cannot_be_set_manually() {
+    let (app, pool, _) = setup_test_app().await;
+    let book_id = insert_book(&pool, 0).await;
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/items", book_id))
+        .set_json(serde_json::json!({ "barcode": format!("T{}", Uuid::new_v4().simple()), "status": "on_loan" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_available_items_fill_waiting_holds() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, first) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, second) = create_user_with_token(&pool, &config, Role::Member).await;
+    let book_id = insert_book(&pool, 1).await;
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}/items", book_id))
+        .send_request(&app)
+        .await;
+    let items: serde_json::Value = test::read_body_json(resp).await;
+    let item_id = items["data"][0]["id"].as_str().unwrap().to_string();
+
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/items/{}", item_id))
+        .set_json(serde_json::json!({ "status": "in_repair" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let mut holds = Vec::new();
+    for token in [&first, &second] {
+        let resp = test::TestRequest::post()
+            .uri("/api/holds")
+            .insert_header(bearer(token))
+            .set_json(serde_json::json!({ "book_id": book_id }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+        let hold: serde_json::Value = test::read_body_json(resp).await;
+        holds.push(hold["id"].as_str().unwrap().to_string());
+    }
+
+    // 修补完成恢复在架，留给排在第一位的预约
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/items/{}", item_id))
+        .set_json(serde_json::json!({ "status": "available" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[0]))
+        .insert_header(bearer(&first))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["status"], "ready");
+
+    // 新增的单册留给第二位
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/books/{}/items", book_id))
+        .set_json(serde_json::json!({ "barcode": format!("T{}", Uuid::new_v4().simple()) }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/holds/{}", holds[1]))
+        .insert_header(bearer(&second))
+        .send_request(&app)
+        .await;
+    let hold: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(hold["status"], "ready");
+}
+
+#[actix_rt::test]
+async fn test_delete_item_on_loan_conflicts() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let book_id = insert_book(&pool, 1).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/items/{}", loan["item_id"].as_str().unwrap()))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/items/{}", Uuid::new_v4()))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 404);
+}
@@ -0,0 +1,202 @@
+use actix_web::{test, web, App, dev::Service};
+use sqlx::MySqlPool;
+use crate::{
+    config::{database::init_test_pool, AppConfig},
+    handlers::hold_handler::place_hold,
+    handlers::loan_handler::{checkout, return_loan, renew_loan, list_user_loans},
+    models::user::Role,
+    tests::helpers::{bearer, create_user_with_token, insert_book},
+};
+
+async fn setup_test_app() -> (impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, MySqlPool, AppConfig) {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .route("/api/loans", web::post().to(checkout))
+            .route("/api/loans/{id}/return", web::post().to(return_loan))
+            .route("/api/loans/{id}/renew", web::post().to(renew_loan))
+            .route("/api/holds", web::post().to(place_hold))
+            .route("/api/users/{id}/loans", web::get().to(list_user_loans))
+    ).await;
+    (app, pool, config)
+}
+
+#[actix_rt::test]
+async fn test_checkout_and_return() {
+    let (app, pool, config) = setup_test_app().await;
+    let (user_id, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let book_id = insert_book(&pool, 1).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(loan["user_id"], user_id);
+    assert!(loan["due_at"].is_string());
+    assert!(loan["returned_at"].is_null());
+
+    // 唯一的副本已借出
+    let (_, other_token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&other_token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/return", loan["id"].as_str().unwrap()))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}/loans?status=returned", user_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["total"], 1);
+    assert!(body["data"][0]["returned_at"].is_string());
+}
+
+#[actix_rt::test]
+async fn test_concurrent_checkout_of_last_copy() {
+    let (app, pool, config) = setup_test_app().await;
+    let book_id = insert_book(&pool, 1).await;
+    let (_, first) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, second) = create_user_with_token(&pool, &config, Role::Member).await;
+
+    let request = |token: String| {
+        test::TestRequest::post()
+            .uri("/api/loans")
+            .insert_header(bearer(&token))
+            .set_json(serde_json::json!({ "book_id": book_id }))
+            .to_request()
+    };
+
+    let (a, b) = futures_util::join!(app.call(request(first)), app.call(request(second)));
+    let statuses = [a.unwrap().status().as_u16(), b.unwrap().status().as_u16()];
+    assert_eq!(statuses.iter().filter(|s| **s == 201).count(), 1);
+    assert_eq!(statuses.iter().filter(|s| **s == 409).count(), 1);
+}
+
+#[actix_rt::test]
+async fn test_member_cannot_view_other_loans() {
+    let (app, pool, config) = setup_test_app().await;
+    let (other_id, _) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, token) = create_user_with_token(&pool, &config, Role::Member).await;
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}/loans", other_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 403);
+}
+
+async fn checkout_book(
+    app: &impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>,
+    token: &str,
+    book_id: &str,
+) -> String {
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+    loan["id"].as_str().unwrap().to_string()
+}
+
+#[actix_rt::test]
+async fn test_renew_until_limit() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let book_id = insert_book(&pool, 1).await;
+    let loan_id = checkout_book(&app, &token, &book_id).await;
+
+    for count in 1..=config.max_renewals {
+        let resp = test::TestRequest::post()
+            .uri(&format!("/api/loans/{}/renew", loan_id))
+            .insert_header(bearer(&token))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 200);
+        let loan: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(loan["renewal_count"], count);
+    }
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/renew", loan_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["details"]["reason"], "RENEWAL_LIMIT_REACHED");
+}
+
+#[actix_rt::test]
+async fn test_renew_refused_with_pending_holds() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, other_token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let book_id = insert_book(&pool, 1).await;
+    let loan_id = checkout_book(&app, &token, &book_id).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/holds")
+        .insert_header(bearer(&other_token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/renew", loan_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["details"]["reason"], "HOLDS_PENDING");
+}
+
+#[actix_rt::test]
+async fn test_renew_refused_when_overdue() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let book_id = insert_book(&pool, 1).await;
+    let loan_id = checkout_book(&app, &token, &book_id).await;
+
+    // 将到期日改到宽限期之前
+    let due_at = chrono::Utc::now().naive_local() - chrono::Duration::days(config.renewal_grace_days + 1);
+    sqlx::query("UPDATE loans SET due_at = ? WHERE id = ?")
+        .bind(due_at)
+        .bind(&loan_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/loans/{}/renew", loan_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["details"]["reason"], "OVERDUE");
+}
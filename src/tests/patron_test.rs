@@ -0,0 +1,131 @@
+use actix_web::{test, web, App, dev::Service};
+use sqlx::MySqlPool;
+use crate::{
+    config::{database::init_test_pool, AppConfig},
+    handlers::loan_handler::checkout,
+    handlers::patron_handler::{block_user, get_profile, unblock_user},
+    models::user::Role,
+    tests::helpers::{bearer, create_user_with_token, insert_book},
+};
+
+async fn setup_test_app() -> (impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, MySqlPool, AppConfig) {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .route("/api/loans", web::post().to(checkout))
+            .route("/api/users/{id}", web::get().to(get_profile))
+            .route("/api/users/{id}/block", web::post().to(block_user))
+            .route("/api/users/{id}/unblock", web::post().to(unblock_user))
+    ).await;
+    (app, pool, config)
+}
+
+#[actix_rt::test]
+async fn test_manual_block_and_unblock() {
+    let (app, pool, config) = setup_test_app().await;
+    let (user_id, token) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (librarian_id, librarian) = create_user_with_token(&pool, &config, Role::Librarian).await;
+    let book_id = insert_book(&pool, 1).await;
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/users/{}/block", user_id))
+        .insert_header(bearer(&librarian))
+        .set_json(serde_json::json!({ "note": "Lost library card reported" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let profile: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(profile["blocked"], true);
+    assert_eq!(profile["block_updated_by"], librarian_id);
+    assert_eq!(profile["standing"]["can_borrow"], false);
+    assert_eq!(profile["standing"]["reasons"][0], "MANUAL_BLOCK");
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["details"]["reason"], "MANUAL_BLOCK");
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/users/{}/unblock", user_id))
+        .insert_header(bearer(&librarian))
+        .set_json(serde_json::json!({ "note": "Card replaced" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}", user_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    let profile: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(profile["blocked"], false);
+    assert_eq!(profile["block_note"], "Card replaced");
+    assert_eq!(profile["standing"]["can_borrow"], true);
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+}
+
+#[actix_rt::test]
+async fn test_overdue_items_block_checkout() {
+    let (app, pool, config) = setup_test_app().await;
+    let (user_id, token) = create_user_with_token(&pool, &config, Role::Member).await;
+
+    for _ in 0..=config.max_overdue_items {
+        let book_id = insert_book(&pool, 1).await;
+        let resp = test::TestRequest::post()
+            .uri("/api/loans")
+            .insert_header(bearer(&token))
+            .set_json(serde_json::json!({ "book_id": book_id }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+    }
+
+    // 只逾期一小时，罚款不会超过欠款上限
+    sqlx::query("UPDATE loans SET due_at = ? WHERE user_id = ?")
+        .bind(chrono::Utc::now().naive_local() - chrono::Duration::hours(1))
+        .bind(&user_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let book_id = insert_book(&pool, 1).await;
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["details"]["reason"], "TOO_MANY_OVERDUE");
+}
+
+#[actix_rt::test]
+async fn test_member_cannot_view_other_profile() {
+    let (app, pool, config) = setup_test_app().await;
+    let (other_id, _) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, token) = create_user_with_token(&pool, &config, Role::Member).await;
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/users/{}", other_id))
+        .insert_header(bearer(&token))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 403);
+}
@@ -0,0 +1,150 @@
+use actix_web::{test, web, App, dev::Service};
+use uuid::Uuid;
+use crate::{
+    config::database::init_test_pool,
+    handlers::book_handler::{create_book, list_books},
+    tests::{book_test::random_isbn, search_test::random_term},
+};
+
+mod conversion {
+    use crate::models::pinyin::{full, initials, pinyin_of, search_key};
+
+    #[test]
+    fn test_full_and_initials() {
+        assert_eq!(full("三体"), "santi");
+        assert_eq!(initials("三体"), "st");
+        assert_eq!(full("刘慈欣"), "liucixin");
+        assert_eq!(initials("刘慈欣"), "lcx");
+    }
+
+    #[test]
+    fn test_only_han_characters_are_converted() {
+        assert_eq!(full("三体2：黑暗森林"), "santiheiansenlin");
+        assert_eq!(initials("三体2：黑暗森林"), "sthasl");
+        assert_eq!(full("Rust 编程"), "biancheng");
+        assert_eq!(initials("Rust 编程"), "bc");
+        assert_eq!(full("The Book"), "");
+        assert_eq!(initials("The Book"), "");
+    }
+
+    #[test]
+    fn test_umlaut_is_written_as_v() {
+        assert_eq!(pinyin_of('女'), Some("nv"));
+        assert_eq!(full("绿"), "lv");
+        assert_eq!(pinyin_of('a'), None);
+    }
+
+    #[test]
+    fn test_search_key() {
+        assert_eq!(search_key("San Ti"), Some("santi".to_string()));
+        assert_eq!(search_key("st"), Some("st".to_string()));
+        assert_eq!(search_key("三体"), None);
+        assert_eq!(search_key("c++"), None);
+        assert_eq!(search_key("2024"), None);
+    }
+}
+
+/// 随机汉字串及其全拼、首字母，拼音列只由汉字生成，用它让测试图书的拼音唯一
+fn random_han() -> (String, String, String) {
+    let chars = [('东', "dong"), ('西', "xi"), ('南', "nan"), ('北', "bei"), ('天', "tian"),
+                 ('木', "mu"), ('山', "shan"), ('水', "shui"), ('风', "feng"), ('火', "huo")];
+    let seed = Uuid::new_v4().as_u128();
+    let picked: Vec<(char, &str)> = (0..10).map(|i| chars[((seed >> (i * 4)) % 10) as usize]).collect();
+    (
+        picked.iter().map(|(c, _)| *c).collect(),
+        picked.iter().map(|(_, pinyin)| *pinyin).collect(),
+        picked.iter().map(|(_, pinyin)| &pinyin[..1]).collect(),
+    )
+}
+
+async fn setup_test_app() -> impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .route("/api/books", web::post().to(create_book))
+            .route("/api/books", web::get().to(list_books))
+    ).await
+}
+
+#[actix_rt::test]
+async fn test_list_books_matches_pinyin() {
+    let app = setup_test_app().await;
+
+    let (han, full, initials) = random_han();
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("三体 {}", han),
+            "author": "刘慈欣",
+            "isbn": random_isbn(),
+            "type": "general",
+            "quantity": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    let book_id = book["id"].as_str().unwrap().to_string();
+
+    for uri in [
+        format!("/api/books?title=santi{}", full),
+        format!("/api/books?title=st{}", initials),
+        format!("/api/books?title=San%20Ti%20{}", full),
+        format!("/api/books?title=st{}&author=liucixin", initials),
+        format!("/api/books?title=st{}&author=lcx", initials),
+        format!("/api/books?q=santi%20{}", full),
+    ] {
+        let resp = test::TestRequest::get().uri(&uri).send_request(&app).await;
+        assert_eq!(resp.status(), 200);
+        let books: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(books["total"], 1, "{}", uri);
+        assert_eq!(books["data"][0]["id"], book_id.as_str());
+    }
+
+    // 首字母只做前缀匹配
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?title=t{}", initials))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 0);
+}
+
+#[actix_rt::test]
+async fn test_english_query_does_not_match_through_pinyin() {
+    let app = setup_test_app().await;
+
+    let term = random_term();
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("The Book {}", term),
+            "author": format!("Go Writer {}", term),
+            "isbn": random_isbn(),
+            "type": "general",
+            "quantity": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?title=Book%20{}", term))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 1);
+
+    // 去掉空格拼接后的英文片段不能再经拼音列命中
+    for uri in [
+        format!("/api/books?title=ebook{}", term),
+        format!("/api/books?q=ok{}", term),
+        format!("/api/books?author=writer{}", term),
+    ] {
+        let resp = test::TestRequest::get().uri(&uri).send_request(&app).await;
+        assert_eq!(resp.status(), 200);
+        let books: serde_json::Value = test::read_body_json(resp).await;
+        assert_eq!(books["total"], 0, "{}", uri);
+    }
+}
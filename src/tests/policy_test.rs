@@ -0,0 +1,196 @@
+use actix_web::{test, web, App, dev::Service};
+use chrono::NaiveDateTime;
+use sqlx::MySqlPool;
+use uuid::Uuid;
+use crate::{
+    config::{database::init_test_pool, AppConfig},
+    handlers::loan_handler::checkout,
+    handlers::policy_handler::{create_policy, delete_policy, resolve_policy, resolve_terms, update_policy},
+    handlers::user_handler::update_user_category,
+    models::user::Role,
+    tests::helpers::{bearer, create_user_with_token, insert_book},
+};
+
+async fn setup_test_app() -> (impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, MySqlPool, AppConfig) {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .route("/api/loans", web::post().to(checkout))
+            .route("/api/users/{id}/category", web::put().to(update_user_category))
+            .route("/api/policies", web::post().to(create_policy))
+            .route("/api/policies/resolve", web::get().to(resolve_policy))
+            .route("/api/policies/{id}", web::put().to(update_policy))
+            .route("/api/policies/{id}", web::delete().to(delete_policy))
+    ).await;
+    (app, pool, config)
+}
+
+#[actix_rt::test]
+async fn test_policy_resolution_and_short_loans() {
+    let (app, pool, config) = setup_test_app().await;
+    // 只使用 faculty 类别，避免影响其他测试使用的默认规则；先清理上次中断留下的数据
+    sqlx::query("DELETE FROM circulation_policies WHERE patron_category = 'faculty'")
+        .execute(&pool)
+        .await
+        .unwrap();
+    let (_, admin) = create_user_with_token(&pool, &config, Role::Admin).await;
+    let (user_id, token) = create_user_with_token(&pool, &config, Role::Member).await;
+
+    let mut policy_ids = Vec::new();
+    for (book_type, hours) in [("*", 2160), ("reference", 2)] {
+        let resp = test::TestRequest::post()
+            .uri("/api/policies")
+            .insert_header(bearer(&admin))
+            .set_json(serde_json::json!({
+                "patron_category": "faculty",
+                "book_type": book_type,
+                "loan_period_hours": hours,
+                "max_renewals": 3,
+                "fine_rate_cents": 50,
+                "max_loans": 20
+            }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+        let policy: serde_json::Value = test::read_body_json(resp).await;
+        policy_ids.push(policy["id"].as_str().unwrap().to_string());
+    }
+
+    // 同一键重复创建返回 409
+    let resp = test::TestRequest::post()
+        .uri("/api/policies")
+        .insert_header(bearer(&admin))
+        .set_json(serde_json::json!({
+            "patron_category": "faculty",
+            "book_type": "reference",
+            "loan_period_hours": 4,
+            "max_renewals": 0,
+            "fine_rate_cents": 0,
+            "max_loans": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::get()
+        .uri("/api/policies/resolve?patron_category=faculty&book_type=thesis")
+        .send_request(&app)
+        .await;
+    let terms: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(terms["policy_id"], policy_ids[0].as_str());
+    assert_eq!(terms["loan_period_hours"], 2160);
+
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/users/{}/category", user_id))
+        .set_json(serde_json::json!({ "patron_category": "faculty" }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    let book_id = insert_book(&pool, 1).await;
+    sqlx::query("UPDATE books SET type = 'reference' WHERE id = ?")
+        .bind(&book_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&token))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+    let checkout_at: NaiveDateTime = serde_json::from_value(loan["checkout_at"].clone()).unwrap();
+    let due_at: NaiveDateTime = serde_json::from_value(loan["due_at"].clone()).unwrap();
+    assert_eq!((due_at - checkout_at).num_hours(), 2);
+    assert_eq!(loan["max_renewals"], 3);
+    assert_eq!(loan["fine_rate_cents"], 50);
+
+    for policy_id in policy_ids {
+        let resp = test::TestRequest::delete()
+            .uri(&format!("/api/policies/{}", policy_id))
+            .insert_header(bearer(&admin))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 204);
+    }
+
+    // 删除后回落到配置默认值
+    let resp = test::TestRequest::get()
+        .uri("/api/policies/resolve?patron_category=faculty&book_type=reference")
+        .send_request(&app)
+        .await;
+    let terms: serde_json::Value = test::read_body_json(resp).await;
+    assert!(terms["policy_id"].is_null());
+    assert_eq!(terms["loan_period_hours"], config.loan_period_days * 24);
+}
+
+#[actix_rt::test]
+async fn test_type_rule_beats_category_wildcard() {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    // 在事务中建立规则并回滚，(student, *) 和 (*, reference) 不影响并行运行的其他测试
+    let mut tx = pool.begin().await.unwrap();
+    sqlx::query(
+        "DELETE FROM circulation_policies
+         WHERE patron_category IN ('student', 'faculty')
+            OR (patron_category = '*' AND book_type = 'reference')",
+    )
+    .execute(&mut *tx)
+    .await
+    .unwrap();
+    for (patron_category, book_type, hours) in [("student", "*", 720), ("faculty", "*", 2160), ("*", "reference", 2)] {
+        sqlx::query(
+            "INSERT INTO circulation_policies
+                 (id, patron_category, book_type, loan_period_hours, max_renewals, fine_rate_cents, max_loans, created_at, updated_at)
+             VALUES (?, ?, ?, ?, 1, 10, 5, NOW(), NOW())",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(patron_category)
+        .bind(book_type)
+        .bind(hours)
+        .execute(&mut *tx)
+        .await
+        .unwrap();
+    }
+
+    for (patron_category, book_type, hours) in [
+        ("student", "reference", 2),
+        ("faculty", "reference", 2),
+        ("student", "general", 720),
+        ("faculty", "thesis", 2160),
+    ] {
+        let terms = resolve_terms(&mut tx, patron_category, book_type, &config).await.unwrap();
+        assert_eq!(terms.loan_period_hours, hours, "{} / {}", patron_category, book_type);
+    }
+    tx.rollback().await.unwrap();
+}
+
+#[actix_rt::test]
+async fn test_invalid_policy_rejected() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, admin) = create_user_with_token(&pool, &config, Role::Admin).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/policies")
+        .insert_header(bearer(&admin))
+        .set_json(serde_json::json!({
+            "patron_category": "alumni",
+            "book_type": "*",
+            "loan_period_hours": 0,
+            "max_renewals": 1,
+            "fine_rate_cents": 10,
+            "max_loans": 5
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert!(body["details"]["patron_category"].is_array());
+    assert!(body["details"]["loan_period_hours"].is_array());
+}
@@ -0,0 +1,108 @@
+use crate::models::book::Book;
+use crate::models::search::SearchQuery;
+use crate::search_index::tokenizer::query_tokens;
+use crate::search_index::SearchIndex;
+
+fn book(id: &str, title: &str, author: &str, description: Option<&str>) -> Book {
+    let now = chrono::Utc::now().naive_local();
+    Book {
+        id: id.to_string(),
+        title: title.to_string(),
+        title_pinyin: String::new(),
+        title_initials: String::new(),
+        subtitle: None,
+        original_title: None,
+        author: author.to_string(),
+        author_pinyin: String::new(),
+        author_initials: String::new(),
+        isbn: "9787536692930".to_string(),
+        description: description.map(str::to_string),
+        r#type: "general".to_string(),
+        publisher: None,
+        publication_year: None,
+        edition: None,
+        language: None,
+        pages: None,
+        call_number: None,
+        call_number_sort: None,
+        work_id: None,
+        series_id: None,
+        series_volume: None,
+        created_at: now,
+        updated_at: now,
+    }
+}
+
+fn catalog() -> SearchIndex {
+    let index = SearchIndex::in_memory().unwrap();
+    index
+        .rebuild(&[
+            book("b1", "三体", "刘慈欣", Some("地球文明与三体文明的第一次接触")),
+            book("b2", "三体Ⅱ：黑暗森林", "刘慈欣", Some("宇宙就是一座黑暗森林")),
+            book("b3", "The Rust Programming Language", "Steve Klabnik", Some("An introduction to Rust")),
+            book("b4", "Programming Pearls", "Jon Bentley", Some("Classic essays on programming")),
+            book("b5", "Data Structures", "Rust Cohle", None),
+        ])
+        .unwrap();
+    index
+}
+
+fn ids(index: &SearchIndex, q: &str) -> Vec<String> {
+    let search = SearchQuery::parse(q).unwrap();
+    index.search(&search, 10).unwrap().into_iter().map(|(id, _)| id).collect()
+}
+
+#[test]
+fn test_query_tokens_use_bigrams_for_cjk_and_words_for_latin() {
+    let tokens: Vec<String> = query_tokens("黑暗森林 Rust-2024").into_iter().map(|(_, text)| text).collect();
+    assert_eq!(tokens, vec!["黑暗", "暗森", "森林", "rust", "2024"]);
+    let tokens: Vec<String> = query_tokens("书").into_iter().map(|(_, text)| text).collect();
+    assert_eq!(tokens, vec!["书"]);
+}
+
+#[test]
+fn test_cjk_search_matches_words_inside_titles() {
+    let index = catalog();
+    let mut found = ids(&index, "三体");
+    found.sort();
+    assert_eq!(found, vec!["b1", "b2"]);
+    assert_eq!(ids(&index, "黑暗森林"), vec!["b2"]);
+    // 单字也能检索
+    assert_eq!(ids(&index, "宙").len(), 1);
+    // 不相邻的字不算命中
+    assert!(ids(&index, "三森").is_empty());
+}
+
+#[test]
+fn test_fuzzy_prefix_and_phrase() {
+    let index = catalog();
+    // 拼写错误一处仍能命中
+    let mut found = ids(&index, "progamming");
+    found.sort();
+    assert_eq!(found, vec!["b3", "b4"]);
+    assert!(ids(&index, "prog*").contains(&"b4".to_string()));
+    assert_eq!(ids(&index, "\"rust programming\""), vec!["b3"]);
+    // 短词不做模糊匹配
+    assert!(ids(&index, "rst").is_empty());
+}
+
+#[test]
+fn test_title_matches_rank_above_author_matches() {
+    let index = catalog();
+    let found = ids(&index, "rust");
+    assert_eq!(found.first().map(String::as_str), Some("b3"));
+    assert!(found.contains(&"b5".to_string()));
+}
+
+#[test]
+fn test_upsert_and_delete_keep_index_in_sync() {
+    let index = catalog();
+    index.upsert(&book("b5", "算法导论", "Thomas Cormen", None)).unwrap();
+    assert_eq!(ids(&index, "算法"), vec!["b5"]);
+    assert!(!ids(&index, "rust").contains(&"b5".to_string()));
+    assert_eq!(index.num_docs(), 5);
+
+    index.delete("b5").unwrap();
+    assert!(ids(&index, "算法").is_empty());
+    assert_eq!(index.num_docs(), 4);
+}
@@ -0,0 +1,141 @@
+use actix_web::{test, web, App};
+use uuid::Uuid;
+use crate::{
+    config::database::init_test_pool,
+    handlers::book_handler::{create_book, list_books},
+    tests::book_test::random_isbn,
+};
+
+mod parsing {
+    use crate::models::search::{SearchError, SearchQuery, SearchTerm};
+
+    #[test]
+    fn test_parse_terms() {
+        let search = SearchQuery::parse(r#"三体 "Dark  Forest" data* +-() "x""#).unwrap();
+        assert_eq!(
+            search.terms(),
+            &[
+                SearchTerm::Word("三体".to_string()),
+                SearchTerm::Phrase("Dark Forest".to_string()),
+                SearchTerm::Prefix("data".to_string()),
+                SearchTerm::Word("x".to_string()),
+            ]
+        );
+        assert_eq!(search.boolean_mode(), r#"+三体 +"Dark Forest" +data* +x"#);
+
+        // 运算符被当作分隔符，未闭合的引号到末尾为止
+        assert_eq!(
+            SearchQuery::parse("a-b*").unwrap().terms(),
+            &[SearchTerm::Word("a".to_string()), SearchTerm::Prefix("b".to_string())]
+        );
+        assert_eq!(
+            SearchQuery::parse("\"open").unwrap().terms(),
+            &[SearchTerm::Word("open".to_string())]
+        );
+        assert_eq!(SearchQuery::parse(" +- ()").unwrap_err(), SearchError::Empty);
+        assert_eq!(SearchQuery::parse("a b c d e f g h i j k").unwrap_err(), SearchError::TooManyTerms);
+    }
+
+    #[test]
+    fn test_highlight() {
+        let search = SearchQuery::parse("rust data*").unwrap();
+        assert_eq!(
+            search.highlight("Programming Rust & Databases", 100).unwrap(),
+            "Programming <mark>Rust</mark> &amp; <mark>Databases</mark>"
+        );
+        assert_eq!(search.highlight("nothing here", 100), None);
+
+        let search = SearchQuery::parse("三体").unwrap();
+        assert_eq!(search.highlight("三体II：黑暗森林", 100).unwrap(), "<mark>三体</mark>II：黑暗森林");
+    }
+
+    #[test]
+    fn test_snippet_around_first_match() {
+        let search = SearchQuery::parse("rust").unwrap();
+        let text = format!("{} rust {}", "a".repeat(100), "b".repeat(100));
+        let snippet = search.highlight(&text, 40).unwrap();
+        assert!(snippet.starts_with('…'));
+        assert!(snippet.ends_with('…'));
+        assert!(snippet.contains("<mark>rust</mark>"));
+        assert_eq!(snippet.replace("<mark>", "").replace("</mark>", "").chars().count(), 42);
+    }
+}
+
+// ngram 分词会跳过含停用词（如 a、i）的词元，随机词只用不会组成停用词的字母
+pub fn random_term() -> String {
+    let letters: Vec<char> = "bcdfghjkmp".chars().collect();
+    let seed = Uuid::new_v4().as_u128();
+    let suffix: String = (0..10).map(|i| letters[((seed >> (i * 4)) % 10) as usize]).collect();
+    format!("zx{}", suffix)
+}
+
+#[actix_rt::test]
+async fn test_full_text_search() {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .route("/api/books", web::post().to(create_book))
+            .route("/api/books", web::get().to(list_books))
+    ).await;
+
+    let term = random_term();
+    let mut ids = Vec::new();
+    for (title, description) in [
+        (format!("{} Handbook", term), "General notes".to_string()),
+        ("Other Title".to_string(), format!("An appendix that mentions {} briefly", term)),
+    ] {
+        let resp = test::TestRequest::post()
+            .uri("/api/books")
+            .set_json(serde_json::json!({
+                "title": title,
+                "author": "Test Author",
+                "isbn": random_isbn(),
+                "description": description,
+                "type": "general",
+                "quantity": 1
+            }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+        let book: serde_json::Value = test::read_body_json(resp).await;
+        ids.push(book["id"].as_str().unwrap().to_string());
+    }
+
+    // 题名命中的排在简介命中之前
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?q={}", term))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 2);
+    assert_eq!(books["data"][0]["id"], ids[0].as_str());
+    assert!(books["data"][0]["score"].as_f64().unwrap() > books["data"][1]["score"].as_f64().unwrap());
+    assert_eq!(books["data"][0]["highlights"]["title"], format!("<mark>{}</mark> Handbook", term));
+    assert!(books["data"][1]["highlights"]["title"].is_null());
+    assert!(books["data"][1]["highlights"]["description"]
+        .as_str()
+        .unwrap()
+        .contains(&format!("<mark>{}</mark>", term)));
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?q=%22{}%20Handbook%22", term))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 1);
+    assert_eq!(books["data"][0]["id"], ids[0].as_str());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?q={}*", &term[..8]))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 2);
+
+    for uri in ["/api/books?q=%2B-", "/api/books?sort=relevance"] {
+        let resp = test::TestRequest::get().uri(uri).send_request(&app).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
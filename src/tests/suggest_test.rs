@@ -0,0 +1,106 @@
+use std::time::Duration;
+
+use actix_web::{test, web, App};
+use crate::{
+    config::database::init_test_pool,
+    handlers::book_handler::create_book,
+    handlers::suggest_handler::{suggest, SuggestCache},
+    tests::{book_test::random_isbn, search_test::random_term},
+};
+
+mod index {
+    use crate::models::pinyin;
+    use crate::models::suggest::SuggestIndex;
+
+    fn catalog() -> SuggestIndex {
+        let mut index = SuggestIndex::default();
+        for (id, title, isbn, popularity) in [
+            ("b1", "三体", "9787536692930", 30),
+            ("b2", "三体Ⅱ：黑暗森林", "9787536693968", 20),
+            ("b3", "The Rust Programming Language", "9781718500440", 5),
+            ("b4", "Rust in Action", "9781617294556", 8),
+            ("b5", "The Rust Programming Language", "9781593278281", 2),
+        ] {
+            index.add_book(id, title, &pinyin::full(title), &pinyin::initials(title), isbn, popularity);
+        }
+        index.add_author("a1", "刘慈欣", 50);
+        index.add_author("a2", "Steve Klabnik", 5);
+        index.finish()
+    }
+
+    fn texts(suggestions: &[crate::models::suggest::Suggestion]) -> Vec<&str> {
+        suggestions.iter().map(|suggestion| suggestion.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_title_prefix_ranked_by_popularity() {
+        let index = catalog();
+        let suggestions = index.suggest("rust", 5);
+        assert_eq!(texts(&suggestions.titles), vec!["Rust in Action", "The Rust Programming Language"]);
+        // 同名的多个版本只保留热度最高的一个
+        assert_eq!(suggestions.titles[1].id, "b3");
+        assert_eq!(texts(&index.suggest("三", 5).titles), vec!["三体", "三体Ⅱ：黑暗森林"]);
+        assert_eq!(index.suggest("rust", 1).titles.len(), 1);
+    }
+
+    #[test]
+    fn test_pinyin_and_initials() {
+        let index = catalog();
+        assert_eq!(texts(&index.suggest("santi", 5).titles), vec!["三体", "三体Ⅱ：黑暗森林"]);
+        assert_eq!(texts(&index.suggest("San Ti", 5).titles), vec!["三体", "三体Ⅱ：黑暗森林"]);
+        assert_eq!(texts(&index.suggest("lcx", 5).authors), vec!["刘慈欣"]);
+        assert_eq!(texts(&index.suggest("klab", 5).authors), vec!["Steve Klabnik"]);
+    }
+
+    #[test]
+    fn test_isbn_prefix_ignores_hyphens() {
+        let index = catalog();
+        let suggestions = index.suggest("978-7-5366", 5);
+        assert_eq!(texts(&suggestions.isbns), vec!["9787536692930", "9787536693968"]);
+        assert!(suggestions.titles.is_empty());
+        assert!(index.suggest("rust", 5).isbns.is_empty());
+        assert!(index.suggest("   ", 5).titles.is_empty());
+    }
+}
+
+#[actix_rt::test]
+async fn test_suggest_endpoint() {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .app_data(web::Data::new(SuggestCache::new(Duration::from_secs(300))))
+            .route("/api/books", web::post().to(create_book))
+            .route("/api/books/suggest", web::get().to(suggest))
+    ).await;
+
+    let term = random_term();
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": format!("{} Handbook", term),
+            "author": format!("Author {}", term),
+            "isbn": random_isbn(),
+            "type": "general",
+            "quantity": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/suggest?q={}", &term[..8]))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let suggestions: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(suggestions["titles"][0]["id"], book["id"]);
+    assert_eq!(suggestions["titles"][0]["text"], format!("{} Handbook", term));
+    assert_eq!(suggestions["authors"][0]["text"], format!("Author {}", term));
+
+    for uri in ["/api/books/suggest?q=%20", "/api/books/suggest?q=rust&limit=0", "/api/books/suggest?q=rust&limit=21"] {
+        let resp = test::TestRequest::get().uri(uri).send_request(&app).await;
+        assert_eq!(resp.status(), 400);
+    }
+}
@@ -0,0 +1,233 @@
+use actix_web::{test, web, App, dev::Service, middleware::from_fn};
+use sqlx::MySqlPool;
+use crate::{
+    config::{database::init_test_pool, AppConfig},
+    handlers::book_handler::{get_book, list_books},
+    handlers::loan_handler::checkout,
+    handlers::transfer_handler::{cancel_transfer, create_transfer, receive_transfer, ship_transfer},
+    middleware::auth::{require_auth, require_librarian},
+    models::user::Role,
+    tests::helpers::{bearer, create_user_with_token, default_branch, insert_book, insert_branch},
+};
+
+async fn setup_test_app() -> (impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, MySqlPool, AppConfig) {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config.clone()))
+            .service(
+                web::scope("/api")
+                    .wrap(from_fn(require_auth))
+                    .route("/books", web::get().to(list_books))
+                    .route("/books/{id}", web::get().to(get_book))
+                    .route("/loans", web::post().to(checkout))
+                    .route("/transfers", web::post().to(create_transfer))
+                    .route("/transfers/{id}/ship", web::post().to(ship_transfer).wrap(from_fn(require_librarian)))
+                    .route("/transfers/{id}/receive", web::post().to(receive_transfer).wrap(from_fn(require_librarian)))
+                    .route("/transfers/{id}/cancel", web::post().to(cancel_transfer)),
+            )
+    ).await;
+    (app, pool, config)
+}
+
+#[actix_rt::test]
+async fn test_transfer_ships_copy_for_pickup_at_other_branch() {
+    let (app, pool, config) = setup_test_app().await;
+    let (member_id, member) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, librarian) = create_user_with_token(&pool, &config, Role::Librarian).await;
+    let book_id = insert_book(&pool, 1).await;
+    let main_branch = default_branch(&pool).await;
+    let east_branch = insert_branch(&pool).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/transfers")
+        .insert_header(bearer(&member))
+        .set_json(serde_json::json!({ "book_id": book_id, "to_branch_id": east_branch }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let transfer: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(transfer["status"], "requested");
+    assert_eq!(transfer["user_id"], member_id.as_str());
+    let transfer_id = transfer["id"].as_str().unwrap().to_string();
+
+    // 只有图书管理员可以发出调拨
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/transfers/{}/ship", transfer_id))
+        .insert_header(bearer(&member))
+        .set_json(serde_json::json!({}))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 403);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/transfers/{}/ship", transfer_id))
+        .insert_header(bearer(&librarian))
+        .set_json(serde_json::json!({}))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let transfer: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(transfer["status"], "in_transit");
+    assert_eq!(transfer["from_branch_id"], main_branch.as_str());
+
+    // 运送途中不可借，也不能再取消
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}", book_id))
+        .insert_header(bearer(&member))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["quantity"], 1);
+    assert_eq!(book["available"], 0);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/transfers/{}/cancel", transfer_id))
+        .insert_header(bearer(&member))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/transfers/{}/receive", transfer_id))
+        .insert_header(bearer(&librarian))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    // 到馆后按分馆统计，且副本已为申请的读者留好
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}", book_id))
+        .insert_header(bearer(&member))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["available"], 1);
+    let branches = book["branches"].as_array().unwrap();
+    assert_eq!(branches.len(), 1);
+    assert_eq!(branches[0]["branch_id"], east_branch.as_str());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?branch_id={}&page_size=100", east_branch))
+        .insert_header(bearer(&member))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert!(books["data"].as_array().unwrap().iter().any(|book| book["id"] == book_id.as_str()));
+
+    let (_, other) = create_user_with_token(&pool, &config, Role::Member).await;
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&other))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&member))
+        .set_json(serde_json::json!({ "book_id": book_id, "branch_id": east_branch }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+}
+
+#[actix_rt::test]
+async fn test_requested_transfer_can_be_cancelled_by_requester_only() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, member) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, stranger) = create_user_with_token(&pool, &config, Role::Member).await;
+    let book_id = insert_book(&pool, 1).await;
+    let branch_id = insert_branch(&pool).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/transfers")
+        .insert_header(bearer(&member))
+        .set_json(serde_json::json!({ "book_id": book_id, "to_branch_id": branch_id }))
+        .send_request(&app)
+        .await;
+    let transfer: serde_json::Value = test::read_body_json(resp).await;
+    let transfer_id = transfer["id"].as_str().unwrap();
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/transfers/{}/cancel", transfer_id))
+        .insert_header(bearer(&stranger))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 403);
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/transfers/{}/cancel", transfer_id))
+        .insert_header(bearer(&member))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 204);
+
+    // 目标分馆已有在架副本时无需调拨
+    let resp = test::TestRequest::post()
+        .uri("/api/transfers")
+        .insert_header(bearer(&member))
+        .set_json(serde_json::json!({ "book_id": book_id, "to_branch_id": default_branch(&pool).await }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+}
+
+#[actix_rt::test]
+async fn test_transferred_copy_reserved_for_requester() {
+    let (app, pool, config) = setup_test_app().await;
+    let (_, member) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, other) = create_user_with_token(&pool, &config, Role::Member).await;
+    let (_, librarian) = create_user_with_token(&pool, &config, Role::Librarian).await;
+    let book_id = insert_book(&pool, 2).await;
+    let east_branch = insert_branch(&pool).await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/transfers")
+        .insert_header(bearer(&member))
+        .set_json(serde_json::json!({ "book_id": book_id, "to_branch_id": east_branch }))
+        .send_request(&app)
+        .await;
+    let transfer: serde_json::Value = test::read_body_json(resp).await;
+    let transfer_id = transfer["id"].as_str().unwrap().to_string();
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/transfers/{}/ship", transfer_id))
+        .insert_header(bearer(&librarian))
+        .set_json(serde_json::json!({}))
+        .send_request(&app)
+        .await;
+    let transfer: serde_json::Value = test::read_body_json(resp).await;
+    let shipped_item = transfer["item_id"].as_str().unwrap().to_string();
+
+    let resp = test::TestRequest::post()
+        .uri(&format!("/api/transfers/{}/receive", transfer_id))
+        .insert_header(bearer(&librarian))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+
+    // 另一位读者不指定分馆借书时，只能借到总馆剩下的那一册
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&other))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+    assert_ne!(loan["item_id"], shipped_item.as_str());
+
+    let resp = test::TestRequest::post()
+        .uri("/api/loans")
+        .insert_header(bearer(&member))
+        .set_json(serde_json::json!({ "book_id": book_id }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let loan: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(loan["item_id"], shipped_item.as_str());
+}
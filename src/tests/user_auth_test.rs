@@ -1,16 +1,18 @@
-use actix_web::{test, web, App, HttpResponse};
+use actix_web::{test, web, App, dev::Service};
 use sqlx::MySqlPool;
 use crate::{
-    models::user::{User, CreateUser, LoginUser},
+    models::user::{CreateUser, LoginUser},
     handlers::user_handler::{register, login, logout},
-    config::database::init_pool,
+    config::{database::init_test_pool, AppConfig},
 };
 
-async fn setup_test_app() -> (App<()>, MySqlPool) {
-    let pool = init_pool().await;
+async fn setup_test_app() -> (impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>, MySqlPool) {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    let config = AppConfig::load().expect("Failed to load config");
     let app = test::init_service(
         App::new()
             .app_data(web::Data::new(pool.clone()))
+            .app_data(web::Data::new(config))
             .service(web::resource("/users/register").route(web::post().to(register)))
             .service(web::resource("/users/login").route(web::post().to(login)))
             .service(web::resource("/users/logout").route(web::post().to(logout)))
@@ -121,4 +123,34 @@ async fn test_user_logout() {
     
     let body: serde_json::Value = test::read_body_json(logout_resp).await;
     assert!(body["message"].is_string());
-} 
\ No newline at end of file
+}
+
+#[actix_rt::test]
+async fn test_duplicate_register_conflict() {
+    let (app, _pool) = setup_test_app().await;
+
+    let user_data = CreateUser {
+        username: format!("testuser_{}", uuid::Uuid::new_v4()),
+        password: "testpass123".to_string(),
+        email: format!("test_{}@example.com", uuid::Uuid::new_v4()),
+    };
+
+    let resp = test::TestRequest::post()
+        .uri("/users/register")
+        .set_json(&user_data)
+        .send_request(&app)
+        .await;
+    assert!(resp.status().is_success());
+
+    // 重复的用户名返回 409
+    let resp = test::TestRequest::post()
+        .uri("/users/register")
+        .set_json(&user_data)
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+
+    let body: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(body["code"], "CONFLICT");
+    assert!(body["message"].is_string());
+}
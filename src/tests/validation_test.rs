@@ -0,0 +1,96 @@
+use validator::Validate;
+use crate::{
+    models::book::CreateBook,
+    models::user::CreateUser,
+    utils::password::validate_password_strength,
+};
+
+fn valid_book() -> CreateBook {
+    CreateBook {
+        title: "三体".to_string(),
+        subtitle: None,
+        original_title: None,
+        author: "刘慈欣".to_string(),
+        authors: None,
+        category_ids: None,
+        work_id: None,
+        series_id: None,
+        series_volume: None,
+        isbn: "9787536692930".to_string(),
+        description: None,
+        r#type: "general".to_string(),
+        publisher: None,
+        publication_year: None,
+        edition: None,
+        language: None,
+        pages: None,
+        call_number: None,
+        quantity: 3,
+        branch_id: None,
+    }
+}
+
+#[test]
+fn test_password_strength_policy() {
+    assert!(validate_password_strength("testpass123").is_ok());
+    assert!(validate_password_strength("a1").is_err());
+    assert!(validate_password_strength("onlyletters").is_err());
+    assert!(validate_password_strength("1234567890").is_err());
+    assert!(validate_password_strength(&format!("a1{}", "x".repeat(80))).is_err());
+}
+
+#[test]
+fn test_create_book_validation() {
+    assert!(valid_book().validate().is_ok());
+
+    let book = CreateBook { quantity: -1, ..valid_book() };
+    assert!(book.validate().unwrap_err().field_errors().contains_key("quantity"));
+
+    let book = CreateBook { title: "   ".to_string(), ..valid_book() };
+    assert!(book.validate().unwrap_err().field_errors().contains_key("title"));
+
+    let book = CreateBook { isbn: "97875366929301234".to_string(), ..valid_book() };
+    assert!(book.validate().unwrap_err().field_errors().contains_key("isbn"));
+
+    let book = CreateBook { r#type: "comic".to_string(), ..valid_book() };
+    assert!(book.validate().is_err());
+
+    let book = CreateBook { call_number: Some("TP311.13/123".to_string()), ..valid_book() };
+    assert!(book.validate().is_ok());
+
+    let book = CreateBook {
+        publisher: Some("重庆出版社".to_string()),
+        publication_year: Some(2008),
+        language: Some("zh".to_string()),
+        pages: Some(302),
+        ..valid_book()
+    };
+    assert!(book.validate().is_ok());
+
+    let book = CreateBook { language: Some("Chinese".to_string()), publication_year: Some(20080), ..valid_book() };
+    let errors = book.validate().unwrap_err();
+    assert!(errors.field_errors().contains_key("language"));
+    assert!(errors.field_errors().contains_key("publication_year"));
+
+    let book = CreateBook { call_number: Some("TP#311".to_string()), ..valid_book() };
+    assert!(book.validate().unwrap_err().field_errors().contains_key("call_number"));
+}
+
+#[test]
+fn test_create_user_validation() {
+    let user = CreateUser {
+        username: "reader_01".to_string(),
+        email: "reader@example.com".to_string(),
+        password: "testpass123".to_string(),
+    };
+    assert!(user.validate().is_ok());
+
+    let user = CreateUser {
+        username: "reader_01".to_string(),
+        email: "not-an-email".to_string(),
+        password: "x".to_string(),
+    };
+    let errors = user.validate().unwrap_err();
+    assert!(errors.field_errors().contains_key("email"));
+    assert!(errors.field_errors().contains_key("password"));
+}
@@ -0,0 +1,261 @@
+use actix_web::{test, web, App, dev::Service};
+use uuid::Uuid;
+use crate::{
+    config::database::init_test_pool,
+    handlers::book_handler::{create_book, get_book, list_books, update_book},
+    handlers::series_handler::{create_series, delete_series, list_series_books},
+    handlers::work_handler::{create_work, delete_work, list_work_books},
+    tests::book_test::random_isbn,
+};
+
+async fn setup_test_app() -> impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error> {
+    let pool = init_test_pool().await.expect("Failed to create test database pool");
+    test::init_service(
+        App::new()
+            .app_data(web::Data::new(pool))
+            .route("/api/works", web::post().to(create_work))
+            .route("/api/works/{id}", web::delete().to(delete_work))
+            .route("/api/works/{id}/books", web::get().to(list_work_books))
+            .route("/api/series", web::post().to(create_series))
+            .route("/api/series/{id}", web::delete().to(delete_series))
+            .route("/api/series/{id}/books", web::get().to(list_series_books))
+            .route("/api/books", web::post().to(create_book))
+            .route("/api/books", web::get().to(list_books))
+            .route("/api/books/{id}", web::get().to(get_book))
+            .route("/api/books/{id}", web::put().to(update_book))
+    ).await
+}
+
+async fn new_book(
+    app: &impl Service<actix_http::Request, Response = actix_web::dev::ServiceResponse, Error = actix_web::Error>,
+    title: &str,
+    extra: serde_json::Value,
+) -> String {
+    let mut body = serde_json::json!({
+        "title": title,
+        "author": "Test Author",
+        "isbn": random_isbn(),
+        "type": "general",
+        "quantity": 1
+    });
+    body.as_object_mut().unwrap().extend(extra.as_object().unwrap().clone());
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(body)
+        .send_request(app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    book["id"].as_str().unwrap().to_string()
+}
+
+#[actix_rt::test]
+async fn test_work_editions_and_collapse() {
+    let app = setup_test_app().await;
+    let title = format!("Work-{}", Uuid::new_v4());
+
+    let resp = test::TestRequest::post()
+        .uri("/api/works")
+        .set_json(serde_json::json!({ "title": title }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let work: serde_json::Value = test::read_body_json(resp).await;
+    let work_id = work["id"].as_str().unwrap().to_string();
+
+    let first = new_book(&app, &title, serde_json::json!({ "work_id": work_id, "publication_year": 2008 })).await;
+    let second = new_book(&app, &title, serde_json::json!({ "work_id": work_id, "publication_year": 2016, "edition": "第2版" })).await;
+    let standalone = new_book(&app, &title, serde_json::json!({})).await;
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}", first))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["work_id"], work_id.as_str());
+    assert_eq!(book["other_editions"].as_array().unwrap().len(), 1);
+    assert_eq!(book["other_editions"][0]["id"], second.as_str());
+    assert!(book["next_in_series"].is_null());
+
+    // 折叠后同一作品只保留出版年最新的版本，未归入作品的图书不受影响
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?title={}&collapse=true", title))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 2);
+    let ids: Vec<&str> = books["data"].as_array().unwrap().iter().map(|book| book["id"].as_str().unwrap()).collect();
+    assert!(ids.contains(&second.as_str()));
+    assert!(ids.contains(&standalone.as_str()));
+
+    // 折叠时代表版本也要满足其余过滤条件
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books?title={}&year_to=2010&collapse=true", title))
+        .send_request(&app)
+        .await;
+    let books: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(books["total"], 1);
+    assert_eq!(books["data"][0]["id"], first.as_str());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/works/{}/books", work_id))
+        .send_request(&app)
+        .await;
+    let editions: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(editions["data"][0]["id"], second.as_str());
+
+    // 仍有版本关联的作品不能删除
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/works/{}", work_id))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 409);
+}
+
+#[actix_rt::test]
+async fn test_series_volumes() {
+    let app = setup_test_app().await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/series")
+        .set_json(serde_json::json!({ "name": format!("Series {}", Uuid::new_v4()) }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let series: serde_json::Value = test::read_body_json(resp).await;
+    let series_id = series["id"].as_str().unwrap().to_string();
+
+    let third = new_book(&app, "Volume 3", serde_json::json!({ "series_id": series_id, "series_volume": 3 })).await;
+    let first = new_book(&app, "Volume 1", serde_json::json!({ "series_id": series_id, "series_volume": 1 })).await;
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}", first))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["next_in_series"]["id"], third.as_str());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}", third))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert!(book["next_in_series"].is_null());
+
+    // 补齐第 2 卷后成为第 1 卷的下一卷
+    let second = new_book(&app, "Volume 2", serde_json::json!({ "series_id": series_id, "series_volume": 2 })).await;
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/books/{}", first))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["next_in_series"]["id"], second.as_str());
+
+    let resp = test::TestRequest::get()
+        .uri(&format!("/api/series/{}/books", series_id))
+        .send_request(&app)
+        .await;
+    let volumes: serde_json::Value = test::read_body_json(resp).await;
+    let ids: Vec<&str> = volumes["data"].as_array().unwrap().iter().map(|book| book["id"].as_str().unwrap()).collect();
+    assert_eq!(ids, [first.as_str(), second.as_str(), third.as_str()]);
+
+    // 卷次必须属于某套丛书，丛书必须存在
+    let resp = test::TestRequest::post()
+        .uri("/api/books")
+        .set_json(serde_json::json!({
+            "title": "Orphan Volume",
+            "author": "Test Author",
+            "isbn": random_isbn(),
+            "type": "general",
+            "series_volume": 1,
+            "quantity": 1
+        }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", first))
+        .set_json(serde_json::json!({ "series_id": Uuid::new_v4().to_string() }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[actix_rt::test]
+async fn test_detach_work_and_series() {
+    let app = setup_test_app().await;
+
+    let resp = test::TestRequest::post()
+        .uri("/api/works")
+        .set_json(serde_json::json!({ "title": format!("Work {}", Uuid::new_v4()) }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 201);
+    let work: serde_json::Value = test::read_body_json(resp).await;
+    let work_id = work["id"].as_str().unwrap().to_string();
+
+    let mut series_ids = Vec::new();
+    for _ in 0..2 {
+        let resp = test::TestRequest::post()
+            .uri("/api/series")
+            .set_json(serde_json::json!({ "name": format!("Series {}", Uuid::new_v4()) }))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 201);
+        let series: serde_json::Value = test::read_body_json(resp).await;
+        series_ids.push(series["id"].as_str().unwrap().to_string());
+    }
+
+    let book_id = new_book(
+        &app,
+        "Detached Volume",
+        serde_json::json!({ "work_id": work_id, "series_id": series_ids[0], "series_volume": 2 }),
+    )
+    .await;
+
+    // 换到另一套丛书而不指定卷次时，原卷次不再保留
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({ "series_id": series_ids[1] }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["series_id"], series_ids[1].as_str());
+    assert!(book["series_volume"].is_null());
+    assert_eq!(book["work_id"], work_id.as_str());
+
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({ "series_volume": 5 }))
+        .send_request(&app)
+        .await;
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(book["series_volume"], 5);
+
+    // 传 null 移出作品和丛书
+    let resp = test::TestRequest::put()
+        .uri(&format!("/api/books/{}", book_id))
+        .set_json(serde_json::json!({ "work_id": null, "series_id": null }))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 200);
+    let book: serde_json::Value = test::read_body_json(resp).await;
+    assert!(book["work_id"].is_null());
+    assert!(book["series_id"].is_null());
+    assert!(book["series_volume"].is_null());
+
+    // 不再有图书引用后作品和丛书可以删除
+    let resp = test::TestRequest::delete()
+        .uri(&format!("/api/works/{}", work_id))
+        .send_request(&app)
+        .await;
+    assert_eq!(resp.status(), 204);
+    for series_id in &series_ids {
+        let resp = test::TestRequest::delete()
+            .uri(&format!("/api/series/{}", series_id))
+            .send_request(&app)
+            .await;
+        assert_eq!(resp.status(), 204);
+    }
+}
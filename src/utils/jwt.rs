@@ -1,6 +1,9 @@
 use jsonwebtoken::{encode, decode, Header, EncodingKey, DecodingKey, Validation, errors::Error as JwtError};
 use serde::{Serialize, Deserialize};
-use chrono::{Utc, Duration};
+use chrono::{DateTime, Utc, Duration};
+
+use crate::config::AppConfig;
+use crate::error::{AppError, AppResult};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
@@ -8,29 +11,34 @@ pub struct Claims {
     pub exp: usize,
 }
 
-pub fn create_token(user_id: &str) -> Result<String, JwtError> {
-    let expiration = Utc::now()
-        .checked_add_signed(Duration::hours(24))
-        .expect("valid timestamp")
-        .timestamp() as usize;
+/// 签发令牌，返回令牌及其过期时间
+pub fn create_token(user_id: &str, config: &AppConfig) -> AppResult<(String, DateTime<Utc>)> {
+    // 过期时长配置得过大时会超出时间范围，按内部错误返回而不是 panic
+    let expires_at = Duration::try_hours(config.jwt_expiry_hours)
+        .and_then(|expiry| Utc::now().checked_add_signed(expiry))
+        .ok_or_else(|| {
+            AppError::Internal(format!("JWT expiry of {} hours is out of range", config.jwt_expiry_hours))
+        })?;
 
     let claims = Claims {
         sub: user_id.to_string(),
-        exp: expiration,
+        exp: expires_at.timestamp() as usize,
     };
 
-    encode(
+    let token = encode(
         &Header::default(),
         &claims,
-        &EncodingKey::from_secret(b"your-secret-key"),
-    )
+        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
+    )?;
+
+    Ok((token, expires_at))
 }
 
-pub fn verify_token(token: &str) -> Result<Claims, JwtError> {
+pub fn verify_token(token: &str, config: &AppConfig) -> Result<Claims, JwtError> {
     decode::<Claims>(
         token,
-        &DecodingKey::from_secret(b"your-secret-key"),
+        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
         &Validation::default(),
     )
     .map(|data| data.claims)
-} 
\ No newline at end of file
+}
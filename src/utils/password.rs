@@ -0,0 +1,28 @@
+use validator::ValidationError;
+
+pub const MIN_PASSWORD_LENGTH: usize = 8;
+// bcrypt 只使用前 72 个字节
+pub const MAX_PASSWORD_BYTES: usize = 72;
+
+/// 密码强度策略：8 位以上，不超过 72 字节，且同时包含字母和数字
+pub fn validate_password_strength(password: &str) -> Result<(), ValidationError> {
+    if password.chars().count() < MIN_PASSWORD_LENGTH {
+        return Err(policy_error("password_too_short", "Password must be at least 8 characters"));
+    }
+    if password.len() > MAX_PASSWORD_BYTES {
+        return Err(policy_error("password_too_long", "Password must be at most 72 bytes"));
+    }
+    if !password.chars().any(|c| c.is_ascii_alphabetic()) || !password.chars().any(|c| c.is_ascii_digit()) {
+        return Err(policy_error(
+            "password_too_weak",
+            "Password must contain both letters and digits",
+        ));
+    }
+    Ok(())
+}
+
+fn policy_error(code: &'static str, message: &'static str) -> ValidationError {
+    let mut error = ValidationError::new(code);
+    error.message = Some(message.into());
+    error
+}